@@ -0,0 +1,63 @@
+//! Benchmark of the loading of large JSON vocabularies, to compare the serial and parallel
+//! (`parallel-vocab` feature) construction of the reverse mappings:
+//!
+//! ```text
+//! cargo bench --bench vocab_loading
+//! cargo bench --bench vocab_loading --features parallel-vocab
+//! ```
+
+use rust_tokenizers::vocab::{Gpt2Vocab, M2M100Vocab, Vocab};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const VOCAB_SIZE: i64 = 250_000;
+const ITERATIONS: usize = 10;
+
+fn write_json_vocab(values: &HashMap<String, i64>) -> anyhow::Result<tempfile::TempPath> {
+    let mut vocab_file = tempfile::NamedTempFile::new()?;
+    serde_json::to_writer(&mut vocab_file, values)?;
+    Ok(vocab_file.into_temp_path())
+}
+
+fn median_load_time<V: Vocab>(path: &str) -> anyhow::Result<Duration> {
+    let mut durations = Vec::with_capacity(ITERATIONS);
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let vocab = V::from_file(path)?;
+        durations.push(start.elapsed());
+        assert!(vocab.len() as i64 >= VOCAB_SIZE);
+    }
+    durations.sort_unstable();
+    Ok(durations[ITERATIONS / 2])
+}
+
+fn main() -> anyhow::Result<()> {
+    let gpt2_values: HashMap<String, i64> = (0..VOCAB_SIZE)
+        .map(|index| (format!("token_{}", index), index))
+        .chain(std::iter::once(("<|endoftext|>".to_string(), VOCAB_SIZE)))
+        .collect();
+    let m2m100_values: HashMap<String, i64> = ["<s>", "<pad>", "</s>", "<unk>"]
+        .iter()
+        .map(|token| token.to_string())
+        .chain((4..VOCAB_SIZE).map(|index| format!("token_{}", index)))
+        .zip(0..)
+        .collect();
+    let gpt2_path = write_json_vocab(&gpt2_values)?;
+    let m2m100_path = write_json_vocab(&m2m100_values)?;
+
+    println!(
+        "parallel-vocab: {}, {} tokens, median of {} iterations",
+        cfg!(feature = "parallel-vocab"),
+        VOCAB_SIZE,
+        ITERATIONS
+    );
+    println!(
+        "Gpt2Vocab::from_file: {:?}",
+        median_load_time::<Gpt2Vocab>(gpt2_path.to_str().unwrap())?
+    );
+    println!(
+        "M2M100Vocab::from_file: {:?}",
+        median_load_time::<M2M100Vocab>(m2m100_path.to_str().unwrap())?
+    );
+    Ok(())
+}
@@ -28,6 +28,7 @@ use crate::{Mask, Offset, OffsetSize, Token, TokenRef};
 /// - splitting on special characters
 /// - text cleaning
 /// - NFKC decomposition
+/// - normalization rules of the SentencePiece model
 /// - (optional) lower casing
 /// - (optional) accent stripping
 /// - SentencePiece decomposition
@@ -170,6 +171,7 @@ impl Tokenizer<AlbertVocab> for AlbertTokenizer {
                 replace_string(token, "\'\'", "\"");
                 clean_text(token, true);
                 decompose_nfkc(token);
+                self.vocab.normalizer.normalize_token(token);
                 if self.lower_case {
                     lowercase(token);
                 }
@@ -194,6 +196,12 @@ impl Tokenizer<AlbertVocab> for AlbertTokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut output = self.model.tokenize_word(word);
+        self.post_process_pieces(&mut output);
+        output
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -213,13 +221,21 @@ impl Tokenizer<AlbertVocab> for AlbertTokenizer {
         let mut offsets: Vec<Option<Offset>> = vec![];
         let mut original_offsets: Vec<Vec<OffsetSize>> = vec![];
         let mut mask: Vec<Mask> = vec![];
+        let cls_id = self
+            .vocab
+            .cls_id()
+            .unwrap_or_else(|| self.vocab.token_to_id(AlbertVocab::cls_value()));
+        let sep_id = self
+            .vocab
+            .sep_id()
+            .unwrap_or_else(|| self.vocab.token_to_id(AlbertVocab::sep_value()));
         special_tokens_mask.push(1);
         special_tokens_mask.extend(vec![0; tokens_ids_with_offsets_1.ids.len()]);
         special_tokens_mask.push(1);
         token_segment_ids.extend(vec![0; tokens_ids_with_offsets_1.ids.len() + 2]);
-        output.push(self.vocab.token_to_id(AlbertVocab::cls_value()));
+        output.push(cls_id);
         output.extend(tokens_ids_with_offsets_1.ids);
-        output.push(self.vocab.token_to_id(AlbertVocab::sep_value()));
+        output.push(sep_id);
         offsets.push(None);
         offsets.extend(tokens_ids_with_offsets_1.offsets);
         offsets.push(None);
@@ -235,7 +251,7 @@ impl Tokenizer<AlbertVocab> for AlbertTokenizer {
             special_tokens_mask.push(1);
             token_segment_ids.extend(vec![1; length + 1]);
             output.extend(tokens_ids_with_offsets_2_value.ids);
-            output.push(self.vocab.token_to_id(AlbertVocab::sep_value()));
+            output.push(sep_id);
             offsets.extend(tokens_ids_with_offsets_2_value.offsets);
             original_offsets.extend(tokens_ids_with_offsets_2_value.reference_offsets);
             offsets.push(None);
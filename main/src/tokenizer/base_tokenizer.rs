@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
+use crate::tokenizer::constants::initialize_character_tables;
 use crate::tokenizer::tokenization_utils::{clean_text, lowercase};
 use crate::tokenizer::tokenization_utils::{
     split_on_punct, split_on_special_tokens, strip_accents, tokenize_cjk_chars, truncate_sequences,
@@ -22,6 +23,7 @@ use crate::vocab::Vocab;
 use itertools::Itertools;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// # Truncation strategy variants
 /// Indicates if and how sequence pairs exceeding a given length should be truncated
@@ -36,6 +38,28 @@ pub enum TruncationStrategy {
     DoNotTruncate,
 }
 
+/// # Padding side variants
+/// Indicates on which side sequences shorter than the longest sequence of a batch should be padded
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum PaddingSide {
+    /// Insert the padding tokens before the sequence (e.g. for decoder inputs of seq2seq models)
+    Left,
+    /// Insert the padding tokens after the sequence
+    Right,
+}
+
+/// # Padded batch of token ids
+/// Output of the padding of a batch of sequences to a common length
+#[derive(Debug, PartialEq, Clone)]
+pub struct PaddedBatch {
+    /// Padded token IDs, all sequences having the length of the longest input sequence
+    pub token_ids: Vec<Vec<i64>>,
+
+    /// Attention mask flagging the tokens of the input sequences (1) and the padding tokens (0).
+    /// This has the same shape as token_ids.
+    pub attention_mask: Vec<Vec<i8>>,
+}
+
 /// Crate-wide primitive used to store offset positions
 pub type OffsetSize = u32;
 
@@ -365,6 +389,55 @@ impl Token {
         //not a real implementation of AsRef because we do something slightly different
         TokenRef::from(self)
     }
+
+    /// Returns the alignment between the (possibly normalized) token text and the original text,
+    /// as byte ranges. The alignment contains one entry per byte of the token text, holding the
+    /// `(begin, end)` byte range of the original character it was derived from. Characters inserted
+    /// or merged during normalization share the range of the original character they refer to.
+    /// This is built from the `reference_offsets`, which index characters of the original text.
+    ///
+    /// # Parameters
+    /// - original_text (`&str`): original text the token reference offsets point to
+    ///
+    /// # Returns
+    /// - `Vec<(usize, usize)>`: original byte range for each byte of the token text
+    ///
+    /// # Example
+    /// ```
+    /// use rust_tokenizers::Token;
+    /// let original_text = "Ünïcode";
+    /// let token = Token::new("unicode".to_string());
+    ///
+    /// let alignments = token.alignments(original_text);
+    /// ```
+    pub fn alignments(&self, original_text: &str) -> Vec<(usize, usize)> {
+        token_alignments(self, &char_byte_ranges(original_text))
+    }
+}
+
+/// Returns the `(begin, end)` byte range of each character of a text
+fn char_byte_ranges(text: &str) -> Vec<(usize, usize)> {
+    text.char_indices()
+        .map(|(begin, character)| (begin, begin + character.len_utf8()))
+        .collect()
+}
+
+/// Returns the original byte range for each byte of the token text (see `Token::alignments`),
+/// given the byte ranges of the characters of the original text. Reference offsets past the end
+/// of the original text are aligned to an empty range at its end.
+fn token_alignments(token: &Token, original_char_ranges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let text_end = original_char_ranges.last().map_or(0, |(_, end)| *end);
+    let mut alignments = Vec::with_capacity(token.text.len());
+    for (character, reference_offset) in token.text.chars().zip(token.reference_offsets.iter()) {
+        let range = original_char_ranges
+            .get(*reference_offset as usize)
+            .copied()
+            .unwrap_or((text_end, text_end));
+        for _ in 0..character.len_utf8() {
+            alignments.push(range);
+        }
+    }
+    alignments
 }
 
 /// # Tokenized Input, ready for processing in language models
@@ -440,6 +513,10 @@ pub struct TokensWithOffsets {
     /// original source are registered as None.
     pub reference_offsets: Vec<Vec<OffsetSize>>,
 
+    /// Offset information (as start and end byte positions) in relation to the original text, from the byte alignment of the
+    /// normalized tokens (see `Token::alignments`). Tokens that can not be related to the original source are registered as None.
+    pub byte_offsets: Vec<Option<Offset>>,
+
     /// Masks tokens providing information on the type of tokens. This vector has the same length as token_ids.
     pub masks: Vec<Mask>,
 }
@@ -521,6 +598,7 @@ pub trait Tokenizer<T: Vocab> {
                 tokens: vec![],
                 offsets: vec![],
                 reference_offsets: vec![],
+                byte_offsets: vec![],
                 masks: vec![],
             };
         }
@@ -528,13 +606,23 @@ pub trait Tokenizer<T: Vocab> {
             (0..text.as_ref().chars().count() as OffsetSize).collect::<Vec<OffsetSize>>();
         let initial_token: TokenRef<'_> = TokenRef::new(text.as_ref(), &initial_offsets);
         let tokens = self.tokenize_to_tokens(initial_token);
+        let original_char_ranges = char_byte_ranges(text.as_ref());
         let length = tokens.len();
         let mut texts = Vec::with_capacity(length);
         let mut offsets = Vec::with_capacity(length);
         let mut original_positions = Vec::with_capacity(length);
+        let mut byte_offsets = Vec::with_capacity(length);
         let mut masks = Vec::with_capacity(length);
 
         for token in tokens {
+            let alignments = token_alignments(&token, &original_char_ranges);
+            byte_offsets.push(match (alignments.first(), alignments.last()) {
+                (Some((begin, _)), Some((_, end))) => Some(Offset {
+                    begin: *begin as OffsetSize,
+                    end: *end as OffsetSize,
+                }),
+                _ => None,
+            });
             texts.push(token.text);
             offsets.push(if !token.reference_offsets.is_empty() {
                 Some(Offset {
@@ -551,10 +639,198 @@ pub trait Tokenizer<T: Vocab> {
             tokens: texts,
             offsets,
             reference_offsets: original_positions,
+            byte_offsets,
             masks,
         }
     }
 
+    /// Count the number of tokens a string would be split into, for example to budget inputs by
+    /// token count. The tokens are counted with `count_tokens`: WordPiece and SentencePiece
+    /// tokenizers count the pieces during segmentation without building the tokens, other
+    /// tokenizers skip only the conversion of the tokens to the string and offset vectors of
+    /// `tokenize_with_offsets`.
+    ///
+    /// # Parameters
+    /// - text : text (string-like) to tokenize
+    ///
+    /// # Returns
+    /// `usize` number of tokens generated for the input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let text = "Hello, world!";
+    /// let num_tokens = tokenizer.count_tokens_str(text);
+    /// ```
+    fn count_tokens_str<S: AsRef<str>>(&self, text: S) -> usize {
+        if text.as_ref().trim().is_empty() {
+            return 0;
+        }
+        let initial_offsets =
+            (0..text.as_ref().chars().count() as OffsetSize).collect::<Vec<OffsetSize>>();
+        let initial_token: TokenRef<'_> = TokenRef::new(text.as_ref(), &initial_offsets);
+        self.count_tokens(initial_token)
+    }
+
+    /// Count the number of tokens `tokenize_to_tokens` splits a `TokenRef` into. This tokenizes the
+    /// input by default, and is overridden by the tokenizers able to count the pieces during
+    /// segmentation.
+    ///
+    /// # Parameters
+    /// - text (`TokenRef`): text to tokenize
+    ///
+    /// # Returns
+    /// `usize` number of tokens generated for the input text
+    fn count_tokens(&self, text: TokenRef) -> usize {
+        self.tokenize_to_tokens(text).len()
+    }
+
+    /// Compute the fertility of the tokenizer for a string, i.e. the average number of tokens
+    /// produced per whitespace-delimited word. This is a common metric to compare the efficiency of
+    /// tokenizers across languages.
+    ///
+    /// # Parameters
+    /// - text : text (string-like) to tokenize
+    ///
+    /// # Returns
+    /// `f64` number of tokens divided by the number of words of the input text (0.0 if the text contains no word)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let text = "Hello, world!";
+    /// let fertility = tokenizer.fertility(text);
+    /// ```
+    fn fertility<S: AsRef<str>>(&self, text: S) -> f64 {
+        let num_words = text.as_ref().split_whitespace().count();
+        if num_words == 0 {
+            return 0.0;
+        }
+        self.count_tokens_str(text) as f64 / num_words as f64
+    }
+
+    /// Compute the fertility of the tokenizer for a list of strings, i.e. the total number of
+    /// tokens divided by the total number of whitespace-delimited words across all texts provided.
+    ///
+    /// # Parameters
+    /// - text_list: list of strings to tokenize
+    ///
+    /// # Returns
+    /// `f64` number of tokens divided by the number of words of the input texts (0.0 if the texts contain no word)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let texts = ["Hello, world!", "Second sentence"];
+    /// let fertility = tokenizer.fertility_list(&texts);
+    /// ```
+    fn fertility_list<S, ST>(&self, text_list: S) -> f64
+    where
+        S: AsRef<[ST]>,
+        ST: AsRef<str>,
+    {
+        let (num_tokens, num_words) =
+            text_list
+                .as_ref()
+                .iter()
+                .fold((0, 0), |(num_tokens, num_words), text| {
+                    (
+                        num_tokens + self.count_tokens_str(text),
+                        num_words + text.as_ref().split_whitespace().count(),
+                    )
+                });
+        if num_words == 0 {
+            return 0.0;
+        }
+        num_tokens as f64 / num_words as f64
+    }
+
+    /// Compute the Jaccard similarity of the sets of token ids of two strings, i.e. the number of
+    /// distinct ids shared by both texts divided by the number of distinct ids in either text.
+    /// This is a cheap measure for near-duplicate detection and clustering of corpora.
+    ///
+    /// # Parameters
+    /// - text_1 (`&str`): first text to compare
+    /// - text_2 (`&str`): second text to compare
+    ///
+    /// # Returns
+    /// `f64` Jaccard index between 0.0 and 1.0 (1.0 if both texts produce no token)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let similarity = tokenizer.token_jaccard("Hello, world!", "Hello world");
+    /// ```
+    fn token_jaccard(&self, text_1: &str, text_2: &str) -> f64 {
+        let ids_1: HashSet<i64> = self
+            .convert_tokens_to_ids(self.tokenize(text_1))
+            .into_iter()
+            .collect();
+        let ids_2: HashSet<i64> = self
+            .convert_tokens_to_ids(self.tokenize(text_2))
+            .into_iter()
+            .collect();
+        let union = ids_1.union(&ids_2).count();
+        if union == 0 {
+            return 1.0;
+        }
+        ids_1.intersection(&ids_2).count() as f64 / union as f64
+    }
+
+    /// Performs the work deferred to the first tokenization ahead of time, for predictable latency
+    /// of the first request in serving contexts. This builds:
+    /// - the character tables shared by all tokenizers (punctuation, control characters, accent
+    ///   markers and byte-to-unicode mappings), which are created lazily on first use
+    /// - the internal caches of the regular expressions and of the tokenizer, by tokenizing a short
+    ///   sample text
+    ///
+    /// The vocabularies build all their mappings when loaded and do not require warming up.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// tokenizer.warm_up();
+    /// ```
+    fn warm_up(&self) {
+        initialize_character_tables();
+        self.tokenize("Hello, world! Héllo wörld 1234.");
+    }
+
     /// Tokenize a TokenRef, returning a sequence of tokens
     ///
     /// # Parameters
@@ -583,6 +859,37 @@ pub trait Tokenizer<T: Vocab> {
     /// ```
     fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token>;
 
+    /// Tokenize a single word with the subword model of the tokenizer (e.g. WordPiece, BPE or
+    /// SentencePiece), without the sentence-level normalization and splitting of
+    /// `tokenize_to_tokens` (special tokens, whitespace, punctuation, lower casing). The word is
+    /// expected in the form the model segments, e.g. lower cased for uncased models. Tokenizers
+    /// without a separate word-level path tokenize the word with `tokenize_to_tokens`.
+    ///
+    /// # Parameters
+    /// - word (`TokenRef`): TokenRef holding the word to tokenize
+    ///
+    /// # Returns
+    /// `Vec<Token>` sub-tokens of the word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use itertools::Itertools;
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, Tokenizer};
+    /// use rust_tokenizers::{OffsetSize, TokenRef};
+    /// let strip_accents = false;
+    /// let lower_case = true;
+    /// let tokenizer =
+    ///     BertTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let word = "unaffable";
+    /// let offsets = (0..word.len() as OffsetSize).collect_vec();
+    /// let tokens = tokenizer.tokenize_word_to_tokens(TokenRef::new(word, &offsets));
+    /// ```
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.tokenize_to_tokens(word)
+    }
+
     /// Tokenize a list of strings, returning tokens with offset information
     ///
     /// # Parameters
@@ -684,6 +991,69 @@ pub trait Tokenizer<T: Vocab> {
             .collect()
     }
 
+    /// Encode a single word into the ids of its segmentation by the subword model (see
+    /// `tokenize_word_to_tokens`), without special tokens or sentence-level normalization. This is
+    /// the primitive to build constraint tries for lexically-constrained decoding.
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to encode
+    ///
+    /// # Returns
+    /// `Vec<i64>` ids of the tokens the word is split into
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let token_ids = tokenizer.encode_word("unaffable");
+    /// ```
+    fn encode_word(&self, word: &str) -> Vec<i64> {
+        let offsets = (0..word.chars().count() as OffsetSize).collect::<Vec<OffsetSize>>();
+        self.tokenize_word_to_tokens(TokenRef::new(word, &offsets))
+            .iter()
+            .map(|token| self.vocab().token_to_id(&token.text))
+            .collect()
+    }
+
+    /// Returns the id of the token a word is encoded to, if the word is encoded to a single token
+    /// of the vocabulary. Words mapped to the unknown token (and not spelling it) return `None`.
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to look up
+    ///
+    /// # Returns
+    /// `Option<i64>` id of the single token for the word, `None` if the word is split into several tokens or is out of vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BaseTokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::BaseVocab;
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer: BaseTokenizer<BaseVocab> =
+    ///     BaseTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let token_id = tokenizer.is_single_token("hello");
+    /// ```
+    fn is_single_token(&self, word: &str) -> Option<i64> {
+        match self.tokenize(word).as_slice() {
+            [token]
+                if self.vocab().values().contains_key(token)
+                    || self.vocab().special_values().contains_key(token) =>
+            {
+                Some(self.vocab().token_to_id(token))
+            }
+            _ => None,
+        }
+    }
+
     /// Encode a string-like (tokenization followed by encoding)
     ///
     /// # Parameters
@@ -1205,6 +1575,117 @@ pub trait Tokenizer<T: Vocab> {
             mask: tokens_ids_with_offsets_1.masks,
         }
     }
+
+    /// Returns the number of special tokens added by `build_input_with_special_tokens` to a single
+    /// sequence or a pair of sequences. This is useful to reserve space for the special tokens
+    /// before truncating inputs to a maximum length.
+    ///
+    /// # Parameters
+    /// - pair (`bool`): flag indicating if the count is for a pair of sequences
+    ///
+    /// # Returns
+    /// - `usize` number of special tokens added to the input(s)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, Tokenizer};
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     BertTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let num_special_tokens_single = tokenizer.num_special_tokens_to_add(false);
+    /// let num_special_tokens_pair = tokenizer.num_special_tokens_to_add(true);
+    /// ```
+    fn num_special_tokens_to_add(&self, pair: bool) -> usize {
+        let empty_input = || TokenIdsWithOffsets {
+            ids: vec![],
+            offsets: vec![],
+            reference_offsets: vec![],
+            masks: vec![],
+        };
+        let second_input = if pair { Some(empty_input()) } else { None };
+        self.build_input_with_special_tokens(empty_input(), second_input)
+            .token_ids
+            .len()
+    }
+
+    /// Tokenizes a text and splits the resulting token ids into overlapping windows, for processing
+    /// inputs longer than the model maximum length. Consecutive windows share `stride` tokens. The
+    /// last window may be shorter than the others, and a text shorter than the window size results
+    /// in a single window. No window is returned if the window size does not leave room for at
+    /// least one token besides the special tokens.
+    ///
+    /// # Parameters
+    /// - text (`&str`): text to tokenize
+    /// - window_size (`usize`): maximum length of each window, including the special tokens if `add_special_tokens` is set
+    /// - stride (`usize`): number of tokens shared by consecutive windows. This should be smaller than the number of non-special tokens in a window: windows advance by at least one token
+    /// - add_special_tokens (`bool`): flag indicating if each window should be wrapped with the special tokens of the tokenizer
+    ///
+    /// # Returns
+    /// - `Vec<Vec<i64>>` token ids of the windows (empty if the text does not contain any token or if `window_size` is not larger than the number of special tokens added)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, Tokenizer};
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     BertTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let windows = tokenizer.window("A very long document...", 512, 128, true);
+    /// ```
+    fn window(
+        &self,
+        text: &str,
+        window_size: usize,
+        stride: usize,
+        add_special_tokens: bool,
+    ) -> Vec<Vec<i64>> {
+        let num_special_tokens = if add_special_tokens {
+            self.num_special_tokens_to_add(false)
+        } else {
+            0
+        };
+        if window_size <= num_special_tokens {
+            return vec![];
+        }
+        let token_ids = self.convert_tokens_to_ids(self.tokenize(text));
+        if token_ids.is_empty() {
+            return vec![];
+        }
+        let content_size = window_size - num_special_tokens;
+        let step = content_size.saturating_sub(stride).max(1);
+
+        let mut windows = vec![];
+        let mut start = 0;
+        loop {
+            let end = (start + content_size).min(token_ids.len());
+            let window_ids = token_ids[start..end].to_vec();
+            windows.push(if add_special_tokens {
+                let length = window_ids.len();
+                self.build_input_with_special_tokens(
+                    TokenIdsWithOffsets {
+                        ids: window_ids,
+                        offsets: vec![None; length],
+                        reference_offsets: vec![vec![]; length],
+                        masks: vec![Mask::None; length],
+                    },
+                    None,
+                )
+                .token_ids
+            } else {
+                window_ids
+            });
+            if end == token_ids.len() {
+                break;
+            }
+            start += step;
+        }
+        windows
+    }
 }
 
 /// # Extension for multithreaded tokenizers
@@ -1592,6 +2073,7 @@ mod tests {
     extern crate anyhow;
 
     use super::*;
+    use crate::tokenizer::tokenization_utils::decompose_nfkc;
     use crate::vocab::base_vocab::swap_key_values;
     use crate::vocab::BertVocab;
     use std::collections::HashMap;
@@ -1637,6 +2119,7 @@ mod tests {
             unknown_value: "[UNK]",
             special_values,
             special_indices,
+            do_lower_case: false,
         }
     }
 
@@ -2074,6 +2557,189 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_count_tokens() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+        let test_sentences = [
+            "Sentence with [MASK] token.",
+            "[CLS]",
+            "Hello, world!",
+            "   ",
+            "",
+            "[UNK]中华人民共和国 [PAD] asdf",
+        ];
+
+        //        When & Then
+        for sentence in test_sentences.iter() {
+            assert_eq!(
+                base_tokenizer.count_tokens_str(sentence),
+                base_tokenizer.tokenize(sentence).len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_num_special_tokens_to_add() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(base_tokenizer.num_special_tokens_to_add(false), 0);
+        assert_eq!(base_tokenizer.num_special_tokens_to_add(true), 0);
+    }
+
+    #[test]
+    fn test_window() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+        let text = "hello world hello world hello";
+
+        //        When
+        let windows = base_tokenizer.window(text, 2, 1, false);
+        let non_overlapping_windows = base_tokenizer.window(text, 2, 0, false);
+        let single_window = base_tokenizer.window(text, 16, 4, false);
+        let empty_windows = base_tokenizer.window("", 2, 1, false);
+        let zero_size_windows = base_tokenizer.window(text, 0, 0, false);
+
+        //        Then
+        let ids = base_tokenizer.convert_tokens_to_ids(base_tokenizer.tokenize(text));
+        assert_eq!(ids.len(), 5);
+        assert_eq!(
+            windows,
+            vec![
+                ids[0..2].to_vec(),
+                ids[1..3].to_vec(),
+                ids[2..4].to_vec(),
+                ids[3..5].to_vec()
+            ]
+        );
+        assert_eq!(
+            non_overlapping_windows,
+            vec![ids[0..2].to_vec(), ids[2..4].to_vec(), ids[4..5].to_vec()]
+        );
+        assert_eq!(single_window, vec![ids]);
+        assert!(zero_size_windows.is_empty());
+        assert!(empty_windows.is_empty());
+    }
+
+    #[test]
+    fn test_fertility() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(base_tokenizer.fertility("Hello, world!"), 2.0);
+        assert_eq!(base_tokenizer.fertility("Hello world"), 1.0);
+        assert_eq!(base_tokenizer.fertility("   "), 0.0);
+        assert_eq!(
+            base_tokenizer.fertility_list(["Hello, world!", "Hello world"]),
+            1.5
+        );
+        assert_eq!(base_tokenizer.fertility_list(["", " "]), 0.0);
+    }
+
+    #[test]
+    fn test_encode_word() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(base_tokenizer.encode_word("Hello"), vec![0]);
+        assert_eq!(base_tokenizer.encode_word("world!"), vec![1, 3]);
+        assert_eq!(base_tokenizer.encode_word(""), Vec::<i64>::new());
+        assert_eq!(base_tokenizer.is_single_token("Hello"), Some(0));
+        assert_eq!(base_tokenizer.is_single_token("[MASK]"), Some(6));
+        assert_eq!(base_tokenizer.is_single_token("[UNK]"), Some(2));
+        assert_eq!(base_tokenizer.is_single_token("world!"), None);
+        assert_eq!(base_tokenizer.is_single_token("oov"), None);
+        assert_eq!(base_tokenizer.is_single_token(""), None);
+    }
+
+    #[test]
+    fn test_token_jaccard() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(
+            base_tokenizer.token_jaccard("Hello, world!", "world hello"),
+            0.5
+        );
+        assert_eq!(base_tokenizer.token_jaccard("hello hello", "Hello"), 1.0);
+        assert_eq!(base_tokenizer.token_jaccard("hello", "world"), 0.0);
+        assert_eq!(base_tokenizer.token_jaccard("hello", ""), 0.0);
+        assert_eq!(base_tokenizer.token_jaccard("", "  "), 1.0);
+    }
+
+    #[test]
+    fn test_token_alignments() {
+        //        Given
+        let original_text = "Hé \u{FB01}";
+        let mut token = Token::new(original_text.to_string());
+        decompose_nfkc(&mut token);
+        lowercase(&mut token);
+
+        //        When
+        let alignments = token.alignments(original_text);
+
+        //        Then
+        assert_eq!(token.text, "hé fi");
+        assert_eq!(
+            alignments,
+            vec![(0, 1), (1, 3), (1, 3), (3, 4), (4, 7), (4, 7)]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_with_byte_offsets() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let base_tokenizer: BaseTokenizer<BertVocab> =
+            BaseTokenizer::from_existing_vocab(vocab, true, true);
+        let source_text = "Hé w\u{F6}rld \u{FB01}!";
+
+        //        When
+        let tokens_with_offsets = base_tokenizer.tokenize_with_offsets(source_text);
+
+        //        Then
+        assert_eq!(
+            tokens_with_offsets.tokens,
+            vec!["he", "world", "\u{FB01}", "!"]
+        );
+        assert_eq!(
+            tokens_with_offsets.offsets,
+            vec![
+                Some(Offset::new(0, 2)),
+                Some(Offset::new(3, 8)),
+                Some(Offset::new(9, 10)),
+                Some(Offset::new(10, 11))
+            ]
+        );
+        assert_eq!(
+            tokens_with_offsets.byte_offsets,
+            vec![
+                Some(Offset::new(0, 3)),
+                Some(Offset::new(4, 10)),
+                Some(Offset::new(11, 14)),
+                Some(Offset::new(14, 15))
+            ]
+        );
+        assert_eq!(&source_text[4..10], "w\u{F6}rld");
+    }
+
     #[test]
     fn test_convert_tokens_to_ids() {
         //        Given
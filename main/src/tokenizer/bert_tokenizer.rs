@@ -17,16 +17,23 @@ use crate::tokenizer::base_tokenizer::{
     BaseTokenizer, Mask, MultiThreadedTokenizer, Offset, OffsetSize, Token, TokenIdsWithOffsets,
     TokenIdsWithSpecialTokens, TokenRef, Tokenizer,
 };
-use crate::tokenizer::tokenization_utils::tokenize_wordpiece;
+use crate::tokenizer::tokenization_utils::{
+    count_wordpiece, tokenize_wordpiece, DEFAULT_MAX_INPUT_CHARS_PER_WORD,
+};
 use crate::vocab::{BertVocab, Vocab};
 
 /// # BERT tokenizer
 /// BERT tokenizer performing:
 /// - BaseTokenizer tokenization (see `BaseTokenizer` for more details)
 /// - WordPiece tokenization
-pub struct BertTokenizer {
-    vocab: BertVocab,
-    base_tokenizer: BaseTokenizer<BertVocab>,
+///
+/// The tokenizer uses a `BertVocab` by default. Vocabulary wrappers of a `BertVocab` (e.g.
+/// `ScriptUnknownVocab<BertVocab>`) can be used with `from_existing_vocab`, in which case words that
+/// cannot be segmented are mapped to the unknown token resolved by the vocabulary.
+pub struct BertTokenizer<V: Vocab = BertVocab> {
+    vocab: V,
+    base_tokenizer: BaseTokenizer<V>,
+    max_input_chars_per_word: usize,
 }
 
 impl BertTokenizer {
@@ -58,13 +65,16 @@ impl BertTokenizer {
         Ok(BertTokenizer {
             vocab,
             base_tokenizer,
+            max_input_chars_per_word: DEFAULT_MAX_INPUT_CHARS_PER_WORD,
         })
     }
+}
 
+impl<V: Vocab + Clone + Sync + Send> BertTokenizer<V> {
     /// Create a new instance of a `BertTokenizer` from an existing vocabulary
     ///
     /// # Parameters
-    /// - vocab (`BertVocab`): Thread-safe reference to a BERT vocabulary
+    /// - vocab (`V`): Thread-safe reference to a BERT vocabulary, or to a wrapper of a BERT vocabulary
     /// - lower_case (`bool`): flag indicating if the text should be lower-cased as part of the tokenization
     /// - strip_accents (`bool`): flag indicating if accents should be stripped from the text
     ///
@@ -80,21 +90,48 @@ impl BertTokenizer {
     /// let tokenizer = BertTokenizer::from_existing_vocab(vocab, lower_case, strip_accents);
     /// ```
     pub fn from_existing_vocab(
-        vocab: BertVocab,
+        vocab: V,
         lower_case: bool,
         strip_accents: bool,
-    ) -> BertTokenizer {
+    ) -> BertTokenizer<V> {
         let base_tokenizer =
             BaseTokenizer::from_existing_vocab(vocab.clone(), lower_case, strip_accents);
         BertTokenizer {
             vocab,
             base_tokenizer,
+            max_input_chars_per_word: DEFAULT_MAX_INPUT_CHARS_PER_WORD,
         }
     }
+
+    /// Sets the maximum number of characters of a word segmented into word pieces. Longer words
+    /// are mapped to the unknown token without being segmented, bounding the tokenization cost of
+    /// pathological inputs. Defaults to 100 characters, matching the reference WordPiece
+    /// implementation.
+    ///
+    /// # Parameters
+    /// - max_input_chars_per_word (`usize`): maximum number of characters of a word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, Tokenizer};
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer = BertTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents)
+    ///     .unwrap()
+    ///     .with_max_input_chars_per_word(200);
+    /// ```
+    pub fn with_max_input_chars_per_word(
+        mut self,
+        max_input_chars_per_word: usize,
+    ) -> BertTokenizer<V> {
+        self.max_input_chars_per_word = max_input_chars_per_word;
+        self
+    }
 }
 
-impl Tokenizer<BertVocab> for BertTokenizer {
-    fn vocab(&self) -> &BertVocab {
+impl<V: Vocab + Sync + Send> Tokenizer<V> for BertTokenizer<V> {
+    fn vocab(&self) -> &V {
         &self.vocab
     }
 
@@ -103,11 +140,25 @@ impl Tokenizer<BertVocab> for BertTokenizer {
         self.base_tokenizer
             .tokenize_to_tokens(initial_token)
             .into_iter()
-            .map(|token| tokenize_wordpiece(token.as_ref(), &self.vocab, 100))
+            .map(|token| {
+                tokenize_wordpiece(token.as_ref(), &self.vocab, self.max_input_chars_per_word)
+            })
             .flatten()
             .collect()
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        tokenize_wordpiece(word, &self.vocab, self.max_input_chars_per_word)
+    }
+
+    fn count_tokens(&self, initial_token: TokenRef) -> usize {
+        self.base_tokenizer
+            .tokenize_to_tokens(initial_token)
+            .iter()
+            .map(|token| count_wordpiece(&token.text, &self.vocab, self.max_input_chars_per_word))
+            .sum()
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens.join(" ").replace(" ##", "").trim().to_owned()
     }
@@ -165,7 +216,7 @@ impl Tokenizer<BertVocab> for BertTokenizer {
     }
 }
 
-impl MultiThreadedTokenizer<BertVocab> for BertTokenizer {}
+impl<V: Vocab + Sync + Send> MultiThreadedTokenizer<V> for BertTokenizer<V> {}
 
 //==============================
 // Unit tests
@@ -175,7 +226,7 @@ mod tests {
     use super::*;
     use crate::tokenizer::base_tokenizer::TruncationStrategy;
     use crate::vocab::base_vocab::swap_key_values;
-    use crate::vocab::BertVocab;
+    use crate::vocab::{BertVocab, Script, ScriptUnknownVocab};
     use crate::TokenizedInput;
     use itertools::Itertools;
     use std::collections::HashMap;
@@ -221,6 +272,7 @@ mod tests {
             unknown_value: "[UNK]",
             special_values,
             special_indices,
+            do_lower_case: false,
         }
     }
 
@@ -260,6 +312,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bert_tokenizer_max_input_chars_per_word() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let bert_tokenizer: BertTokenizer =
+            BertTokenizer::from_existing_vocab(vocab.clone(), true, true);
+        let capped_bert_tokenizer: BertTokenizer =
+            BertTokenizer::from_existing_vocab(vocab, true, true).with_max_input_chars_per_word(8);
+        let long_word = format!("hello {} world", "una".repeat(100_000));
+
+        //        When & Then
+        assert_eq!(
+            bert_tokenizer.tokenize(&long_word),
+            vec!["hello", "[UNK]", "world"]
+        );
+        assert_eq!(
+            bert_tokenizer.tokenize("unaffable"),
+            vec!["una", "##ffa", "##ble"]
+        );
+        assert_eq!(capped_bert_tokenizer.tokenize("unaffable"), vec!["[UNK]"]);
+        assert_eq!(capped_bert_tokenizer.tokenize("hello"), vec!["hello"]);
+    }
+
+    #[test]
+    fn test_count_tokens() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let bert_tokenizer: BertTokenizer =
+            BertTokenizer::from_existing_vocab(vocab.clone(), true, true);
+        let capped_bert_tokenizer: BertTokenizer =
+            BertTokenizer::from_existing_vocab(vocab, true, true).with_max_input_chars_per_word(8);
+        let source_texts = [
+            "Hello [MASK] world!",
+            "Hello, unaffable world!",
+            "[UNK]中华人民共和国 [PAD] asdf",
+            "",
+        ];
+
+        //        When & Then
+        for source_text in source_texts.iter() {
+            assert_eq!(
+                bert_tokenizer.count_tokens_str(source_text),
+                bert_tokenizer.tokenize(source_text).len()
+            );
+            assert_eq!(
+                capped_bert_tokenizer.count_tokens_str(source_text),
+                capped_bert_tokenizer.tokenize(source_text).len()
+            );
+        }
+        assert_eq!(bert_tokenizer.count_tokens_str("unaffable"), 3);
+        assert_eq!(capped_bert_tokenizer.count_tokens_str("unaffable"), 1);
+    }
+
+    #[test]
+    fn test_bert_tokenizer_script_unknown_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab = generate_test_vocab();
+        vocab.values.insert("[UNK_CJK]".to_owned(), 14);
+        vocab.indices.insert(14, "[UNK_CJK]".to_owned());
+        let mut script_unknown_vocab = ScriptUnknownVocab::new(vocab);
+        script_unknown_vocab.register_unknown(Script::Cjk, "[UNK_CJK]")?;
+        let bert_tokenizer = BertTokenizer::from_existing_vocab(script_unknown_vocab, true, true);
+
+        //        When
+        let tokens = bert_tokenizer.tokenize("Hello, 中华人民!");
+        let token_ids = bert_tokenizer.convert_tokens_to_ids(&tokens);
+
+        //        Then
+        assert_eq!(
+            tokens,
+            vec!["hello", "[UNK]", "中", "华", "人", "[UNK_CJK]", "!"]
+        );
+        assert_eq!(token_ids, vec![0, 2, 7, 8, 9, 14, 3]);
+        Ok(())
+    }
+
     #[test]
     fn test_bert_tokenizer_no_lower_casing() {
         //        Given
@@ -296,6 +424,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_num_special_tokens_to_add() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let bert_tokenizer: BertTokenizer = BertTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(bert_tokenizer.num_special_tokens_to_add(false), 2);
+        assert_eq!(bert_tokenizer.num_special_tokens_to_add(true), 3);
+    }
+
+    #[test]
+    fn test_window_with_special_tokens() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let bert_tokenizer: BertTokenizer = BertTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When
+        let windows = bert_tokenizer.window("hello world hello", 4, 1, true);
+        let special_tokens_only_windows = bert_tokenizer.window("hello world hello", 2, 0, true);
+        let single_token_windows = bert_tokenizer.window("hello world hello", 3, 0, true);
+
+        //        Then
+        assert_eq!(windows, vec![vec![4, 0, 1, 5], vec![4, 1, 0, 5]]);
+        assert!(special_tokens_only_windows.is_empty());
+        assert_eq!(
+            single_token_windows,
+            vec![vec![4, 0, 5], vec![4, 1, 5], vec![4, 0, 5]]
+        );
+    }
+
     #[test]
     fn test_encode() {
         //        Given
@@ -667,4 +826,24 @@ mod tests {
             expected_results
         );
     }
+
+    #[test]
+    fn test_encode_word() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let bert_tokenizer: BertTokenizer = BertTokenizer::from_existing_vocab(vocab, true, true);
+
+        //        When & Then
+        assert_eq!(bert_tokenizer.encode_word("unaffable"), vec![11, 12, 13]);
+        assert_eq!(bert_tokenizer.encode_word("hello"), vec![0]);
+        assert_eq!(bert_tokenizer.encode_word("hello!"), vec![2]);
+        assert_eq!(
+            bert_tokenizer
+                .encode("hello!", None, 128, &TruncationStrategy::LongestFirst, 0)
+                .token_ids,
+            vec![4, 0, 3, 5]
+        );
+        assert_eq!(bert_tokenizer.is_single_token("hello"), Some(0));
+        assert_eq!(bert_tokenizer.is_single_token("unaffable"), None);
+    }
 }
@@ -0,0 +1,294 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tokenizer::base_tokenizer::{TokenIdsWithOffsets, TokenIdsWithSpecialTokens};
+use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
+use crate::vocab::Vocab;
+use crate::{OffsetSize, Token, TokenRef};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+type CacheKey = (String, Vec<OffsetSize>);
+
+struct CacheEntry {
+    tokens: Vec<Token>,
+    last_used: u64,
+}
+
+struct LruCache {
+    entries: HashMap<CacheKey, CacheEntry>,
+    counter: u64,
+}
+
+/// # CachedTokenizer
+/// Tokenizer wrapper memoizing the tokenization of input strings in a least-recently-used cache of
+/// configurable capacity. This speeds up applications tokenizing the same short strings repeatedly
+/// (e.g. system prompts or role markers), at the cost of the memory used by the cache.
+///
+/// The cache belongs to the wrapped tokenizer: the tokenizer settings (e.g. lower casing or accent
+/// stripping) are fixed at construction, and cached results are therefore valid for every
+/// subsequent call. Entries are keyed by the input text and its reference offsets.
+///
+/// The cache is shared between threads. Lookups do not block: if the cache is in use by another
+/// thread, the input is tokenized without the cache. Evicting an entry scans the cache, making
+/// insertions O(capacity): the cache is intended for small capacities.
+pub struct CachedTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    tokenizer: T,
+    capacity: usize,
+    cache: Mutex<LruCache>,
+    _vocab: PhantomData<V>,
+}
+
+impl<T, V> CachedTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    /// Create a new `CachedTokenizer` wrapping an existing tokenizer
+    ///
+    /// # Parameters
+    /// - tokenizer (`T`): tokenizer to wrap
+    /// - capacity (`usize`): maximum number of inputs kept in the cache
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, CachedTokenizer, Tokenizer};
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     BertTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents).unwrap();
+    ///
+    /// let cached_tokenizer = CachedTokenizer::new(tokenizer, 1024);
+    /// let tokens = cached_tokenizer.tokenize("You are a helpful assistant.");
+    /// ```
+    pub fn new(tokenizer: T, capacity: usize) -> CachedTokenizer<T, V> {
+        CachedTokenizer {
+            tokenizer,
+            capacity,
+            cache: Mutex::new(LruCache {
+                entries: HashMap::with_capacity(capacity),
+                counter: 0,
+            }),
+            _vocab: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped tokenizer
+    pub fn inner(&self) -> &T {
+        &self.tokenizer
+    }
+
+    /// Returns the number of inputs currently cached
+    pub fn cache_len(&self) -> usize {
+        self.cache
+            .lock()
+            .map(|cache| cache.entries.len())
+            .unwrap_or(0)
+    }
+
+    /// Removes all entries from the cache
+    pub fn clear_cache(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.entries.clear();
+        }
+    }
+
+    fn get_cached(&self, key: &CacheKey) -> Option<Vec<Token>> {
+        let mut cache = self.cache.try_lock().ok()?;
+        cache.counter += 1;
+        let counter = cache.counter;
+        let entry = cache.entries.get_mut(key)?;
+        entry.last_used = counter;
+        Some(entry.tokens.clone())
+    }
+
+    fn insert_cached(&self, key: CacheKey, tokens: &[Token]) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Ok(mut cache) = self.cache.try_lock() {
+            if cache.entries.len() >= self.capacity && !cache.entries.contains_key(&key) {
+                let least_recently_used = cache
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                if let Some(least_recently_used) = least_recently_used {
+                    cache.entries.remove(&least_recently_used);
+                }
+            }
+            cache.counter += 1;
+            let last_used = cache.counter;
+            cache.entries.insert(
+                key,
+                CacheEntry {
+                    tokens: tokens.to_vec(),
+                    last_used,
+                },
+            );
+        }
+    }
+}
+
+impl<T, V> Tokenizer<V> for CachedTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    fn vocab(&self) -> &V {
+        self.tokenizer.vocab()
+    }
+
+    fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token> {
+        let key = (text.text.to_owned(), text.reference_offsets.to_vec());
+        if let Some(tokens) = self.get_cached(&key) {
+            return tokens;
+        }
+        let tokens = self.tokenizer.tokenize_to_tokens(text);
+        self.insert_cached(key, &tokens);
+        tokens
+    }
+
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.tokenizer.tokenize_word_to_tokens(word)
+    }
+
+    fn count_tokens(&self, text: TokenRef) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
+    fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
+        self.tokenizer.convert_tokens_to_string(tokens)
+    }
+
+    fn build_input_with_special_tokens(
+        &self,
+        tokens_ids_with_offsets_1: TokenIdsWithOffsets,
+        tokens_ids_with_offsets_2: Option<TokenIdsWithOffsets>,
+    ) -> TokenIdsWithSpecialTokens {
+        self.tokenizer
+            .build_input_with_special_tokens(tokens_ids_with_offsets_1, tokens_ids_with_offsets_2)
+    }
+}
+
+impl<T, V> MultiThreadedTokenizer<V> for CachedTokenizer<T, V>
+where
+    T: MultiThreadedTokenizer<V>,
+    V: Vocab + Sync + Send,
+{
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::tokenizer::{BaseTokenizer, TruncationStrategy};
+    use crate::vocab::BaseVocab;
+    use std::io::Write;
+    use std::time::Instant;
+
+    fn generate_test_tokenizer() -> anyhow::Result<BaseTokenizer<BaseVocab>> {
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "[UNK]\nyou\nare\na\nhelpful\nassistant\n.\nuser\n:\nhello"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        drop(path);
+        Ok(BaseTokenizer::from_existing_vocab(vocab, true, true))
+    }
+
+    #[test]
+    fn test_cached_tokenizer() -> anyhow::Result<()> {
+        //        Given
+        let tokenizer = generate_test_tokenizer()?;
+        let cached_tokenizer = CachedTokenizer::new(generate_test_tokenizer()?, 2);
+
+        //        When & Then
+        for text in [
+            "You are a helpful assistant.",
+            "User:",
+            "You are a helpful assistant.",
+        ]
+        .iter()
+        {
+            assert_eq!(cached_tokenizer.tokenize(text), tokenizer.tokenize(text));
+            assert_eq!(
+                cached_tokenizer.encode(text, None, 128, &TruncationStrategy::LongestFirst, 0),
+                tokenizer.encode(text, None, 128, &TruncationStrategy::LongestFirst, 0)
+            );
+        }
+        assert_eq!(cached_tokenizer.cache_len(), 2);
+
+        cached_tokenizer.tokenize("Hello");
+        assert_eq!(cached_tokenizer.cache_len(), 2);
+        assert!(cached_tokenizer
+            .get_cached(&("User:".to_owned(), (0..5).collect()))
+            .is_none());
+        assert!(cached_tokenizer
+            .get_cached(&("You are a helpful assistant.".to_owned(), (0..28).collect()))
+            .is_some());
+
+        cached_tokenizer.clear_cache();
+        assert_eq!(cached_tokenizer.cache_len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_cached_tokenizer() -> anyhow::Result<()> {
+        // Run with `cargo test --release -- --ignored bench_cached_tokenizer --nocapture`
+        let tokenizer = generate_test_tokenizer()?;
+        let cached_tokenizer = CachedTokenizer::new(generate_test_tokenizer()?, 16);
+        let texts = [
+            "You are a helpful assistant.",
+            "User:",
+            "Assistant:",
+            "Hello, you are a helpful assistant.",
+        ];
+        let iterations = 100_000;
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            for text in texts.iter() {
+                tokenizer.tokenize(text);
+            }
+        }
+        let uncached_duration = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..iterations {
+            for text in texts.iter() {
+                cached_tokenizer.tokenize(text);
+            }
+        }
+        let cached_duration = start.elapsed();
+
+        println!(
+            "uncached: {:?}, cached: {:?}, speedup: {:.2}x",
+            uncached_duration,
+            cached_duration,
+            uncached_duration.as_secs_f64() / cached_duration.as_secs_f64()
+        );
+        Ok(())
+    }
+}
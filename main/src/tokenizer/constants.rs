@@ -635,3 +635,13 @@ lazy_static! {
         .map(|(key, value)| (value, key))
         .collect();
 }
+
+/// Forces the initialization of the lazily-built character tables, so that their construction cost
+/// is not incurred by the first tokenization.
+pub(crate) fn initialize_character_tables() {
+    lazy_static::initialize(&PUNCTUATION_CHARS);
+    lazy_static::initialize(&CONTROL_CHARS);
+    lazy_static::initialize(&ACCENT_MARKERS);
+    lazy_static::initialize(&BYTES_TO_UNICODE);
+    lazy_static::initialize(&UNICODE_TO_BYTES);
+}
@@ -14,8 +14,8 @@
 
 use crate::error::TokenizerError;
 use crate::tokenizer::tokenization_utils::{
-    ctrl_bpe, fix_mask, lowercase, split_on_bpe_pairs, split_on_regex, split_on_special_tokens,
-    BpeCache,
+    bpe_merge_count, ctrl_bpe, fix_mask, lowercase, split_on_bpe_pairs, split_on_regex,
+    split_on_special_tokens, BpeCache,
 };
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::bpe_vocab::BpePairVocab;
@@ -107,6 +107,31 @@ impl CtrlTokenizer {
             lower_case,
         }
     }
+
+    /// Returns the number of BPE merges applied to segment a single word, for example to understand
+    /// why some words are encoded as a single token and others as many. The word is segmented as a
+    /// single unit: it is not lower-cased or split by the pre-tokenization.
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to segment
+    ///
+    /// # Returns
+    /// - `usize` number of merges applied to the word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::CtrlTokenizer;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     CtrlTokenizer::from_file("path/to/vocab/file", "path/to/merges/file", lower_case)
+    ///         .unwrap();
+    ///
+    /// let merge_count = tokenizer.merge_count("hello");
+    /// ```
+    pub fn merge_count(&self, word: &str) -> usize {
+        bpe_merge_count(word, ctrl_bpe, &self.bpe_ranks, false)
+    }
 }
 
 impl Tokenizer<OpenAiGptVocab> for CtrlTokenizer {
@@ -143,6 +168,10 @@ impl Tokenizer<OpenAiGptVocab> for CtrlTokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        split_on_bpe_pairs(word, ctrl_bpe, &self.bpe_ranks, &self.cache, false)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens.join(" ").replace("@@ ", "").trim().to_owned()
     }
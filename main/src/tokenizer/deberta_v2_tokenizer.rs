@@ -0,0 +1,292 @@
+// Copyright 2020 Microsoft and the HuggingFace Inc. team.
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::tokenizer::base_tokenizer::{TokenIdsWithOffsets, TokenIdsWithSpecialTokens};
+use crate::tokenizer::tokenization_utils::{
+    clean_text, decompose_nfkc, is_whitespace, lowercase, split_on_punct, split_on_special_tokens,
+};
+use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
+use crate::vocab::{DeBERTaV2Vocab, SentencePieceModel, Vocab};
+use crate::{Mask, Offset, OffsetSize, Token, TokenRef};
+
+/// # DeBERTa (v2) tokenizer
+/// DeBERTa (v2) tokenizer performing:
+/// - splitting on special characters
+/// - text cleaning
+/// - NFKC decomposition
+/// - (optional) lower casing
+/// - (optional) splitting on punctuation
+/// - SentencePiece decomposition
+#[allow(clippy::upper_case_acronyms)]
+pub struct DeBERTaV2Tokenizer {
+    model: SentencePieceModel,
+    vocab: DeBERTaV2Vocab,
+    lower_case: bool,
+    split_on_punct: bool,
+}
+
+impl DeBERTaV2Tokenizer {
+    /// Create a new instance of a `DeBERTaV2Tokenizer`
+    /// Expects a SentencePiece protobuf file as an input.
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the SentencePiece model file
+    /// - lower_case (`bool`): flag indicating if the text should be lower-cased as part of the tokenization
+    /// - split_on_punct (`bool`): flag indicating if the text should be split on punctuation before the SentencePiece decomposition
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{DeBERTaV2Tokenizer, Tokenizer};
+    /// let lower_case = false;
+    /// let split_on_punct = false;
+    /// let tokenizer =
+    ///     DeBERTaV2Tokenizer::from_file("path/to/vocab/file", lower_case, split_on_punct).unwrap();
+    /// ```
+    pub fn from_file(
+        path: &str,
+        lower_case: bool,
+        split_on_punct: bool,
+    ) -> Result<DeBERTaV2Tokenizer, TokenizerError> {
+        let model = SentencePieceModel::from_file(path)?;
+        let vocab = DeBERTaV2Vocab::from_file(path)?;
+        Ok(DeBERTaV2Tokenizer {
+            model,
+            vocab,
+            lower_case,
+            split_on_punct,
+        })
+    }
+
+    /// Create a new instance of a `DeBERTaV2Tokenizer` from an existing vocabulary and model
+    ///
+    /// # Parameters
+    /// - vocab (`DeBERTaV2Vocab`): vocabulary
+    /// - model (`SentencePieceModel`): SentencePiece model
+    /// - lower_case (`bool`): flag indicating if the text should be lower-cased as part of the tokenization
+    /// - split_on_punct (`bool`): flag indicating if the text should be split on punctuation before the SentencePiece decomposition
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{DeBERTaV2Tokenizer, Tokenizer};
+    /// use rust_tokenizers::vocab::{DeBERTaV2Vocab, SentencePieceModel, Vocab};
+    /// let lower_case = false;
+    /// let split_on_punct = false;
+    /// let vocab = DeBERTaV2Vocab::from_file("path/to/vocab/file").unwrap();
+    /// let model = SentencePieceModel::from_file("path/to/model/file").unwrap();
+    ///
+    /// let tokenizer =
+    ///     DeBERTaV2Tokenizer::from_existing_vocab_and_model(vocab, model, lower_case, split_on_punct);
+    /// ```
+    pub fn from_existing_vocab_and_model(
+        vocab: DeBERTaV2Vocab,
+        model: SentencePieceModel,
+        lower_case: bool,
+        split_on_punct: bool,
+    ) -> DeBERTaV2Tokenizer {
+        DeBERTaV2Tokenizer {
+            model,
+            vocab,
+            lower_case,
+            split_on_punct,
+        }
+    }
+
+    fn tokenize_word(&self, mut word: Token) -> Vec<Token> {
+        // Each word is encoded independently by the reference implementation: trailing whitespace
+        // is dropped and the word receives its own leading meta symbol.
+        let trimmed = word.text.trim_end_matches(|c: char| is_whitespace(&c));
+        let trimmed_len = trimmed.len();
+        let trimmed_char_count = trimmed.chars().count();
+        if trimmed_char_count == 0 {
+            return vec![];
+        }
+        word.text.truncate(trimmed_len);
+        word.reference_offsets.truncate(trimmed_char_count);
+
+        word.text = word.text.replace(|c: char| is_whitespace(&c), "\u{2581}");
+        if !word.text.starts_with('\u{2581}') {
+            word.text.insert(0, '\u{2581}');
+            let first_offset = word.reference_offsets.first().copied().unwrap_or(0);
+            word.reference_offsets.insert(0, first_offset);
+        };
+        let output = self.model.decode_forward_token_ref(word.as_ref());
+        let decoded = self.model.decode_backward(&output);
+        self.model.parse_nodes_to_tokens(decoded)
+    }
+}
+
+impl Tokenizer<DeBERTaV2Vocab> for DeBERTaV2Tokenizer {
+    fn vocab(&self) -> &DeBERTaV2Vocab {
+        &self.vocab
+    }
+
+    fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token> {
+        let mut tokens = split_on_special_tokens(text, &self.vocab)
+            .into_iter()
+            .map(|token| token.to_owned())
+            .collect::<Vec<Token>>();
+
+        let mut sub_tokens: Vec<Token> = Vec::new();
+        for token in tokens.iter_mut() {
+            if token.mask != Mask::Special && token.mask != Mask::Unknown {
+                clean_text(token, true);
+                decompose_nfkc(token);
+                if self.lower_case {
+                    lowercase(token);
+                }
+                if self.split_on_punct {
+                    for word in split_on_punct(token.as_ref()) {
+                        sub_tokens.extend(self.tokenize_word(word.to_owned()));
+                    }
+                } else {
+                    sub_tokens.extend(self.tokenize_word(token.clone()));
+                }
+            } else {
+                sub_tokens.push(token.clone());
+            }
+        }
+        sub_tokens
+    }
+
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.tokenize_word(word.to_owned())
+    }
+
+    fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
+        tokens
+            .into_iter()
+            .map(|v| v.replace('\u{2581}', " "))
+            .collect::<Vec<String>>()
+            .join("")
+            .trim()
+            .to_owned()
+    }
+
+    fn build_input_with_special_tokens(
+        &self,
+        tokens_ids_with_offsets_1: TokenIdsWithOffsets,
+        tokens_ids_with_offsets_2: Option<TokenIdsWithOffsets>,
+    ) -> TokenIdsWithSpecialTokens {
+        let mut output: Vec<i64> = vec![];
+        let mut token_segment_ids: Vec<i8> = vec![];
+        let mut special_tokens_mask: Vec<i8> = vec![];
+        let mut offsets: Vec<Option<Offset>> = vec![];
+        let mut original_offsets: Vec<Vec<OffsetSize>> = vec![];
+        let mut mask: Vec<Mask> = vec![];
+        special_tokens_mask.push(1);
+        special_tokens_mask.extend(vec![0; tokens_ids_with_offsets_1.ids.len()]);
+        special_tokens_mask.push(1);
+        token_segment_ids.extend(vec![0; tokens_ids_with_offsets_1.ids.len() + 2]);
+        output.push(self.vocab.token_to_id(DeBERTaV2Vocab::cls_value()));
+        output.extend(tokens_ids_with_offsets_1.ids);
+        output.push(self.vocab.token_to_id(DeBERTaV2Vocab::sep_value()));
+        offsets.push(None);
+        offsets.extend(tokens_ids_with_offsets_1.offsets);
+        offsets.push(None);
+        original_offsets.push(vec![]);
+        original_offsets.extend(tokens_ids_with_offsets_1.reference_offsets);
+        original_offsets.push(vec![]);
+        mask.push(Mask::Special);
+        mask.extend(tokens_ids_with_offsets_1.masks);
+        mask.push(Mask::Special);
+        if let Some(tokens_ids_with_offsets_2_value) = tokens_ids_with_offsets_2 {
+            let length = tokens_ids_with_offsets_2_value.ids.len();
+            special_tokens_mask.extend(vec![0; length]);
+            special_tokens_mask.push(1);
+            token_segment_ids.extend(vec![1; length + 1]);
+            output.extend(tokens_ids_with_offsets_2_value.ids);
+            output.push(self.vocab.token_to_id(DeBERTaV2Vocab::sep_value()));
+            offsets.extend(tokens_ids_with_offsets_2_value.offsets);
+            original_offsets.extend(tokens_ids_with_offsets_2_value.reference_offsets);
+            offsets.push(None);
+            original_offsets.push(vec![]);
+            mask.extend(tokens_ids_with_offsets_2_value.masks);
+            mask.push(Mask::Special);
+        }
+        TokenIdsWithSpecialTokens {
+            token_ids: output,
+            segment_ids: token_segment_ids,
+            special_tokens_mask,
+            token_offsets: offsets,
+            reference_offsets: original_offsets,
+            mask,
+        }
+    }
+}
+
+impl MultiThreadedTokenizer<DeBERTaV2Vocab> for DeBERTaV2Tokenizer {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    fn generate_test_proto_file() -> anyhow::Result<tempfile::TempPath> {
+        let pieces = [
+            "[PAD]",
+            "[CLS]",
+            "[SEP]",
+            "[UNK]",
+            "\u{2581}hello",
+            "\u{2581}world",
+            "\u{2581}",
+            ",",
+        ];
+        let mut proto = ModelProto::new();
+        for piece in pieces.iter() {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(-1.0);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        Ok(model_file.into_temp_path())
+    }
+
+    #[test]
+    fn test_deberta_v2_tokenizer() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file()?;
+        let path = path.to_path_buf();
+        let tokenizer = DeBERTaV2Tokenizer::from_file(path.to_str().unwrap(), true, false)?;
+        let tokenizer_split_punct =
+            DeBERTaV2Tokenizer::from_file(path.to_str().unwrap(), true, true)?;
+
+        //        When & Then
+        assert_eq!(
+            tokenizer.tokenize("Hello, world"),
+            vec!["\u{2581}hello", ",", "\u{2581}world"]
+        );
+        assert_eq!(
+            tokenizer_split_punct.tokenize("Hello, world"),
+            vec!["\u{2581}hello", "\u{2581}", ",", "\u{2581}world"]
+        );
+        assert_eq!(
+            tokenizer_split_punct.tokenize("Hello [MASK] world"),
+            vec!["\u{2581}hello", "[MASK]", "\u{2581}world"]
+        );
+        Ok(())
+    }
+}
@@ -15,7 +15,8 @@
 use crate::error::TokenizerError;
 use crate::tokenizer::constants::UNICODE_TO_BYTES;
 use crate::tokenizer::tokenization_utils::{
-    bpe, fix_mask, split_on_bpe_pairs, split_on_regex_with_lookahead, split_on_special_tokens,
+    bpe, bpe_merge_count, fix_mask, split_on_bpe_pairs, split_on_regex_with_lookahead,
+    split_on_special_tokens,
 };
 use crate::tokenizer::tokenization_utils::{lowercase, BpeCache};
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
@@ -119,6 +120,32 @@ impl Gpt2Tokenizer {
             lower_case,
         }
     }
+
+    /// Returns the number of BPE merges applied to segment a single word, for example to understand
+    /// why some words are encoded as a single token and others as many. The word is segmented as a
+    /// single unit: it is not lower-cased or split by the pre-tokenization. The word is encoded as
+    /// bytes before segmentation and should include its leading space if any (e.g. `" hello"`).
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to segment
+    ///
+    /// # Returns
+    /// - `usize` number of merges applied to the word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::Gpt2Tokenizer;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     Gpt2Tokenizer::from_file("path/to/vocab/file", "path/to/merges/file", lower_case)
+    ///         .unwrap();
+    ///
+    /// let merge_count = tokenizer.merge_count(" hello");
+    /// ```
+    pub fn merge_count(&self, word: &str) -> usize {
+        bpe_merge_count(word, bpe, &self.bpe_ranks, true)
+    }
 }
 
 impl Tokenizer<Gpt2Vocab> for Gpt2Tokenizer {
@@ -160,6 +187,10 @@ impl Tokenizer<Gpt2Vocab> for Gpt2Tokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        split_on_bpe_pairs(word, bpe, &self.bpe_ranks, &self.cache, true)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         let tokens = tokens
             .iter()
@@ -265,6 +296,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_warm_up() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let merges = generate_test_merges();
+        let gpt2_tokenizer: Gpt2Tokenizer =
+            Gpt2Tokenizer::from_existing_vocab_and_merges(vocab, merges, true);
+        assert!(gpt2_tokenizer.cache.read().unwrap().is_empty());
+
+        //        When
+        gpt2_tokenizer.warm_up();
+
+        //        Then
+        assert!(gpt2_tokenizer.cache.read().unwrap().contains_key("hello"));
+    }
+
+    #[test]
+    fn test_merge_count() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let merges = generate_test_merges();
+        let gpt2_tokenizer: Gpt2Tokenizer =
+            Gpt2Tokenizer::from_existing_vocab_and_merges(vocab, merges, true);
+
+        //        When & Then
+        assert_eq!(gpt2_tokenizer.merge_count(""), 0);
+        assert_eq!(gpt2_tokenizer.merge_count("x"), 0);
+        assert_eq!(gpt2_tokenizer.merge_count("the"), 2);
+        assert_eq!(gpt2_tokenizer.merge_count(" the"), 3);
+        assert_eq!(gpt2_tokenizer.merge_count(" earth"), 4);
+    }
+
     #[test]
     fn test_gpt2_tokenizer_no_lower_casing() {
         //        Given
@@ -12,10 +12,12 @@
 
 use crate::error::TokenizerError;
 use crate::tokenizer::base_tokenizer::{
-    Mask, Offset, OffsetSize, Token, TokenIdsWithOffsets, TokenIdsWithSpecialTokens, TokenRef,
+    Mask, Offset, OffsetSize, PaddedBatch, PaddingSide, Token, TokenIdsWithOffsets,
+    TokenIdsWithSpecialTokens, TokenRef,
 };
 use crate::tokenizer::tokenization_utils::{
-    clean_text, decompose_nfkc, is_whitespace, lowercase, split_on_language_code,
+    clean_text, decompose_nfkc, is_whitespace, lowercase, pad_batch, prepend_whitespace_marker,
+    shift_labels, split_on_language_code,
 };
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::{M2M100Vocab, SentencePieceBpeModel, Vocab};
@@ -94,6 +96,70 @@ impl M2M100Tokenizer {
             lower_case,
         }
     }
+
+    /// Pad a batch of decoder inputs (e.g. decoder prefixes for generation) to a common length.
+    /// Sequences are left-padded with the padding token of the vocabulary so that the generated
+    /// tokens are aligned to the right.
+    ///
+    /// # Parameters
+    /// - decoder_inputs (`&[Vec<i64>]`): sequences of decoder token ids to pad
+    ///
+    /// # Returns
+    /// - `PaddedBatch` containing the left-padded token ids and the corresponding attention mask
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::M2M100Tokenizer;
+    /// let lower_case = false;
+    /// let tokenizer = M2M100Tokenizer::from_files(
+    ///     "path/to/vocab/file",
+    ///     "path/to/spiece/model/file",
+    ///     lower_case,
+    /// )
+    /// .unwrap();
+    ///
+    /// let decoder_inputs = vec![vec![2, 128022], vec![2]];
+    /// let padded_decoder_inputs = tokenizer.pad_decoder_inputs(&decoder_inputs);
+    /// ```
+    pub fn pad_decoder_inputs(&self, decoder_inputs: &[Vec<i64>]) -> PaddedBatch {
+        let pad_id = self.vocab.token_to_id(M2M100Vocab::pad_value());
+        pad_batch(decoder_inputs, pad_id, PaddingSide::Left)
+    }
+
+    /// Build the decoder inputs and labels for training from a sequence of target token ids.
+    /// Following the M2M100 convention, the decoder inputs are the targets shifted to the right
+    /// and starting with the end of sequence token. Padding positions of the labels are replaced
+    /// by `-100` so that they are ignored by the loss.
+    ///
+    /// # Parameters
+    /// - target_ids (`&[i64]`): (possibly right-padded) target token ids, including the target language code and end of sequence token
+    ///
+    /// # Returns
+    /// - `(Vec<i64>, Vec<i64>)`: decoder input ids and labels
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{M2M100Tokenizer, Tokenizer, TruncationStrategy};
+    /// let lower_case = false;
+    /// let tokenizer = M2M100Tokenizer::from_files(
+    ///     "path/to/vocab/file",
+    ///     "path/to/spiece/model/file",
+    ///     lower_case,
+    /// )
+    /// .unwrap();
+    ///
+    /// let target_ids = tokenizer
+    ///     .encode(">>fr.<< Bonjour", None, 128, &TruncationStrategy::LongestFirst, 0)
+    ///     .token_ids;
+    /// let (decoder_input_ids, labels) = tokenizer.build_labels(&target_ids);
+    /// ```
+    pub fn build_labels(&self, target_ids: &[i64]) -> (Vec<i64>, Vec<i64>) {
+        let pad_id = self.vocab.token_to_id(M2M100Vocab::pad_value());
+        let eos_id = self.vocab.token_to_id(M2M100Vocab::eos_value());
+        shift_labels(target_ids, pad_id, eos_id)
+    }
 }
 
 impl Tokenizer<M2M100Vocab> for M2M100Tokenizer {
@@ -134,6 +200,12 @@ impl Tokenizer<M2M100Vocab> for M2M100Tokenizer {
         output
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut word = word.to_owned();
+        prepend_whitespace_marker(&mut word);
+        self.model.tokenize_to_tokens(word.as_ref())
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -88,6 +88,82 @@ impl MBart50Tokenizer {
             lower_case,
         }
     }
+
+    /// Builds the encoder input following the MBart language-code suffix scheme: the source
+    /// language code is appended at the end of the sequence, followed by the EOS token
+    /// (`X [lang_code] </s>`). This differs from M2M100 (and from `encode`, which keeps a language
+    /// code provided at the start of the text as a prefix).
+    ///
+    /// # Parameters
+    /// - token_ids (`&[i64]`): ids of the source sequence, without special tokens
+    /// - language_code (`&str`): source language code (e.g. `en_XX` or `>>en<<`)
+    ///
+    /// # Returns
+    /// - `Result<Vec<i64>, TokenizerError>`: encoder input ids, error if the language code is not supported
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{MBart50Tokenizer, Tokenizer};
+    /// let lower_case = false;
+    /// let tokenizer = MBart50Tokenizer::from_file("path/to/vocab/file", lower_case).unwrap();
+    ///
+    /// let token_ids = tokenizer.convert_tokens_to_ids(tokenizer.tokenize("Hello world"));
+    /// let encoder_input = tokenizer.build_encoder_input(&token_ids, "en_XX").unwrap();
+    /// ```
+    pub fn build_encoder_input(
+        &self,
+        token_ids: &[i64],
+        language_code: &str,
+    ) -> Result<Vec<i64>, TokenizerError> {
+        let language_code_id = self.get_language_code_id(language_code)?;
+        let mut output = Vec::with_capacity(token_ids.len() + 2);
+        output.extend_from_slice(token_ids);
+        output.push(language_code_id);
+        output.push(self.vocab.token_to_id(MBart50Vocab::eos_value()));
+        Ok(output)
+    }
+
+    /// Builds the decoder input for a target language: the decoder starts with the EOS token,
+    /// followed by the target language code and the target sequence (`</s> [lang_code] X`).
+    ///
+    /// # Parameters
+    /// - token_ids (`&[i64]`): ids of the target sequence, without special tokens (empty for generation)
+    /// - language_code (`&str`): target language code (e.g. `fr_XX` or `>>fr<<`)
+    ///
+    /// # Returns
+    /// - `Result<Vec<i64>, TokenizerError>`: decoder input ids, error if the language code is not supported
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::MBart50Tokenizer;
+    /// let lower_case = false;
+    /// let tokenizer = MBart50Tokenizer::from_file("path/to/vocab/file", lower_case).unwrap();
+    ///
+    /// let decoder_input = tokenizer.build_decoder_input(&[], "fr_XX").unwrap();
+    /// ```
+    pub fn build_decoder_input(
+        &self,
+        token_ids: &[i64],
+        language_code: &str,
+    ) -> Result<Vec<i64>, TokenizerError> {
+        let language_code_id = self.get_language_code_id(language_code)?;
+        let mut output = Vec::with_capacity(token_ids.len() + 2);
+        output.push(self.vocab.token_to_id(MBart50Vocab::eos_value()));
+        output.push(language_code_id);
+        output.extend_from_slice(token_ids);
+        Ok(output)
+    }
+
+    fn get_language_code_id(&self, language_code: &str) -> Result<i64, TokenizerError> {
+        self.vocab.language_code_id(language_code).ok_or_else(|| {
+            TokenizerError::TokenNotFound(format!(
+                "Language code {} is not supported by the MBart50 vocabulary",
+                language_code
+            ))
+        })
+    }
 }
 
 impl Tokenizer<MBart50Vocab> for MBart50Tokenizer {
@@ -128,6 +204,10 @@ impl Tokenizer<MBart50Vocab> for MBart50Tokenizer {
         output
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.model.tokenize_word(word)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -198,3 +278,75 @@ impl Tokenizer<MBart50Vocab> for MBart50Tokenizer {
 }
 
 impl MultiThreadedTokenizer<MBart50Vocab> for MBart50Tokenizer {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    fn generate_test_tokenizer() -> anyhow::Result<MBart50Tokenizer> {
+        let pieces = ["<unk>", "<s>", "</s>", "\u{2581}hello", "\u{2581}world"];
+        let mut proto = ModelProto::new();
+        for piece in pieces.iter() {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(-1.0);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+        let tokenizer = MBart50Tokenizer::from_file(path.to_path_buf().to_str().unwrap(), false)?;
+        drop(path);
+        Ok(tokenizer)
+    }
+
+    #[test]
+    fn test_build_encoder_input() -> anyhow::Result<()> {
+        //        Given
+        let tokenizer = generate_test_tokenizer()?;
+        let token_ids = tokenizer.convert_tokens_to_ids(tokenizer.tokenize("hello world"));
+        let en_id = tokenizer.vocab.token_to_id(">>en<<");
+        let eos_id = tokenizer.vocab.token_to_id(MBart50Vocab::eos_value());
+
+        //        When
+        let encoder_input = tokenizer.build_encoder_input(&token_ids, "en_XX")?;
+
+        //        Then
+        assert_eq!(token_ids, vec![4, 5]);
+        assert_eq!(encoder_input, vec![4, 5, en_id, eos_id]);
+        assert_eq!(
+            tokenizer.build_encoder_input(&token_ids, ">>en<<")?,
+            encoder_input
+        );
+        assert!(tokenizer.build_encoder_input(&token_ids, "xx_XX").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_decoder_input() -> anyhow::Result<()> {
+        //        Given
+        let tokenizer = generate_test_tokenizer()?;
+        let token_ids = tokenizer.convert_tokens_to_ids(tokenizer.tokenize("hello world"));
+        let fr_id = tokenizer.vocab.token_to_id(">>fr<<");
+        let eos_id = tokenizer.vocab.token_to_id(MBart50Vocab::eos_value());
+
+        //        When
+        let decoder_input = tokenizer.build_decoder_input(&token_ids, "fr_XX")?;
+        let generation_decoder_input = tokenizer.build_decoder_input(&[], "fr_XX")?;
+
+        //        Then
+        assert_eq!(decoder_input, vec![eos_id, fr_id, 4, 5]);
+        assert_eq!(generation_decoder_input, vec![eos_id, fr_id]);
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@
 //! - SentencePiece (Unigram) tokenizers:
 //!     - SentencePiece
 //!     - ALBERT
+//!     - DeBERTa (v2)
 //!     - XLMRoBERTa
 //!     - XLNet
 //!     - T5
@@ -32,44 +33,63 @@
 //!     - Reformer
 //!
 //! All tokenizers are `Send`, `Sync` and support multi-threaded tokenization and encoding.
+//! Generated outputs can be decoded incrementally, one token at a time, using a `StreamDecoder`.
+//! Tokenizers can be wrapped in a `CachedTokenizer` to memoize the tokenization of repeated inputs.
+//! Tokenizers loaded from a single vocabulary file can be created with options by a `TokenizerBuilder`.
+//! `PretrainedTokenizer` loads the tokenizer matching the model type of a pretrained model directory.
 
 mod albert_tokenizer;
 pub(crate) mod base_tokenizer;
 mod bert_tokenizer;
+mod cached_tokenizer;
 mod constants;
 mod ctrl_tokenizer;
+mod deberta_v2_tokenizer;
 mod gpt2_tokenizer;
 mod m2m100_tokenizer;
 mod marian_tokenizer;
 mod mbart50_tokenizer;
 mod openai_gpt_tokenizer;
 mod pegasus_tokenizer;
+mod pretrained_tokenizer;
 mod prophetnet_tokenizer;
 mod reformer_tokenizer;
 mod roberta_tokenizer;
 mod sentence_piece_bpe_tokenizer;
 mod sentence_piece_tokenizer;
+mod stream_decoder;
 mod t5_tokenizer;
 pub(crate) mod tokenization_utils;
+mod tokenizer_builder;
 mod xlm_roberta_tokenizer;
 mod xlnet_tokenizer;
 
 pub use albert_tokenizer::AlbertTokenizer;
-pub use base_tokenizer::{BaseTokenizer, MultiThreadedTokenizer, Tokenizer, TruncationStrategy};
+pub use base_tokenizer::{
+    BaseTokenizer, MultiThreadedTokenizer, PaddedBatch, PaddingSide, Tokenizer, TruncationStrategy,
+};
 pub use bert_tokenizer::BertTokenizer;
+pub use cached_tokenizer::CachedTokenizer;
 pub use ctrl_tokenizer::CtrlTokenizer;
+pub use deberta_v2_tokenizer::DeBERTaV2Tokenizer;
 pub use gpt2_tokenizer::Gpt2Tokenizer;
 pub use m2m100_tokenizer::M2M100Tokenizer;
 pub use marian_tokenizer::MarianTokenizer;
 pub use mbart50_tokenizer::MBart50Tokenizer;
 pub use openai_gpt_tokenizer::OpenAiGptTokenizer;
 pub use pegasus_tokenizer::PegasusTokenizer;
+pub use pretrained_tokenizer::PretrainedTokenizer;
 pub use prophetnet_tokenizer::ProphetNetTokenizer;
 pub use reformer_tokenizer::ReformerTokenizer;
 pub use roberta_tokenizer::RobertaTokenizer;
 pub use sentence_piece_bpe_tokenizer::SentencePieceBpeTokenizer;
 pub use sentence_piece_tokenizer::SentencePieceTokenizer;
+pub use stream_decoder::StreamDecoder;
 pub use t5_tokenizer::T5Tokenizer;
-pub use tokenization_utils::truncate_sequences;
+pub use tokenization_utils::{
+    diff_tokenizations, pad_batch, pad_batch_with_vocab, shift_labels,
+    truncate_keeping_special_tokens, truncate_sequences,
+};
+pub use tokenizer_builder::{BuildableTokenizer, ConfiguredTokenizer, TokenizerBuilder};
 pub use xlm_roberta_tokenizer::XLMRobertaTokenizer;
 pub use xlnet_tokenizer::XLNetTokenizer;
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::tokenizer::tokenization_utils::{openai_gpt_bpe, split_on_bpe_pairs, BpeCache};
+use crate::tokenizer::tokenization_utils::{
+    bpe_merge_count, openai_gpt_bpe, split_on_bpe_pairs, BpeCache,
+};
 use crate::tokenizer::{BaseTokenizer, MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::bpe_vocab::BpePairVocab;
 use crate::vocab::{OpenAiGptVocab, Vocab};
@@ -99,6 +101,31 @@ impl OpenAiGptTokenizer {
             cache,
         }
     }
+
+    /// Returns the number of BPE merges applied to segment a single word, for example to understand
+    /// why some words are encoded as a single token and others as many. The word is segmented as a
+    /// single unit: it is not lower-cased or split by the pre-tokenization.
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to segment
+    ///
+    /// # Returns
+    /// - `usize` number of merges applied to the word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::OpenAiGptTokenizer;
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     OpenAiGptTokenizer::from_file("path/to/vocab/file", "path/to/merges/file", lower_case)
+    ///         .unwrap();
+    ///
+    /// let merge_count = tokenizer.merge_count("hello");
+    /// ```
+    pub fn merge_count(&self, word: &str) -> usize {
+        bpe_merge_count(word, openai_gpt_bpe, &self.bpe_ranks, false)
+    }
 }
 
 impl Tokenizer<OpenAiGptVocab> for OpenAiGptTokenizer {
@@ -130,6 +157,10 @@ impl Tokenizer<OpenAiGptVocab> for OpenAiGptTokenizer {
         tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        split_on_bpe_pairs(word, openai_gpt_bpe, &self.bpe_ranks, &self.cache, false)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens.join("").replace("</w>", " ").trim().to_owned()
     }
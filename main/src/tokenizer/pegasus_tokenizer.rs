@@ -142,6 +142,12 @@ impl Tokenizer<PegasusVocab> for PegasusTokenizer {
         output
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut output = self.model.tokenize_word(word);
+        self.model.populate_masks(output.as_mut_slice(), '\u{2581}');
+        output
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -0,0 +1,437 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::tokenizer::{
+    AlbertTokenizer, BertTokenizer, CtrlTokenizer, DeBERTaV2Tokenizer, Gpt2Tokenizer,
+    M2M100Tokenizer, MBart50Tokenizer, MarianTokenizer, OpenAiGptTokenizer, PegasusTokenizer,
+    ProphetNetTokenizer, ReformerTokenizer, RobertaTokenizer, T5Tokenizer, Tokenizer,
+    TruncationStrategy, XLMRobertaTokenizer, XLNetTokenizer,
+};
+use crate::vocab::pretrained_vocab::{normalize_model_type, read_json_file, read_model_type};
+use crate::{TokenizedInput, TokensWithOffsets};
+use serde_json::Value;
+use std::path::Path;
+
+/// # PretrainedTokenizer
+/// Tokenizer loaded from a pretrained model directory (as distributed by the Transformers library),
+/// wrapping the concrete tokenizer matching the model type. The model type is read from the
+/// `tokenizer_class` of `tokenizer_config.json` if available, and from the `model_type` of
+/// `config.json` otherwise. The lower casing and accent stripping options are read from
+/// `tokenizer_config.json` (`do_lower_case`, `strip_accents` or `keep_accents`), falling back to the
+/// defaults of the model type. The files expected for the model type are then loaded from the same
+/// directory:
+/// - ALBERT, Pegasus, Reformer, T5, XLNet: `spiece.model`
+/// - BERT, DistilBERT: `vocab.txt`
+/// - DeBERTa (v2): `spm.model`
+/// - GPT, GPT2, CTRL, RoBERTa: `vocab.json` and `merges.txt`
+/// - M2M100: `vocab.json` and `sentencepiece.bpe.model`
+/// - Marian: `vocab.json` and `source.spm`
+/// - MBart50, XLMRoBERTa: `sentencepiece.bpe.model`
+/// - ProphetNet: `prophetnet.tokenizer`
+#[allow(clippy::upper_case_acronyms)]
+pub enum PretrainedTokenizer {
+    /// ALBERT tokenizer
+    Albert(Box<AlbertTokenizer>),
+    /// BERT (and DistilBERT) tokenizer
+    Bert(Box<BertTokenizer>),
+    /// CTRL tokenizer
+    Ctrl(Box<CtrlTokenizer>),
+    /// DeBERTa (v2) tokenizer
+    DeBERTaV2(Box<DeBERTaV2Tokenizer>),
+    /// GPT2 tokenizer
+    Gpt2(Box<Gpt2Tokenizer>),
+    /// M2M100 tokenizer
+    M2M100(Box<M2M100Tokenizer>),
+    /// Marian tokenizer
+    Marian(Box<MarianTokenizer>),
+    /// MBart50 tokenizer
+    MBart50(Box<MBart50Tokenizer>),
+    /// GPT tokenizer
+    OpenAiGpt(Box<OpenAiGptTokenizer>),
+    /// Pegasus tokenizer
+    Pegasus(Box<PegasusTokenizer>),
+    /// ProphetNet tokenizer
+    ProphetNet(Box<ProphetNetTokenizer>),
+    /// Reformer tokenizer
+    Reformer(Box<ReformerTokenizer>),
+    /// RoBERTa tokenizer
+    Roberta(Box<RobertaTokenizer>),
+    /// T5 tokenizer
+    T5(Box<T5Tokenizer>),
+    /// XLMRoBERTa tokenizer
+    XLMRoberta(Box<XLMRobertaTokenizer>),
+    /// XLNet tokenizer
+    XLNet(Box<XLNetTokenizer>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $tokenizer:ident => $body:expr) => {
+        match $self {
+            PretrainedTokenizer::Albert($tokenizer) => $body,
+            PretrainedTokenizer::Bert($tokenizer) => $body,
+            PretrainedTokenizer::Ctrl($tokenizer) => $body,
+            PretrainedTokenizer::DeBERTaV2($tokenizer) => $body,
+            PretrainedTokenizer::Gpt2($tokenizer) => $body,
+            PretrainedTokenizer::M2M100($tokenizer) => $body,
+            PretrainedTokenizer::Marian($tokenizer) => $body,
+            PretrainedTokenizer::MBart50($tokenizer) => $body,
+            PretrainedTokenizer::OpenAiGpt($tokenizer) => $body,
+            PretrainedTokenizer::Pegasus($tokenizer) => $body,
+            PretrainedTokenizer::ProphetNet($tokenizer) => $body,
+            PretrainedTokenizer::Reformer($tokenizer) => $body,
+            PretrainedTokenizer::Roberta($tokenizer) => $body,
+            PretrainedTokenizer::T5($tokenizer) => $body,
+            PretrainedTokenizer::XLMRoberta($tokenizer) => $body,
+            PretrainedTokenizer::XLNet($tokenizer) => $body,
+        }
+    };
+}
+
+impl PretrainedTokenizer {
+    /// Create a new instance of a `PretrainedTokenizer` from a pretrained model directory.
+    ///
+    /// # Parameters
+    /// - path (`&Path`): path to the directory containing the configuration and vocabulary files
+    ///
+    /// # Returns
+    /// - `Result<PretrainedTokenizer, TokenizerError>`: tokenizer matching the model type, or an error if the model type can not be determined, is not supported, or if the tokenizer files can not be loaded
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::PretrainedTokenizer;
+    /// let tokenizer = PretrainedTokenizer::from_pretrained("path/to/model/directory").unwrap();
+    /// let tokens = tokenizer.tokenize("Hello, world!");
+    /// ```
+    pub fn from_pretrained<P: AsRef<Path>>(path: P) -> Result<PretrainedTokenizer, TokenizerError> {
+        let path = path.as_ref();
+        let model_type = normalize_model_type(&read_model_type(path)?);
+        let tokenizer_config = read_json_file(&path.join("tokenizer_config.json"))?;
+        let config_flag = |name: &str| -> Option<bool> {
+            tokenizer_config
+                .as_ref()
+                .and_then(|config| config.get(name))
+                .and_then(Value::as_bool)
+        };
+        let lower_case = config_flag("do_lower_case").unwrap_or(matches!(
+            model_type.as_str(),
+            "albert" | "bert" | "distilbert" | "prophetnet"
+        ));
+        let strip_accents = config_flag("strip_accents")
+            .or_else(|| config_flag("keep_accents").map(|keep_accents| !keep_accents))
+            .unwrap_or(match model_type.as_str() {
+                "albert" | "xlnet" => true,
+                _ => lower_case,
+            });
+        let file_path = |file_name: &str| -> Result<String, TokenizerError> {
+            path.join(file_name)
+                .to_str()
+                .map(|value| value.to_owned())
+                .ok_or_else(|| {
+                    TokenizerError::FileNotFound(format!(
+                        "invalid tokenizer file path for {}",
+                        file_name
+                    ))
+                })
+        };
+        Ok(match model_type.as_str() {
+            "albert" => PretrainedTokenizer::Albert(Box::new(AlbertTokenizer::from_file(
+                &file_path("spiece.model")?,
+                lower_case,
+                strip_accents,
+            )?)),
+            "bert" | "distilbert" => PretrainedTokenizer::Bert(Box::new(BertTokenizer::from_file(
+                &file_path("vocab.txt")?,
+                lower_case,
+                strip_accents,
+            )?)),
+            "ctrl" => PretrainedTokenizer::Ctrl(Box::new(CtrlTokenizer::from_file(
+                &file_path("vocab.json")?,
+                &file_path("merges.txt")?,
+                lower_case,
+            )?)),
+            "debertav2" => PretrainedTokenizer::DeBERTaV2(Box::new(DeBERTaV2Tokenizer::from_file(
+                &file_path("spm.model")?,
+                lower_case,
+                config_flag("split_by_punct").unwrap_or(false),
+            )?)),
+            "gpt2" => PretrainedTokenizer::Gpt2(Box::new(Gpt2Tokenizer::from_file(
+                &file_path("vocab.json")?,
+                &file_path("merges.txt")?,
+                lower_case,
+            )?)),
+            "m2m100" => PretrainedTokenizer::M2M100(Box::new(M2M100Tokenizer::from_files(
+                &file_path("vocab.json")?,
+                &file_path("sentencepiece.bpe.model")?,
+                lower_case,
+            )?)),
+            "marian" => PretrainedTokenizer::Marian(Box::new(MarianTokenizer::from_files(
+                &file_path("vocab.json")?,
+                &file_path("source.spm")?,
+                lower_case,
+            )?)),
+            "mbart50" => PretrainedTokenizer::MBart50(Box::new(MBart50Tokenizer::from_file(
+                &file_path("sentencepiece.bpe.model")?,
+                lower_case,
+            )?)),
+            "openaigpt" => PretrainedTokenizer::OpenAiGpt(Box::new(OpenAiGptTokenizer::from_file(
+                &file_path("vocab.json")?,
+                &file_path("merges.txt")?,
+                lower_case,
+            )?)),
+            "pegasus" => PretrainedTokenizer::Pegasus(Box::new(PegasusTokenizer::from_file(
+                &file_path("spiece.model")?,
+                lower_case,
+            )?)),
+            "prophetnet" => {
+                PretrainedTokenizer::ProphetNet(Box::new(ProphetNetTokenizer::from_file(
+                    &file_path("prophetnet.tokenizer")?,
+                    lower_case,
+                    strip_accents,
+                )?))
+            }
+            "reformer" => PretrainedTokenizer::Reformer(Box::new(ReformerTokenizer::from_file(
+                &file_path("spiece.model")?,
+                lower_case,
+            )?)),
+            "roberta" => PretrainedTokenizer::Roberta(Box::new(RobertaTokenizer::from_file(
+                &file_path("vocab.json")?,
+                &file_path("merges.txt")?,
+                lower_case,
+                config_flag("add_prefix_space").unwrap_or(false),
+            )?)),
+            "t5" => PretrainedTokenizer::T5(Box::new(T5Tokenizer::from_file(
+                &file_path("spiece.model")?,
+                lower_case,
+            )?)),
+            "xlmroberta" => PretrainedTokenizer::XLMRoberta(Box::new(
+                XLMRobertaTokenizer::from_file(&file_path("sentencepiece.bpe.model")?, lower_case)?,
+            )),
+            "xlnet" => PretrainedTokenizer::XLNet(Box::new(XLNetTokenizer::from_file(
+                &file_path("spiece.model")?,
+                lower_case,
+                strip_accents,
+            )?)),
+            _ => {
+                return Err(TokenizerError::ValueError(format!(
+                    "Unsupported model type: {}",
+                    model_type
+                )));
+            }
+        })
+    }
+
+    /// Tokenize a string using the wrapped tokenizer, returns a vector of tokens as strings.
+    ///
+    /// # Parameters
+    /// - text : text (string-like) to tokenize
+    ///
+    /// # Returns
+    /// `Vec<String>` containing the tokens string representation
+    pub fn tokenize<S: AsRef<str>>(&self, text: S) -> Vec<String> {
+        dispatch!(self, tokenizer => tokenizer.tokenize(text))
+    }
+
+    /// Tokenize a string using the wrapped tokenizer, returning tokens with offset information
+    ///
+    /// # Parameters
+    /// - text : text (string-like) to tokenize
+    ///
+    /// # Returns
+    /// `TokensWithOffsets` with the tokens and their offset information
+    pub fn tokenize_with_offsets<S: AsRef<str>>(&self, text: S) -> TokensWithOffsets {
+        dispatch!(self, tokenizer => tokenizer.tokenize_with_offsets(text))
+    }
+
+    /// Tokenize a list of strings using the wrapped tokenizer, returning a vector of tokens per input
+    ///
+    /// # Parameters
+    /// - text_list: list of strings to tokenize
+    ///
+    /// # Returns
+    /// `Vec<Vec<String>>` with the token strings representation
+    pub fn tokenize_list<S, ST>(&self, text_list: S) -> Vec<Vec<String>>
+    where
+        S: AsRef<[ST]>,
+        ST: AsRef<str>,
+    {
+        dispatch!(self, tokenizer => tokenizer.tokenize_list(text_list))
+    }
+
+    /// Convert a slice of string-like to a vector of token indices using the wrapped tokenizer
+    ///
+    /// # Parameters
+    /// - tokens: list of token string-like to convert to ids
+    ///
+    /// # Returns
+    /// `Vec<i64>` with the token indices
+    pub fn convert_tokens_to_ids<S, ST>(&self, tokens: S) -> Vec<i64>
+    where
+        S: AsRef<[ST]>,
+        ST: AsRef<str>,
+    {
+        dispatch!(self, tokenizer => tokenizer.convert_tokens_to_ids(tokens))
+    }
+
+    /// Encode a string-like (tokenization followed by encoding) using the wrapped tokenizer
+    ///
+    /// # Parameters
+    /// - text_1: input text (string-like) to encode
+    /// - text_2: optional additional input text (string-like) to encode. When provided, both texts are combined into a single encoding by using the `build_input_with_special_tokens` method.
+    /// - max_len (`usize`): maximum combined sequence length. If the combined encoding would exceed this max_len, the encoding is truncated following the `TruncationStrategy` provided.
+    /// - truncation_strategy (`&TruncationStrategy`): strategy to follow for the truncation, if required
+    /// - stride (`usize`): amount of tokens to shift the input by if truncation is required (allowing for the generation of overlapping sequences with overflowing tokens)
+    ///
+    /// # Returns
+    /// `TokenizedInput` containing the encoding output (token indices, token types, segment ids,
+    /// overflowing tokens and special token mask)
+    pub fn encode<S: AsRef<str>>(
+        &self,
+        text_1: S,
+        text_2: Option<S>,
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> TokenizedInput {
+        dispatch!(self, tokenizer => tokenizer.encode(text_1, text_2, max_len, truncation_strategy, stride))
+    }
+
+    /// Encode a sequence of string-like texts using the wrapped tokenizer
+    ///
+    /// # Parameters
+    /// - text_list: sequence of input text (`&str`) to encode
+    /// - max_len (`usize`): maximum combined sequence length
+    /// - truncation_strategy (`&TruncationStrategy`): strategy to follow for the truncation, if required
+    /// - stride (`usize`): amount of tokens to shift the input by if truncation is required
+    ///
+    /// # Returns
+    /// `Vec<TokenizedInput>` containing the encoding output for each input
+    pub fn encode_list<S, ST>(
+        &self,
+        text_list: S,
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> Vec<TokenizedInput>
+    where
+        S: AsRef<[ST]>,
+        ST: AsRef<str>,
+    {
+        dispatch!(self, tokenizer => tokenizer.encode_list(text_list, max_len, truncation_strategy, stride))
+    }
+
+    /// Decode a sequence of token indices to a string using the wrapped tokenizer
+    ///
+    /// # Parameters
+    /// - token_ids (`Vec<i64>`): tokens to decode
+    /// - skip_special_tokens (`bool`): flag indicating if special tokens should be included in the output
+    /// - clean_up_tokenization_spaces (`bool`): flag indicating if a clean-up of the tokenization spaces should be performed
+    ///
+    /// # Returns
+    /// - `String`: decoded sentence
+    pub fn decode(
+        &self,
+        token_ids: Vec<i64>,
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> String {
+        dispatch!(self, tokenizer => tokenizer.decode(token_ids, skip_special_tokens, clean_up_tokenization_spaces))
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn test_create_object_from_pretrained() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut config_file = File::create(model_dir.path().join("config.json"))?;
+        write!(config_file, "{{\"model_type\": \"bert\"}}")?;
+        let mut vocab_file = File::create(model_dir.path().join("vocab.txt"))?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n ! \n [CLS] \n [SEP] \n [MASK] \n [PAD]"
+        )?;
+
+        //        When
+        let tokenizer = PretrainedTokenizer::from_pretrained(model_dir.path())?;
+
+        //        Then
+        assert!(matches!(tokenizer, PretrainedTokenizer::Bert(_)));
+        assert_eq!(
+            tokenizer.tokenize("Hello World!"),
+            vec!["hello", "world", "!"]
+        );
+        assert_eq!(
+            tokenizer
+                .encode("Hello", None, 128, &TruncationStrategy::LongestFirst, 0)
+                .token_ids,
+            vec![4, 0, 5]
+        );
+        assert_eq!(
+            tokenizer.decode(vec![4, 0, 1, 5], true, true),
+            "hello world"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenizer_config_options() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut tokenizer_config_file =
+            File::create(model_dir.path().join("tokenizer_config.json"))?;
+        write!(
+            tokenizer_config_file,
+            "{{\"tokenizer_class\": \"BertTokenizer\", \"do_lower_case\": false}}"
+        )?;
+        let mut vocab_file = File::create(model_dir.path().join("vocab.txt"))?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n ! \n [CLS] \n [SEP] \n [MASK] \n [PAD]"
+        )?;
+
+        //        When
+        let tokenizer = PretrainedTokenizer::from_pretrained(model_dir.path())?;
+
+        //        Then
+        assert_eq!(
+            tokenizer.tokenize("Hello world!"),
+            vec!["[UNK]", "world", "!"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_model_type() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut config_file = File::create(model_dir.path().join("config.json"))?;
+        write!(config_file, "{{\"model_type\": \"fnet\"}}")?;
+
+        //        When
+        let tokenizer = PretrainedTokenizer::from_pretrained(model_dir.path());
+
+        //        Then
+        assert!(matches!(tokenizer, Err(TokenizerError::ValueError(_))));
+        Ok(())
+    }
+}
@@ -16,7 +16,9 @@ use crate::tokenizer::base_tokenizer::{
     BaseTokenizer, Mask, MultiThreadedTokenizer, Offset, OffsetSize, Token, TokenIdsWithOffsets,
     TokenIdsWithSpecialTokens, TokenRef, Tokenizer,
 };
-use crate::tokenizer::tokenization_utils::tokenize_wordpiece;
+use crate::tokenizer::tokenization_utils::{
+    count_wordpiece, tokenize_wordpiece, DEFAULT_MAX_INPUT_CHARS_PER_WORD,
+};
 use crate::vocab::{ProphetNetVocab, Vocab};
 
 /// # ProphetNet tokenizer
@@ -26,6 +28,7 @@ use crate::vocab::{ProphetNetVocab, Vocab};
 pub struct ProphetNetTokenizer {
     vocab: ProphetNetVocab,
     base_tokenizer: BaseTokenizer<ProphetNetVocab>,
+    max_input_chars_per_word: usize,
 }
 
 impl ProphetNetTokenizer {
@@ -57,6 +60,7 @@ impl ProphetNetTokenizer {
         Ok(ProphetNetTokenizer {
             vocab,
             base_tokenizer,
+            max_input_chars_per_word: DEFAULT_MAX_INPUT_CHARS_PER_WORD,
         })
     }
 
@@ -88,8 +92,35 @@ impl ProphetNetTokenizer {
         ProphetNetTokenizer {
             vocab,
             base_tokenizer,
+            max_input_chars_per_word: DEFAULT_MAX_INPUT_CHARS_PER_WORD,
         }
     }
+
+    /// Sets the maximum number of characters of a word segmented into word pieces. Longer words
+    /// are mapped to the unknown token without being segmented, bounding the tokenization cost of
+    /// pathological inputs. Defaults to 100 characters, matching the reference WordPiece
+    /// implementation.
+    ///
+    /// # Parameters
+    /// - max_input_chars_per_word (`usize`): maximum number of characters of a word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{ProphetNetTokenizer, Tokenizer};
+    /// let strip_accents = false;
+    /// let lower_case = false;
+    /// let tokenizer = ProphetNetTokenizer::from_file("path/to/vocab/file", lower_case, strip_accents)
+    ///     .unwrap()
+    ///     .with_max_input_chars_per_word(200);
+    /// ```
+    pub fn with_max_input_chars_per_word(
+        mut self,
+        max_input_chars_per_word: usize,
+    ) -> ProphetNetTokenizer {
+        self.max_input_chars_per_word = max_input_chars_per_word;
+        self
+    }
 }
 
 impl Tokenizer<ProphetNetVocab> for ProphetNetTokenizer {
@@ -102,11 +133,25 @@ impl Tokenizer<ProphetNetVocab> for ProphetNetTokenizer {
         self.base_tokenizer
             .tokenize_to_tokens(initial_token)
             .into_iter()
-            .map(|token| tokenize_wordpiece(token.as_ref(), &self.vocab, 100))
+            .map(|token| {
+                tokenize_wordpiece(token.as_ref(), &self.vocab, self.max_input_chars_per_word)
+            })
             .flatten()
             .collect()
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        tokenize_wordpiece(word, &self.vocab, self.max_input_chars_per_word)
+    }
+
+    fn count_tokens(&self, initial_token: TokenRef) -> usize {
+        self.base_tokenizer
+            .tokenize_to_tokens(initial_token)
+            .iter()
+            .map(|token| count_wordpiece(&token.text, &self.vocab, self.max_input_chars_per_word))
+            .sum()
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens.join(" ").replace(" ##", "").trim().to_owned()
     }
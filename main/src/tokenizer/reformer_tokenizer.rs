@@ -14,8 +14,8 @@
 use crate::error::TokenizerError;
 use crate::tokenizer::base_tokenizer::{Token, TokenRef};
 use crate::tokenizer::tokenization_utils::{
-    bpe, clean_text, decompose_nfkc, fix_mask, is_whitespace, lowercase, split_on_bpe_pairs,
-    split_on_special_tokens, whitespace_tokenize, BpeCache,
+    bpe, clean_text, decompose_nfkc, fix_mask, is_whitespace, lowercase, prepend_whitespace_marker,
+    split_on_bpe_pairs, split_on_special_tokens, whitespace_tokenize, BpeCache,
 };
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::{BpePairVocab, ReformerVocab, Vocab};
@@ -127,6 +127,12 @@ impl Tokenizer<ReformerVocab> for ReformerTokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut word = word.to_owned();
+        prepend_whitespace_marker(&mut word);
+        split_on_bpe_pairs(word.as_ref(), bpe, &self.bpe_ranks, &self.cache, false)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -19,8 +19,8 @@ use crate::tokenizer::base_tokenizer::{
 };
 use crate::tokenizer::constants::UNICODE_TO_BYTES;
 use crate::tokenizer::tokenization_utils::{
-    bpe, fix_mask, is_whitespace, split_on_bpe_pairs, split_on_regex_with_lookahead,
-    split_on_special_tokens,
+    bpe, bpe_merge_count, fix_mask, is_whitespace, split_on_bpe_pairs,
+    split_on_regex_with_lookahead, split_on_special_tokens,
 };
 use crate::tokenizer::tokenization_utils::{lowercase, BpeCache};
 use crate::tokenizer::MultiThreadedTokenizer;
@@ -140,6 +140,37 @@ impl RobertaTokenizer {
             add_prefix_space,
         }
     }
+
+    /// Returns the number of BPE merges applied to segment a single word, for example to understand
+    /// why some words are encoded as a single token and others as many. The word is segmented as a
+    /// single unit: it is not lower-cased or split by the pre-tokenization. The word is encoded as
+    /// bytes before segmentation and should include its leading space if any (e.g. `" hello"`).
+    ///
+    /// # Parameters
+    /// - word (`&str`): word to segment
+    ///
+    /// # Returns
+    /// - `usize` number of merges applied to the word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::RobertaTokenizer;
+    /// let lower_case = false;
+    /// let add_prefix_space = true;
+    /// let tokenizer = RobertaTokenizer::from_file(
+    ///     "path/to/vocab/file",
+    ///     "path/to/merges/file",
+    ///     lower_case,
+    ///     add_prefix_space,
+    /// )
+    /// .unwrap();
+    ///
+    /// let merge_count = tokenizer.merge_count(" hello");
+    /// ```
+    pub fn merge_count(&self, word: &str) -> usize {
+        bpe_merge_count(word, bpe, &self.bpe_ranks, true)
+    }
 }
 
 impl Tokenizer<RobertaVocab> for RobertaTokenizer {
@@ -189,6 +220,10 @@ impl Tokenizer<RobertaVocab> for RobertaTokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        split_on_bpe_pairs(word, bpe, &self.bpe_ranks, &self.cache, true)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         let tokens = tokens
             .iter()
@@ -12,7 +12,9 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::tokenizer::tokenization_utils::{clean_text, decompose_nfkc, is_whitespace, lowercase};
+use crate::tokenizer::tokenization_utils::{
+    clean_text, decompose_nfkc, is_whitespace, lowercase, prepend_whitespace_marker,
+};
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::{SentencePieceBpeModel, SentencePieceVocab, Vocab};
 use crate::{Token, TokenRef};
@@ -110,6 +112,12 @@ impl Tokenizer<SentencePieceVocab> for SentencePieceBpeTokenizer {
         self.model.tokenize_to_tokens(token.as_ref())
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut word = word.to_owned();
+        prepend_whitespace_marker(&mut word);
+        self.model.tokenize_to_tokens(word.as_ref())
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
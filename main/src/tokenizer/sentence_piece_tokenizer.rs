@@ -12,7 +12,7 @@
 
 use crate::error::TokenizerError;
 use crate::tokenizer::base_tokenizer::{Token, TokenRef};
-use crate::tokenizer::tokenization_utils::{clean_text, lowercase};
+use crate::tokenizer::tokenization_utils::{clean_text, lowercase, normalize_line_endings};
 use crate::tokenizer::tokenization_utils::{decompose_nfkc, is_whitespace};
 use crate::tokenizer::{MultiThreadedTokenizer, Tokenizer};
 use crate::vocab::{SentencePieceModel, SentencePieceVocab, Vocab};
@@ -27,6 +27,7 @@ pub struct SentencePieceTokenizer {
     model: SentencePieceModel,
     vocab: SentencePieceVocab,
     lower_case: bool,
+    normalize_line_endings: bool,
 }
 
 impl SentencePieceTokenizer {
@@ -55,6 +56,7 @@ impl SentencePieceTokenizer {
             model,
             vocab,
             lower_case,
+            normalize_line_endings: false,
         })
     }
 
@@ -86,17 +88,43 @@ impl SentencePieceTokenizer {
             model,
             vocab,
             lower_case,
+            normalize_line_endings: false,
         }
     }
-}
 
-impl Tokenizer<SentencePieceVocab> for SentencePieceTokenizer {
-    fn vocab(&self) -> &SentencePieceVocab {
-        &self.vocab
+    /// Enables (or disables) the normalization of line endings before tokenization: Windows
+    /// (`\r\n`) and classic Mac OS (`\r`) line endings are converted to `\n`, so that texts
+    /// produce the same tokens regardless of the platform they originate from. This is disabled by
+    /// default. When enabled, the `\r` of `\r\n` pairs is dropped and the token offsets skip it:
+    /// offsets still refer to positions in the original text.
+    ///
+    /// # Parameters
+    /// - normalize_line_endings (`bool`): flag indicating if line endings should be normalized
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{SentencePieceTokenizer, Tokenizer};
+    /// let lower_case = false;
+    /// let tokenizer = SentencePieceTokenizer::from_file("path/to/vocab/file", lower_case)
+    ///     .unwrap()
+    ///     .with_line_ending_normalization(true);
+    /// ```
+    pub fn with_line_ending_normalization(
+        mut self,
+        normalize_line_endings: bool,
+    ) -> SentencePieceTokenizer {
+        self.normalize_line_endings = normalize_line_endings;
+        self
     }
 
-    fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token> {
+    /// Normalizes the text and replaces the whitespaces with the SentencePiece whitespace marker,
+    /// prepended to the text if missing
+    fn normalize(&self, text: TokenRef) -> Token {
         let mut token = text.to_owned();
+        if self.normalize_line_endings {
+            normalize_line_endings(&mut token);
+        }
         clean_text(&mut token, true);
         decompose_nfkc(&mut token);
         if self.lower_case {
@@ -107,11 +135,30 @@ impl Tokenizer<SentencePieceVocab> for SentencePieceTokenizer {
             token.text.insert(0, '\u{2581}');
             token.reference_offsets.insert(0, 0);
         };
+        token
+    }
+}
+
+impl Tokenizer<SentencePieceVocab> for SentencePieceTokenizer {
+    fn vocab(&self) -> &SentencePieceVocab {
+        &self.vocab
+    }
+
+    fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token> {
+        let token = self.normalize(text);
         let output = self.model.decode_forward_token_ref(token.as_ref());
         let decoded = self.model.decode_backward(&output);
         self.model.parse_nodes_to_tokens(decoded)
     }
 
+    fn count_tokens(&self, text: TokenRef) -> usize {
+        self.model.count_pieces(self.normalize(text).as_ref())
+    }
+
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.model.tokenize_word(word)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -122,3 +169,67 @@ impl Tokenizer<SentencePieceVocab> for SentencePieceTokenizer {
 }
 
 impl MultiThreadedTokenizer<SentencePieceVocab> for SentencePieceTokenizer {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use crate::Offset;
+    use protobuf::Message;
+    use std::io::Write;
+
+    #[test]
+    fn test_normalize_line_endings() -> anyhow::Result<()> {
+        //        Given
+        let pieces = [
+            "<unk>",
+            "<s>",
+            "</s>",
+            "\u{2581}hello",
+            "\u{2581}world",
+            "\u{2581}",
+        ];
+        let mut proto = ModelProto::new();
+        for piece in pieces.iter() {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(-1.0);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+        let path = path.to_path_buf();
+        let tokenizer = SentencePieceTokenizer::from_file(path.to_str().unwrap(), false)?;
+        let normalizing_tokenizer =
+            SentencePieceTokenizer::from_file(path.to_str().unwrap(), false)?
+                .with_line_ending_normalization(true);
+
+        //        When
+        let tokens = tokenizer.tokenize("hello\r\nworld");
+        let normalized_tokens = normalizing_tokenizer.tokenize_with_offsets("hello\r\nworld");
+
+        //        Then
+        assert_eq!(tokens, vec!["\u{2581}hello", "\u{2581}", "\u{2581}world"]);
+        assert_eq!(
+            normalized_tokens.tokens,
+            vec!["\u{2581}hello", "\u{2581}world"]
+        );
+        assert_eq!(
+            normalized_tokens.tokens,
+            normalizing_tokenizer.tokenize("hello\nworld")
+        );
+        assert_eq!(
+            normalized_tokens.offsets,
+            vec![Some(Offset::new(0, 5)), Some(Offset::new(6, 12))]
+        );
+        Ok(())
+    }
+}
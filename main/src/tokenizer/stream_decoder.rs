@@ -0,0 +1,229 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tokenizer::Tokenizer;
+use crate::vocab::Vocab;
+use std::marker::PhantomData;
+
+/// # StreamDecoder
+/// Incremental decoder for text generated one token at a time. Each call to `push` returns only
+/// the text finalized by the new token, so that partial outputs can be rendered as they are
+/// generated.
+///
+/// Decoding a token in isolation is not sufficient: tokenizers drop the leading whitespace of the
+/// decoded text (e.g. the SentencePiece meta symbol `▁` of the first token) and byte-level tokens
+/// may only hold part of a multi-byte character. The decoder therefore decodes a short window made
+/// of the previously emitted token(s) and the pending tokens, and emits the text extending the
+/// decoded window. Text ending with an incomplete character (decoded as `U+FFFD`) is buffered until
+/// the following tokens complete it. Tokens are dropped from the window once emitted, keeping the
+/// cost of each `push` independent of the length of the generated sequence.
+pub struct StreamDecoder<'a, T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    tokenizer: &'a T,
+    skip_special_tokens: bool,
+    token_ids: Vec<i64>,
+    prefix_offset: usize,
+    read_offset: usize,
+    _vocab: PhantomData<V>,
+}
+
+impl<'a, T, V> StreamDecoder<'a, T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    /// Create a new `StreamDecoder` for a tokenizer
+    ///
+    /// # Parameters
+    /// - tokenizer (`&T`): tokenizer used to decode the token ids
+    /// - skip_special_tokens (`bool`): flag indicating if special tokens should be excluded from the output
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{Gpt2Tokenizer, StreamDecoder};
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     Gpt2Tokenizer::from_file("path/to/vocab/file", "path/to/merges/file", lower_case)
+    ///         .unwrap();
+    ///
+    /// let mut stream_decoder = StreamDecoder::new(&tokenizer, true);
+    /// ```
+    pub fn new(tokenizer: &'a T, skip_special_tokens: bool) -> StreamDecoder<'a, T, V> {
+        StreamDecoder {
+            tokenizer,
+            skip_special_tokens,
+            token_ids: vec![],
+            prefix_offset: 0,
+            read_offset: 0,
+            _vocab: PhantomData,
+        }
+    }
+
+    /// Add a newly generated token id to the decoder
+    ///
+    /// # Parameters
+    /// - id (`i64`): generated token id
+    ///
+    /// # Returns
+    /// - `Option<String>` text finalized by the token, `None` if the token is buffered (or does not add any text)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{Gpt2Tokenizer, StreamDecoder};
+    /// let lower_case = false;
+    /// let tokenizer =
+    ///     Gpt2Tokenizer::from_file("path/to/vocab/file", "path/to/merges/file", lower_case)
+    ///         .unwrap();
+    /// let mut stream_decoder = StreamDecoder::new(&tokenizer, true);
+    ///
+    /// for id in vec![31373, 995] {
+    ///     if let Some(text) = stream_decoder.push(id) {
+    ///         print!("{}", text);
+    ///     }
+    /// }
+    /// if let Some(text) = stream_decoder.flush() {
+    ///     print!("{}", text);
+    /// }
+    /// ```
+    pub fn push(&mut self, id: i64) -> Option<String> {
+        self.token_ids.push(id);
+        let prefix_text = self.decode_window(self.read_offset);
+        let new_text = self.decode_window(self.token_ids.len());
+        if new_text.len() > prefix_text.len() && !new_text.ends_with('\u{FFFD}') {
+            let text = new_text.get(prefix_text.len()..)?.to_owned();
+            self.prefix_offset = self.read_offset;
+            self.read_offset = self.token_ids.len();
+            self.token_ids.drain(..self.prefix_offset);
+            self.read_offset -= self.prefix_offset;
+            self.prefix_offset = 0;
+            Some(text)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the text of the buffered tokens, including incomplete characters, and resets the
+    /// decoder. This should be called once the generation is complete.
+    ///
+    /// # Returns
+    /// - `Option<String>` text of the tokens buffered since the last emitted text, `None` if no text is pending
+    pub fn flush(&mut self) -> Option<String> {
+        let prefix_text = self.decode_window(self.read_offset);
+        let new_text = self.decode_window(self.token_ids.len());
+        self.token_ids.clear();
+        self.prefix_offset = 0;
+        self.read_offset = 0;
+        new_text
+            .get(prefix_text.len()..)
+            .filter(|text| !text.is_empty())
+            .map(|text| text.to_owned())
+    }
+
+    fn decode_window(&self, end: usize) -> String {
+        if end <= self.prefix_offset {
+            return String::new();
+        }
+        self.tokenizer.decode(
+            self.token_ids[self.prefix_offset..end].to_vec(),
+            self.skip_special_tokens,
+            false,
+        )
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::Gpt2Tokenizer;
+    use crate::vocab::base_vocab::swap_key_values;
+    use crate::vocab::{BpePairVocab, Gpt2Vocab};
+    use std::collections::HashMap;
+
+    fn generate_test_tokenizer() -> Gpt2Tokenizer {
+        // `é` is encoded as the bytes 0xC3 0xA9, represented by `Ã` and `©` in the byte-level vocabulary
+        let values: HashMap<String, i64> = [
+            ("hello".to_owned(), 0),
+            ("\u{0120}world".to_owned(), 1),
+            ("\u{00C3}".to_owned(), 2),
+            ("\u{00A9}".to_owned(), 3),
+            ("<|endoftext|>".to_owned(), 4),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let special_values: HashMap<String, i64> =
+            [("<|endoftext|>".to_owned(), 4)].iter().cloned().collect();
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+        let vocab = Gpt2Vocab {
+            values,
+            indices,
+            unknown_value: "<|endoftext|>",
+            special_values,
+            special_indices,
+        };
+        let merges = BpePairVocab {
+            values: HashMap::new(),
+        };
+        Gpt2Tokenizer::from_existing_vocab_and_merges(vocab, merges, false)
+    }
+
+    #[test]
+    fn test_stream_decoder() {
+        //        Given
+        let tokenizer = generate_test_tokenizer();
+        let mut stream_decoder = StreamDecoder::new(&tokenizer, true);
+
+        //        When
+        let outputs = [0, 1, 2, 3, 4]
+            .iter()
+            .map(|id| stream_decoder.push(*id))
+            .collect::<Vec<Option<String>>>();
+
+        //        Then
+        assert_eq!(
+            outputs,
+            vec![
+                Some("hello".to_owned()),
+                Some(" world".to_owned()),
+                None,
+                Some("\u{00E9}".to_owned()),
+                None
+            ]
+        );
+        assert_eq!(stream_decoder.flush(), None);
+    }
+
+    #[test]
+    fn test_stream_decoder_flush_incomplete_character() {
+        //        Given
+        let tokenizer = generate_test_tokenizer();
+        let mut stream_decoder = StreamDecoder::new(&tokenizer, true);
+
+        //        When
+        let first_output = stream_decoder.push(0);
+        let second_output = stream_decoder.push(2);
+        let flushed = stream_decoder.flush();
+
+        //        Then
+        assert_eq!(first_output, Some("hello".to_owned()));
+        assert_eq!(second_output, None);
+        assert_eq!(flushed, Some("\u{FFFD}".to_owned()));
+    }
+}
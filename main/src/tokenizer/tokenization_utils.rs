@@ -13,13 +13,15 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::tokenizer::base_tokenizer::{TokenIdsWithOffsets, TruncationStrategy};
+use crate::tokenizer::base_tokenizer::{
+    PaddedBatch, PaddingSide, TokenIdsWithOffsets, TruncationStrategy,
+};
 use crate::tokenizer::constants::{
     ACCENT_MARKERS, ADDITIONAL_WHITESPACE_CHARS, BYTES_TO_UNICODE, CONTROL_CHARS,
     PUNCTUATION_CHARS, WHITESPACE_CHARS,
 };
 use crate::vocab::bpe_vocab::{BpePairRef, BpePairVocab};
-use crate::vocab::{BertVocab, Vocab};
+use crate::vocab::{Script, Vocab};
 use crate::{Mask, Offset, OffsetSize, Token, TokenRef};
 use regex::Regex;
 use std::borrow::BorrowMut;
@@ -58,6 +60,38 @@ pub fn clean_text(token: &mut Token, strict: bool) {
     token.offset.end = *token.reference_offsets.last().unwrap_or(&(0)) + 1;
 }
 
+/// Normalizes line endings by converting Windows (`\r\n`) and classic Mac OS (`\r`) line endings
+/// to `\n`. The reference offsets keep pointing to the original text: the `\r` of a `\r\n` pair
+/// is dropped (the text becomes shorter than the original) while a lone `\r` maps to its original
+/// position. `Token::alignments` can be used to map the normalized text back to the original text.
+pub fn normalize_line_endings(token: &mut Token) {
+    if !token.text.contains('\r') {
+        return;
+    }
+    let mut normalized_string = String::with_capacity(token.text.len());
+    let mut character_mapping: Vec<OffsetSize> = Vec::with_capacity(token.reference_offsets.len());
+    let mut characters = token
+        .text
+        .chars()
+        .zip(token.reference_offsets.iter())
+        .peekable();
+    while let Some((character, position)) = characters.next() {
+        if character == '\r' {
+            if let Some(('\n', _)) = characters.peek() {
+                continue;
+            }
+            normalized_string.push('\n');
+        } else {
+            normalized_string.push(character);
+        }
+        character_mapping.push(*position);
+    }
+    token.text = normalized_string;
+    token.reference_offsets = character_mapping;
+    token.offset.begin = *token.reference_offsets.first().unwrap_or(&(0));
+    token.offset.end = *token.reference_offsets.last().unwrap_or(&(0)) + 1;
+}
+
 /// Replaces a pattern &str by a replacement &str keeping track of the offsets
 /// (all new characters in replacement have the same reference offset as the first pattern character as these may have a different size)
 pub fn replace_string(token: &mut Token, pattern: &str, replacement_string: &str) {
@@ -110,7 +144,7 @@ pub fn tokenize_cjk_chars(token: TokenRef) -> Vec<TokenRef> {
     split_on_char(token, is_cjk_char, true, Mask::CJK)
 }
 
-fn is_cjk_char(character: &char) -> bool {
+pub(crate) fn is_cjk_char(character: &char) -> bool {
     let u32_char = *character as u32;
     (0x4E00..=0x9FFF).contains(&u32_char)
         | (0x3400..=0x4DBF).contains(&u32_char)
@@ -214,7 +248,13 @@ pub fn decompose_nfkc(token: &mut Token) {
     let mut cur_position: isize = 0;
     for (character, extra_char) in token.text.nfkc() {
         decomposed_string.push(character);
-        character_mapping.push(token.reference_offsets[cur_position as usize]);
+        // Characters inserted by the decomposition refer to the previous original character
+        let reference_position = if extra_char > 0 {
+            (cur_position - 1).max(0)
+        } else {
+            cur_position
+        };
+        character_mapping.push(token.reference_offsets[reference_position as usize]);
         cur_position = cur_position + 1 - extra_char;
     }
     token.text = decomposed_string;
@@ -223,6 +263,15 @@ pub fn decompose_nfkc(token: &mut Token) {
     token.offset.end = *token.reference_offsets.last().unwrap_or(&(0)) + 1;
 }
 
+///Prepend the SentencePiece whitespace marker (`▁`) to a word if missing, referring to the first character of the word
+pub(crate) fn prepend_whitespace_marker(token: &mut Token) {
+    if !token.text.starts_with('\u{2581}') {
+        token.text.insert(0, '\u{2581}');
+        let first_offset = token.reference_offsets.first().copied().unwrap_or(0);
+        token.reference_offsets.insert(0, first_offset);
+    }
+}
+
 ///Split a token on punctuation
 pub fn split_on_punct(token: TokenRef) -> Vec<TokenRef> {
     split_on_char(token, is_punctuation, true, Mask::Punctuation)
@@ -511,13 +560,29 @@ where
     tokens
 }
 
+/// Default maximum number of characters of a word segmented by WordPiece, longer words being
+/// mapped to the unknown token (as in the reference WordPiece implementation)
+pub const DEFAULT_MAX_INPUT_CHARS_PER_WORD: usize = 100;
+
+/// Returns the unknown token of a word that cannot be segmented, resolved by the vocabulary from the
+/// first character of the word specific to a script (see `Vocab::unknown_id_for_char`)
+fn unknown_token_for_word(word: &str, vocab: &impl Vocab) -> String {
+    match word
+        .chars()
+        .find(|character| Script::of(*character) != Script::Common)
+    {
+        Some(character) => vocab.id_to_token(&vocab.unknown_id_for_char(character)),
+        None => vocab.get_unknown_value().to_owned(),
+    }
+}
+
 ///Tokenize a token into word pieces according to the supplied vocabulary
 ///Continuation word pieces will all have the suffix `##`
 pub fn tokenize_wordpiece(token: TokenRef, vocab: &impl Vocab, max_word_len: usize) -> Vec<Token> {
     let mut tokens: Vec<Token> = Vec::new();
     if token.text.chars().count() > max_word_len {
         tokens.push(Token {
-            text: BertVocab::unknown_value().to_owned(),
+            text: unknown_token_for_word(token.text, vocab),
             offset: token.offset,
             reference_offsets: token.reference_offsets.to_vec(),
             mask: Mask::Unknown,
@@ -566,7 +631,7 @@ pub fn tokenize_wordpiece(token: TokenRef, vocab: &impl Vocab, max_word_len: usi
             }
             if is_unk {
                 return vec![Token {
-                    text: BertVocab::unknown_value().to_owned(),
+                    text: unknown_token_for_word(token.text, vocab),
                     offset: token.offset,
                     reference_offsets: token.reference_offsets.to_vec(),
                     mask: Mask::Unknown,
@@ -583,6 +648,38 @@ pub fn tokenize_wordpiece(token: TokenRef, vocab: &impl Vocab, max_word_len: usi
     tokens
 }
 
+///Count the word pieces a token is split into by `tokenize_wordpiece`, without building the
+///tokens. Words longer than `max_word_len` characters or that cannot be split into word pieces
+///count as a single unknown token.
+pub fn count_wordpiece(token: &str, vocab: &impl Vocab, max_word_len: usize) -> usize {
+    if token.chars().count() > max_word_len {
+        return 1;
+    }
+    let mut candidate = String::with_capacity(token.len() + 2);
+    let mut count = 0;
+    let mut start = 0;
+    while start < token.len() {
+        let mut end = token.len();
+        loop {
+            candidate.clear();
+            if start > 0 {
+                candidate.push_str("##");
+            }
+            candidate.push_str(&token[start..end]);
+            if vocab.values().contains_key(&candidate) {
+                break;
+            }
+            match token[start..end].char_indices().last() {
+                Some((last_char_start, _)) if last_char_start > 0 => end = start + last_char_start,
+                _ => return 1,
+            }
+        }
+        count += 1;
+        start = end;
+    }
+    count
+}
+
 /// # Truncates a sequence pair in place to the maximum length.
 ///
 ///   * tokens_1: list of tokenized input ids. Can be obtained from a string by chaining the
@@ -805,6 +902,217 @@ fn truncate_with_overflow(
     (overflow_tokens, overflow_offsets)
 }
 
+/// # Truncate a sequence of token ids including its special tokens, keeping the special tokens at its ends
+/// Unlike `truncate_sequences`, which truncates sequences before the special tokens are added, this
+/// truncates a sequence already built with its special tokens. The special tokens at the start
+/// (e.g. `[CLS]` or a language prefix) and at the end (e.g. `[SEP]` or `</s>`) of the sequence are
+/// kept, and tokens are removed from the end of the content between them.
+///
+/// # Parameters
+/// - ids (`&mut Vec<i64>`): sequence of token ids to truncate in place
+/// - max_len (`usize`): maximum length of the sequence
+/// - vocab (`&impl Vocab`): vocabulary used to identify the special tokens
+///
+/// # Returns
+/// - `Result<Vec<i64>, TokenizerError>`: removed (overflowing) token ids, error if the special tokens at the ends of the sequence exceed the maximum length
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_tokenizers::tokenizer::truncate_keeping_special_tokens;
+/// use rust_tokenizers::vocab::{BertVocab, Vocab};
+/// let vocab = BertVocab::from_file("path/to/vocab/file").unwrap();
+/// let mut ids = vec![101, 7592, 2088, 999, 102];
+///
+/// let overflow_ids = truncate_keeping_special_tokens(&mut ids, 4, &vocab).unwrap();
+/// ```
+pub fn truncate_keeping_special_tokens(
+    ids: &mut Vec<i64>,
+    max_len: usize,
+    vocab: &impl Vocab,
+) -> Result<Vec<i64>, TokenizerError> {
+    if ids.len() <= max_len {
+        return Ok(Vec::new());
+    }
+    let is_special = |id: &&i64| vocab.special_indices().contains_key(*id);
+    let num_leading = ids.iter().take_while(is_special).count();
+    let num_trailing = ids[num_leading..]
+        .iter()
+        .rev()
+        .take_while(is_special)
+        .count();
+    if num_leading + num_trailing > max_len {
+        return Err(TokenizerError::ValueError(format!(
+            "The sequence contains {} special tokens at its ends, exceeding the maximum length {}",
+            num_leading + num_trailing,
+            max_len
+        )));
+    }
+    let content_end = ids.len() - num_trailing;
+    let truncation_start = max_len - num_trailing;
+    Ok(ids.drain(truncation_start..content_end).collect())
+}
+
+/// # Pad a batch of sequences of token ids to the length of the longest sequence
+///
+/// # Parameters
+/// - sequences (`&[Vec<i64>]`): sequences of token ids to pad
+/// - pad_id (`i64`): id of the padding token
+/// - padding_side (`PaddingSide`): side on which the padding tokens are inserted
+///
+/// # Returns
+/// - `PaddedBatch` containing the padded token ids and the corresponding attention mask
+///
+/// # Example
+///
+/// ```
+/// use rust_tokenizers::tokenizer::{pad_batch, PaddingSide};
+/// let sequences = vec![vec![5, 6, 7], vec![8]];
+///
+/// let padded_batch = pad_batch(&sequences, 0, PaddingSide::Left);
+/// assert_eq!(padded_batch.token_ids, vec![vec![5, 6, 7], vec![0, 0, 8]]);
+/// assert_eq!(padded_batch.attention_mask, vec![vec![1, 1, 1], vec![0, 0, 1]]);
+/// ```
+pub fn pad_batch(sequences: &[Vec<i64>], pad_id: i64, padding_side: PaddingSide) -> PaddedBatch {
+    let max_len = sequences
+        .iter()
+        .map(|sequence| sequence.len())
+        .max()
+        .unwrap_or(0);
+    pad_batch_to_length(sequences, pad_id, padding_side, max_len)
+}
+
+/// # Pad a batch of sequences of token ids with the padding token of a vocabulary
+/// The sequences are padded to `max_len` if provided, to the length of the longest sequence
+/// otherwise. Sequences are not truncated (see `truncate_keeping_special_tokens`).
+///
+/// # Parameters
+/// - sequences (`&[Vec<i64>]`): sequences of token ids to pad
+/// - vocab (`&impl Vocab`): vocabulary providing the padding token id (`Vocab::pad_id`)
+/// - max_len (`Option<usize>`): length of the padded sequences, defaults to the length of the longest sequence
+/// - padding_side (`PaddingSide`): side on which the padding tokens are inserted
+///
+/// # Returns
+/// - `Result<PaddedBatch, TokenizerError>`: padded token ids and the corresponding attention mask, `TokenizerError::ValueError` if the vocabulary does not define a padding token or if a sequence is longer than `max_len`
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_tokenizers::tokenizer::{pad_batch_with_vocab, PaddingSide};
+/// use rust_tokenizers::vocab::{BertVocab, Vocab};
+/// let vocab = BertVocab::from_file("path/to/vocab/file").unwrap();
+/// let sequences = vec![vec![101, 7592, 102], vec![101, 102]];
+///
+/// let padded_batch =
+///     pad_batch_with_vocab(&sequences, &vocab, Some(8), PaddingSide::Right).unwrap();
+/// ```
+pub fn pad_batch_with_vocab(
+    sequences: &[Vec<i64>],
+    vocab: &impl Vocab,
+    max_len: Option<usize>,
+    padding_side: PaddingSide,
+) -> Result<PaddedBatch, TokenizerError> {
+    let pad_id = vocab.pad_id().ok_or_else(|| {
+        TokenizerError::ValueError("The vocabulary does not define a padding token".to_string())
+    })?;
+    let longest = sequences
+        .iter()
+        .map(|sequence| sequence.len())
+        .max()
+        .unwrap_or(0);
+    let padded_len = match max_len {
+        Some(max_len) if max_len < longest => {
+            return Err(TokenizerError::ValueError(format!(
+                "The batch contains a sequence of {} tokens, exceeding the maximum length {}",
+                longest, max_len
+            )));
+        }
+        Some(max_len) => max_len,
+        None => longest,
+    };
+    Ok(pad_batch_to_length(
+        sequences,
+        pad_id,
+        padding_side,
+        padded_len,
+    ))
+}
+
+fn pad_batch_to_length(
+    sequences: &[Vec<i64>],
+    pad_id: i64,
+    padding_side: PaddingSide,
+    max_len: usize,
+) -> PaddedBatch {
+    let mut token_ids = Vec::with_capacity(sequences.len());
+    let mut attention_mask = Vec::with_capacity(sequences.len());
+    for sequence in sequences {
+        let padding_len = max_len - sequence.len();
+        let mut padded_ids = Vec::with_capacity(max_len);
+        let mut mask = Vec::with_capacity(max_len);
+        match padding_side {
+            PaddingSide::Left => {
+                padded_ids.extend(vec![pad_id; padding_len]);
+                padded_ids.extend(sequence);
+                mask.extend(vec![0; padding_len]);
+                mask.extend(vec![1; sequence.len()]);
+            }
+            PaddingSide::Right => {
+                padded_ids.extend(sequence);
+                padded_ids.extend(vec![pad_id; padding_len]);
+                mask.extend(vec![1; sequence.len()]);
+                mask.extend(vec![0; padding_len]);
+            }
+        }
+        token_ids.push(padded_ids);
+        attention_mask.push(mask);
+    }
+    PaddedBatch {
+        token_ids,
+        attention_mask,
+    }
+}
+
+/// # Build decoder inputs and labels from a sequence of target token ids
+/// The decoder inputs are the targets shifted one position to the right, starting with the
+/// decoder start token. The labels are the targets, with padding positions replaced by `-100`
+/// so that they are ignored by the loss.
+///
+/// # Parameters
+/// - target_ids (`&[i64]`): (possibly right-padded) target token ids
+/// - pad_id (`i64`): id of the padding token
+/// - decoder_start_id (`i64`): id of the token starting the decoder inputs
+///
+/// # Returns
+/// - `(Vec<i64>, Vec<i64>)`: decoder input ids and labels, both with the length of the targets
+///
+/// # Example
+///
+/// ```
+/// use rust_tokenizers::tokenizer::shift_labels;
+/// let target_ids = vec![128_022, 71, 12, 2, 1];
+///
+/// let (decoder_input_ids, labels) = shift_labels(&target_ids, 1, 2);
+/// assert_eq!(decoder_input_ids, vec![2, 128_022, 71, 12, 2]);
+/// assert_eq!(labels, vec![128_022, 71, 12, 2, -100]);
+/// ```
+pub fn shift_labels(
+    target_ids: &[i64],
+    pad_id: i64,
+    decoder_start_id: i64,
+) -> (Vec<i64>, Vec<i64>) {
+    let mut decoder_input_ids = Vec::with_capacity(target_ids.len());
+    if !target_ids.is_empty() {
+        decoder_input_ids.push(decoder_start_id);
+        decoder_input_ids.extend_from_slice(&target_ids[..target_ids.len() - 1]);
+    }
+    let labels = target_ids
+        .iter()
+        .map(|&id| if id == pad_id { -100 } else { id })
+        .collect();
+    (decoder_input_ids, labels)
+}
+
 pub fn get_pairs(token: &[String]) -> Option<HashSet<BpePairRef>> {
     match token.len() {
         0 | 1 => None,
@@ -950,6 +1258,33 @@ pub fn bpe(token: &str, bpe_ranks: &BpePairVocab) -> (Vec<String>, Vec<usize>) {
     (output.0, char_counts)
 }
 
+/// Returns the number of merges applied by a BPE function to segment a single word. Each merge
+/// combines two adjacent symbols, the count is therefore the difference between the number of
+/// initial symbols (characters, or bytes if `as_bytes` is set) and the number of output tokens.
+pub fn bpe_merge_count<F>(
+    word: &str,
+    bpe_function: F,
+    bpe_ranks: &BpePairVocab,
+    as_bytes: bool,
+) -> usize
+where
+    F: Fn(&str, &BpePairVocab) -> (Vec<String>, Vec<usize>),
+{
+    let text: String = if as_bytes {
+        word.as_bytes()
+            .iter()
+            .map(|v| BYTES_TO_UNICODE.get(v).unwrap())
+            .collect()
+    } else {
+        word.to_owned()
+    };
+    if text.is_empty() {
+        return 0;
+    }
+    let (tokens, _) = bpe_function(&text, bpe_ranks);
+    text.chars().count().saturating_sub(tokens.len())
+}
+
 fn bytes_offsets(text: &str) -> Vec<usize> {
     let mut offsets = Vec::with_capacity(text.len());
     for (char_idx, character) in text.chars().enumerate() {
@@ -1071,6 +1406,74 @@ pub fn fix_mask(tokens: &mut Vec<Token>) {
     }
 }
 
+/// # Aligned difference between two tokenizations
+/// Compares two token sequences (e.g. the output of a tokenizer and of a reference implementation)
+/// and returns the positions where they differ, using a longest common subsequence alignment.
+/// Tokens removed from the first sequence and inserted in the second sequence between two common
+/// tokens are paired as substitutions, the remaining ones are returned with a `None` counterpart.
+/// This has a quadratic complexity in the length of the sequences and is intended for debugging.
+///
+/// # Parameters
+/// - a (`&[String]`): first token sequence
+/// - b (`&[String]`): second token sequence
+///
+/// # Returns
+/// - `Vec<(usize, Option<String>, Option<String>)>`: position in the first sequence, token of the first sequence and token of the second sequence for each difference. Tokens inserted at the end of the first sequence are reported at position `a.len()`.
+///
+/// # Example
+///
+/// ```
+/// use rust_tokenizers::tokenizer::diff_tokenizations;
+/// let a = vec!["\u{2581}hello".to_string(), ",".to_string(), "\u{2581}world".to_string()];
+/// let b = vec!["\u{2581}hello".to_string(), "\u{2581}world".to_string()];
+///
+/// let diff = diff_tokenizations(&a, &b);
+/// assert_eq!(diff, vec![(1, Some(",".to_string()), None)]);
+/// ```
+pub fn diff_tokenizations(
+    a: &[String],
+    b: &[String],
+) -> Vec<(usize, Option<String>, Option<String>)> {
+    let mut lcs_lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs_lengths[i][j] = if a[i] == b[j] {
+                lcs_lengths[i + 1][j + 1] + 1
+            } else {
+                lcs_lengths[i + 1][j].max(lcs_lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut differences = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let (gap_start_a, gap_start_b) = (i, j);
+        while (i < a.len() || j < b.len()) && !(i < a.len() && j < b.len() && a[i] == b[j]) {
+            if j >= b.len() || (i < a.len() && lcs_lengths[i + 1][j] >= lcs_lengths[i][j + 1]) {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        let removed = &a[gap_start_a..i];
+        let inserted = &b[gap_start_b..j];
+        for position in 0..removed.len().max(inserted.len()) {
+            differences.push((
+                (gap_start_a + position).min(i),
+                removed.get(position).cloned(),
+                inserted.get(position).cloned(),
+            ));
+        }
+    }
+    differences
+}
+
 pub(crate) fn split_on_language_code<'a>(
     token: TokenRef<'a>,
     code_length: usize,
@@ -1135,6 +1538,7 @@ mod tests {
     use super::*;
     use crate::error::TokenizerError;
     use crate::vocab::base_vocab::swap_key_values;
+    use crate::vocab::{BaseVocab, BertVocab};
     use std::collections::HashMap;
 
     fn generate_test_vocab() -> BertVocab {
@@ -1178,6 +1582,7 @@ mod tests {
             unknown_value: "[UNK]",
             special_values,
             special_indices,
+            do_lower_case: false,
         }
     }
 
@@ -1218,6 +1623,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_normalize_line_endings() {
+        //        Given
+        let test_tuples = [
+            ("No line break.", "No line break.", vec![]),
+            ("Windows\r\nline", "Windows\nline", vec![8]),
+            ("Mac\rline", "Mac\nline", vec![3]),
+            ("Mixed\r\r\n\n", "Mixed\n\n\n", vec![5, 7, 8]),
+        ];
+
+        //        When & Then
+        for (source_text, expected_result, expected_line_break_offsets) in test_tuples.iter() {
+            let mut token = Token::new(source_text.to_string());
+            normalize_line_endings(&mut token);
+            let line_break_offsets = token
+                .text
+                .chars()
+                .zip(token.reference_offsets.iter())
+                .filter(|(character, _)| *character == '\n')
+                .map(|(_, position)| *position)
+                .collect::<Vec<OffsetSize>>();
+            assert_eq!(token.text, *expected_result);
+            assert_eq!(token.reference_offsets.len(), token.text.chars().count());
+            assert_eq!(line_break_offsets, *expected_line_break_offsets);
+        }
+    }
+
     #[test]
     fn test_split_on_special_tokens() {
         //        Given
@@ -1737,6 +2169,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_truncate_keeping_special_tokens() {
+        //        Given
+        let vocab = generate_test_vocab();
+        let mut ids = vec![4, 0, 1, 3, 0, 1, 3, 0, 1, 5];
+        let mut short_ids = vec![4, 0, 5];
+        let mut special_ids = vec![4, 4, 5, 5];
+
+        //        When
+        let overflow_ids = truncate_keeping_special_tokens(&mut ids, 5, &vocab).unwrap();
+        let short_overflow_ids =
+            truncate_keeping_special_tokens(&mut short_ids, 5, &vocab).unwrap();
+        let special_ids_result = truncate_keeping_special_tokens(&mut special_ids, 3, &vocab);
+
+        //        Then
+        assert_eq!(ids, vec![4, 0, 1, 3, 5]);
+        assert_eq!(overflow_ids, vec![0, 1, 3, 0, 1]);
+        assert_eq!(short_ids, vec![4, 0, 5]);
+        assert!(short_overflow_ids.is_empty());
+        assert!(matches!(
+            special_ids_result,
+            Err(TokenizerError::ValueError(_))
+        ));
+        assert_eq!(special_ids, vec![4, 4, 5, 5]);
+    }
+
     #[test]
     fn test_truncate_single_sentence() {
         //        Given
@@ -2431,6 +2889,146 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pad_batch() {
+        //        Given
+        let decoder_prefixes = vec![vec![2, 128_022], vec![2, 128_022, 71, 12], vec![2]];
+        let pad_id = 1;
+
+        //        When
+        let left_padded = pad_batch(&decoder_prefixes, pad_id, PaddingSide::Left);
+        let right_padded = pad_batch(&decoder_prefixes, pad_id, PaddingSide::Right);
+
+        //        Then
+        assert_eq!(
+            left_padded.token_ids,
+            vec![
+                vec![1, 1, 2, 128_022],
+                vec![2, 128_022, 71, 12],
+                vec![1, 1, 1, 2]
+            ]
+        );
+        assert_eq!(
+            left_padded.attention_mask,
+            vec![vec![0, 0, 1, 1], vec![1, 1, 1, 1], vec![0, 0, 0, 1]]
+        );
+        assert_eq!(
+            right_padded.token_ids,
+            vec![
+                vec![2, 128_022, 1, 1],
+                vec![2, 128_022, 71, 12],
+                vec![2, 1, 1, 1]
+            ]
+        );
+        assert_eq!(
+            right_padded.attention_mask,
+            vec![vec![1, 1, 0, 0], vec![1, 1, 1, 1], vec![1, 0, 0, 0]]
+        );
+        assert_eq!(
+            pad_batch(&[], pad_id, PaddingSide::Left),
+            PaddedBatch {
+                token_ids: vec![],
+                attention_mask: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn test_pad_batch_with_vocab() -> anyhow::Result<()> {
+        //        Given
+        let vocab = generate_test_vocab();
+        let no_pad_vocab = BaseVocab::from_reader("hello \n [UNK]".as_bytes())?;
+        let sequences = vec![vec![4, 0, 5], vec![4, 5]];
+
+        //        When
+        let longest_padded = pad_batch_with_vocab(&sequences, &vocab, None, PaddingSide::Right)?;
+        let max_len_padded = pad_batch_with_vocab(&sequences, &vocab, Some(5), PaddingSide::Left)?;
+
+        //        Then
+        assert_eq!(
+            longest_padded.token_ids,
+            vec![vec![4, 0, 5], vec![4, 5, 10]]
+        );
+        assert_eq!(
+            longest_padded.attention_mask,
+            vec![vec![1, 1, 1], vec![1, 1, 0]]
+        );
+        assert_eq!(
+            max_len_padded.token_ids,
+            vec![vec![10, 10, 4, 0, 5], vec![10, 10, 10, 4, 5]]
+        );
+        assert_eq!(
+            max_len_padded.attention_mask,
+            vec![vec![0, 0, 1, 1, 1], vec![0, 0, 0, 1, 1]]
+        );
+        assert!(matches!(
+            pad_batch_with_vocab(&sequences, &vocab, Some(2), PaddingSide::Right),
+            Err(TokenizerError::ValueError(_))
+        ));
+        assert!(matches!(
+            pad_batch_with_vocab(&sequences, &no_pad_vocab, None, PaddingSide::Right),
+            Err(TokenizerError::ValueError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shift_labels() {
+        //        Given
+        let pad_id = 1;
+        let eos_id = 2;
+        let target_ids = vec![128_022, 71, 12, eos_id, pad_id, pad_id];
+
+        //        When
+        let (decoder_input_ids, labels) = shift_labels(&target_ids, pad_id, eos_id);
+
+        //        Then
+        assert_eq!(decoder_input_ids, vec![2, 128_022, 71, 12, 2, 1]);
+        assert_eq!(labels, vec![128_022, 71, 12, 2, -100, -100]);
+        assert_eq!(shift_labels(&[], pad_id, eos_id), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_diff_tokenizations() {
+        //        Given
+        let to_strings =
+            |tokens: &[&str]| -> Vec<String> { tokens.iter().map(|v| v.to_string()).collect() };
+        let test_tuples: [(&[&str], &[&str], Vec<(usize, Option<&str>, Option<&str>)>); 6] = [
+            (&["a", "b", "c"], &["a", "b", "c"], vec![]),
+            (&["a", "b", "c"], &["a", "c"], vec![(1, Some("b"), None)]),
+            (&["a", "c"], &["a", "b", "c"], vec![(1, None, Some("b"))]),
+            (
+                &["hello", "wor", "##ld", "!"],
+                &["hello", "world", "!"],
+                vec![(1, Some("wor"), Some("world")), (2, Some("##ld"), None)],
+            ),
+            (
+                &["a"],
+                &["a", "b", "c"],
+                vec![(1, None, Some("b")), (1, None, Some("c"))],
+            ),
+            (&[], &["a"], vec![(0, None, Some("a"))]),
+        ];
+
+        //        When & Then
+        for (a, b, expected_result) in test_tuples.iter() {
+            let expected_result: Vec<(usize, Option<String>, Option<String>)> = expected_result
+                .iter()
+                .map(|(position, a, b)| {
+                    (
+                        *position,
+                        a.map(|v| v.to_string()),
+                        b.map(|v| v.to_string()),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                diff_tokenizations(&to_strings(a), &to_strings(b)),
+                expected_result
+            );
+        }
+    }
+
     #[test]
     fn test_get_pair() {
         //        Given
@@ -0,0 +1,418 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::tokenizer::base_tokenizer::{TokenIdsWithOffsets, TokenIdsWithSpecialTokens};
+use crate::tokenizer::tokenization_utils::pad_batch_with_vocab;
+use crate::tokenizer::{
+    AlbertTokenizer, BaseTokenizer, BertTokenizer, MultiThreadedTokenizer, PaddedBatch,
+    PaddingSide, ProphetNetTokenizer, Tokenizer, TruncationStrategy, XLNetTokenizer,
+};
+use crate::vocab::{
+    AlbertVocab, ProphetNetVocab, SentencePieceModel, Vocab, VocabBuilder, VocabMut, XLNetVocab,
+};
+use crate::{Token, TokenRef};
+use std::marker::PhantomData;
+
+/// # Tokenizer buildable by the `TokenizerBuilder`
+/// Tokenizers created from a single vocabulary file, with lower casing and accent stripping options.
+pub trait BuildableTokenizer<V: Vocab>: Tokenizer<V> + Sized {
+    /// Create the tokenizer from a vocabulary already loaded from a file. The path is used to read
+    /// additional resources stored in the same file (e.g. SentencePiece models).
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the vocabulary file
+    /// - vocab (`V`): vocabulary read from the file
+    /// - lower_case (`bool`): flag indicating if the text should be lower-cased as part of the tokenization
+    /// - strip_accents (`bool`): flag indicating if accents should be stripped from the text
+    fn from_file_with_vocab(
+        path: &str,
+        vocab: V,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError>;
+}
+
+impl<V: Vocab + Sync + Send> BuildableTokenizer<V> for BaseTokenizer<V> {
+    fn from_file_with_vocab(
+        _path: &str,
+        vocab: V,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError> {
+        Ok(BaseTokenizer::from_existing_vocab(
+            vocab,
+            lower_case,
+            strip_accents,
+        ))
+    }
+}
+
+impl<V: Vocab + Clone + Sync + Send> BuildableTokenizer<V> for BertTokenizer<V> {
+    fn from_file_with_vocab(
+        _path: &str,
+        vocab: V,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError> {
+        Ok(BertTokenizer::from_existing_vocab(
+            vocab,
+            lower_case,
+            strip_accents,
+        ))
+    }
+}
+
+impl BuildableTokenizer<ProphetNetVocab> for ProphetNetTokenizer {
+    fn from_file_with_vocab(
+        _path: &str,
+        vocab: ProphetNetVocab,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError> {
+        Ok(ProphetNetTokenizer::from_existing_vocab(
+            vocab,
+            lower_case,
+            strip_accents,
+        ))
+    }
+}
+
+impl BuildableTokenizer<AlbertVocab> for AlbertTokenizer {
+    fn from_file_with_vocab(
+        path: &str,
+        vocab: AlbertVocab,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError> {
+        let model = SentencePieceModel::from_file(path)?;
+        Ok(AlbertTokenizer::from_existing_vocab_and_model(
+            vocab,
+            model,
+            lower_case,
+            strip_accents,
+        ))
+    }
+}
+
+impl BuildableTokenizer<XLNetVocab> for XLNetTokenizer {
+    fn from_file_with_vocab(
+        path: &str,
+        vocab: XLNetVocab,
+        lower_case: bool,
+        strip_accents: bool,
+    ) -> Result<Self, TokenizerError> {
+        let model = SentencePieceModel::from_file(path)?;
+        Ok(XLNetTokenizer::from_existing_vocab_and_model(
+            vocab,
+            model,
+            lower_case,
+            strip_accents,
+        ))
+    }
+}
+
+/// # TokenizerBuilder
+/// Builder creating a tokenizer from a vocabulary file with a set of options. Options are set with
+/// chainable setters, and the vocabulary and tokenizer are loaded by `build`:
+/// - lower casing and accent stripping are passed to the tokenizer
+/// - additional special tokens are registered in the vocabulary
+/// - the maximum length and padding side are applied by `ConfiguredTokenizer::encode_batch`
+#[derive(Debug, Clone)]
+pub struct TokenizerBuilder {
+    lower_case: bool,
+    strip_accents: bool,
+    max_length: Option<usize>,
+    padding_side: PaddingSide,
+    special_tokens: Vec<String>,
+}
+
+impl Default for TokenizerBuilder {
+    fn default() -> Self {
+        TokenizerBuilder {
+            lower_case: false,
+            strip_accents: false,
+            max_length: None,
+            padding_side: PaddingSide::Right,
+            special_tokens: vec![],
+        }
+    }
+}
+
+impl TokenizerBuilder {
+    /// Create a new `TokenizerBuilder` with default options (no lower casing, no accent stripping,
+    /// no maximum length and padding on the right)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, PaddingSide, TokenizerBuilder};
+    /// use rust_tokenizers::vocab::BertVocab;
+    /// let tokenizer = TokenizerBuilder::new()
+    ///     .lower_case(true)
+    ///     .max_length(512)
+    ///     .padding_side(PaddingSide::Left)
+    ///     .add_special_tokens(&["[E1]", "[E2]"])
+    ///     .build::<BertTokenizer, BertVocab>("path/to/vocab/file")
+    ///     .unwrap();
+    /// ```
+    pub fn new() -> TokenizerBuilder {
+        TokenizerBuilder::default()
+    }
+
+    /// Sets the flag indicating if the text should be lower-cased as part of the tokenization
+    pub fn lower_case(mut self, lower_case: bool) -> TokenizerBuilder {
+        self.lower_case = lower_case;
+        self
+    }
+
+    /// Sets the flag indicating if accents should be stripped from the text
+    pub fn strip_accents(mut self, strip_accents: bool) -> TokenizerBuilder {
+        self.strip_accents = strip_accents;
+        self
+    }
+
+    /// Sets the maximum length of the encoded sequences (including special tokens)
+    pub fn max_length(mut self, max_length: usize) -> TokenizerBuilder {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Sets the side on which sequences are padded when encoding a batch
+    pub fn padding_side(mut self, padding_side: PaddingSide) -> TokenizerBuilder {
+        self.padding_side = padding_side;
+        self
+    }
+
+    /// Register additional tokens as special values once the vocabulary is loaded. The tokens
+    /// must be present in the vocabulary file.
+    pub fn add_special_tokens(mut self, special_tokens: &[&str]) -> TokenizerBuilder {
+        self.special_tokens
+            .extend(special_tokens.iter().map(|token| token.to_string()));
+        self
+    }
+
+    /// Read the vocabulary and create the tokenizer, applying the builder options
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the vocabulary file
+    ///
+    /// # Returns
+    /// - `Result<ConfiguredTokenizer<T, V>, TokenizerError>`: tokenizer created, error if the vocabulary cannot be read or an additional special token is missing from the vocabulary
+    pub fn build<T, V>(&self, path: &str) -> Result<ConfiguredTokenizer<T, V>, TokenizerError>
+    where
+        T: BuildableTokenizer<V>,
+        V: VocabMut,
+    {
+        let special_tokens = self
+            .special_tokens
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<&str>>();
+        let vocab = VocabBuilder::<V>::new()
+            .add_special_tokens(&special_tokens)
+            .build(path)?;
+        let tokenizer = T::from_file_with_vocab(path, vocab, self.lower_case, self.strip_accents)?;
+        Ok(ConfiguredTokenizer {
+            tokenizer,
+            max_length: self.max_length,
+            padding_side: self.padding_side,
+            _vocab: PhantomData,
+        })
+    }
+}
+
+/// # ConfiguredTokenizer
+/// Tokenizer created by a `TokenizerBuilder`, holding the encoding options of the builder
+/// (maximum length and padding side) in addition to the wrapped tokenizer.
+pub struct ConfiguredTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    tokenizer: T,
+    max_length: Option<usize>,
+    padding_side: PaddingSide,
+    _vocab: PhantomData<V>,
+}
+
+impl<T, V> ConfiguredTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    /// Returns a reference to the wrapped tokenizer
+    pub fn inner(&self) -> &T {
+        &self.tokenizer
+    }
+
+    /// Returns the maximum length of the encoded sequences, if set
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    /// Returns the side on which sequences are padded when encoding a batch
+    pub fn padding_side(&self) -> PaddingSide {
+        self.padding_side
+    }
+
+    /// Encode a list of texts, truncating the sequences to the maximum length and padding them
+    /// to the longest sequence on the configured side with the padding token of the vocabulary.
+    ///
+    /// # Parameters
+    /// - text_list: list of strings to encode
+    ///
+    /// # Returns
+    /// - `PaddedBatch` containing the padded token ids and the corresponding attention mask, or a
+    ///   `TokenizerError::ValueError` if the vocabulary does not define a padding token
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::tokenizer::{BertTokenizer, Tokenizer, TokenizerBuilder};
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let tokenizer = TokenizerBuilder::new()
+    ///     .max_length(128)
+    ///     .build::<BertTokenizer, BertVocab>("path/to/vocab/file")
+    ///     .unwrap();
+    ///
+    /// let batch = tokenizer
+    ///     .encode_batch(&["Hello, world!", "Second sentence"])
+    ///     .unwrap();
+    /// ```
+    pub fn encode_batch<S, ST>(&self, text_list: S) -> Result<PaddedBatch, TokenizerError>
+    where
+        S: AsRef<[ST]>,
+        ST: AsRef<str>,
+    {
+        let token_ids = self
+            .tokenizer
+            .encode_list(
+                text_list,
+                self.max_length.unwrap_or(usize::MAX),
+                &TruncationStrategy::LongestFirst,
+                0,
+            )
+            .into_iter()
+            .map(|input| input.token_ids)
+            .collect::<Vec<Vec<i64>>>();
+        pad_batch_with_vocab(&token_ids, Tokenizer::vocab(self), None, self.padding_side)
+    }
+}
+
+impl<T, V> Tokenizer<V> for ConfiguredTokenizer<T, V>
+where
+    T: Tokenizer<V>,
+    V: Vocab,
+{
+    fn vocab(&self) -> &V {
+        self.tokenizer.vocab()
+    }
+
+    fn tokenize_to_tokens(&self, text: TokenRef) -> Vec<Token> {
+        self.tokenizer.tokenize_to_tokens(text)
+    }
+
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.tokenizer.tokenize_word_to_tokens(word)
+    }
+
+    fn count_tokens(&self, text: TokenRef) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
+    fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
+        self.tokenizer.convert_tokens_to_string(tokens)
+    }
+
+    fn build_input_with_special_tokens(
+        &self,
+        tokens_ids_with_offsets_1: TokenIdsWithOffsets,
+        tokens_ids_with_offsets_2: Option<TokenIdsWithOffsets>,
+    ) -> TokenIdsWithSpecialTokens {
+        self.tokenizer
+            .build_input_with_special_tokens(tokens_ids_with_offsets_1, tokens_ids_with_offsets_2)
+    }
+}
+
+impl<T, V> MultiThreadedTokenizer<V> for ConfiguredTokenizer<T, V>
+where
+    T: MultiThreadedTokenizer<V>,
+    V: Vocab + Sync + Send,
+{
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::{BaseVocab, BertVocab};
+    use std::io::Write;
+
+    #[test]
+    fn test_build_tokenizer() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n [PAD] \n [CLS] \n [SEP] \n [MASK] \n [E1]"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let path_str = path.to_path_buf().to_str().unwrap().to_owned();
+
+        //        When
+        let tokenizer = TokenizerBuilder::new()
+            .lower_case(true)
+            .max_length(4)
+            .padding_side(PaddingSide::Left)
+            .add_special_tokens(&["[E1]"])
+            .build::<BertTokenizer, BertVocab>(&path_str)?;
+        let batch = tokenizer.encode_batch(["Hello World hello", "[E1]"])?;
+
+        //        Then
+        assert_eq!(tokenizer.max_length(), Some(4));
+        assert_eq!(tokenizer.padding_side(), PaddingSide::Left);
+        assert_eq!(
+            Tokenizer::vocab(&tokenizer).special_values.get("[E1]"),
+            Some(&7)
+        );
+        assert_eq!(batch.token_ids, vec![vec![4, 0, 1, 5], vec![3, 4, 7, 5]]);
+        assert_eq!(
+            batch.attention_mask,
+            vec![vec![1, 1, 1, 1], vec![0, 1, 1, 1]]
+        );
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_batch_without_padding_token() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK]")?;
+        let path = vocab_file.into_temp_path();
+        let path_str = path.to_path_buf().to_str().unwrap().to_owned();
+        let tokenizer =
+            TokenizerBuilder::new().build::<BaseTokenizer<BaseVocab>, BaseVocab>(&path_str)?;
+
+        //        When
+        let batch = tokenizer.encode_batch(["hello world", "hello"]);
+
+        //        Then
+        assert!(matches!(batch, Err(TokenizerError::ValueError(_))));
+        drop(path);
+        Ok(())
+    }
+}
@@ -88,6 +88,21 @@ impl XLMRobertaTokenizer {
             lower_case,
         }
     }
+
+    /// Normalizes a token and replaces the whitespaces with the SentencePiece whitespace marker,
+    /// prepended to the token if missing
+    fn normalize(&self, token: &mut Token) {
+        clean_text(token, true);
+        decompose_nfkc(token);
+        if self.lower_case {
+            lowercase(token);
+        }
+        token.text = token.text.replace(|c: char| is_whitespace(&c), "\u{2581}");
+        if !token.text.starts_with('\u{2581}') {
+            token.text.insert(0, '\u{2581}');
+            token.reference_offsets.insert(0, 0);
+        };
+    }
 }
 
 impl Tokenizer<XLMRobertaVocab> for XLMRobertaTokenizer {
@@ -104,16 +119,7 @@ impl Tokenizer<XLMRobertaVocab> for XLMRobertaTokenizer {
         let mut sub_tokens: Vec<Token> = Vec::new();
         for token in tokens.iter_mut() {
             if token.mask != Mask::Special && token.mask != Mask::Unknown {
-                clean_text(token, true);
-                decompose_nfkc(token);
-                if self.lower_case {
-                    lowercase(token);
-                }
-                token.text = token.text.replace(|c: char| is_whitespace(&c), "\u{2581}");
-                if !token.text.starts_with('\u{2581}') {
-                    token.text.insert(0, '\u{2581}');
-                    token.reference_offsets.insert(0, 0);
-                };
+                self.normalize(token);
                 let output = self.model.decode_forward_token_ref(token.as_ref());
                 let decoded = self.model.decode_backward(&output);
 
@@ -126,6 +132,25 @@ impl Tokenizer<XLMRobertaVocab> for XLMRobertaTokenizer {
         sub_tokens
     }
 
+    fn count_tokens(&self, text: TokenRef) -> usize {
+        split_on_special_tokens(text, &self.vocab)
+            .into_iter()
+            .map(|token| {
+                if token.mask != Mask::Special && token.mask != Mask::Unknown {
+                    let mut token = token.to_owned();
+                    self.normalize(&mut token);
+                    self.model.count_pieces(token.as_ref())
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        self.model.tokenize_word(word)
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
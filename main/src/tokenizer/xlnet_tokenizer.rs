@@ -192,6 +192,12 @@ impl Tokenizer<XLNetVocab> for XLNetTokenizer {
         sub_tokens
     }
 
+    fn tokenize_word_to_tokens(&self, word: TokenRef) -> Vec<Token> {
+        let mut output = self.model.tokenize_word(word);
+        self.post_process_pieces(&mut output);
+        output
+    }
+
     fn convert_tokens_to_string(&self, tokens: Vec<String>) -> String {
         tokens
             .into_iter()
@@ -13,12 +13,17 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::{merge_vocab, parse_byte_piece, swap_key_values, vocab_partial_eq};
+#[cfg(feature = "mmap")]
+use crate::vocab::sentence_piece_vocab::read_protobuf_mmap_file;
+use crate::vocab::sentence_piece_vocab::{
+    read_protobuf_file, read_protobuf_with_size_limit, read_sentencepiece,
+    read_sentencepiece_proto, SentencePieceValues,
+};
+use crate::vocab::{SentencePieceNormalizer, SpecialTokenConfig, Vocab, VocabMut};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs::File;
+use std::convert::TryFrom;
 use std::io::Read;
 
 /// # AlbertVocab
@@ -30,8 +35,12 @@ use std::io::Read;
 /// - PAD token
 /// - MASK token
 ///
-/// Expects a SentencePiece protobuf file when created from file.
-#[derive(Debug, Clone)]
+/// Expects a SentencePiece protobuf file when created from file. Pieces of the `CONTROL` and
+/// `USER_DEFINED` types are registered as special values, and pieces of the `BYTE` type (`<0xNN>`)
+/// are registered as byte-fallback entries. The normalization rules of the model are read from its
+/// `NormalizerSpec`. The vocabulary implements `Serialize` and `Deserialize`, allowing a fully
+/// constructed vocabulary to be cached (e.g. with `bincode`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlbertVocab {
     /// A mapping of tokens as string to indices (i.e. the encoder base)
     pub values: HashMap<String, i64>,
@@ -39,8 +48,8 @@ pub struct AlbertVocab {
     /// A mapping of token ids to strings (i.e. the decoder base)
     pub indices: HashMap<i64, String>,
 
-    /// The string to use for unknown (out of vocabulary) tokens
-    pub unknown_value: &'static str,
+    /// The string to use for unknown (out of vocabulary) tokens, read from the SentencePiece model
+    pub unknown_value: String,
 
     /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
     /// values), special values typically include things like BOS/EOS markers, class markers, mask
@@ -49,6 +58,28 @@ pub struct AlbertVocab {
 
     /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
     pub special_indices: HashMap<i64, String>,
+
+    /// A mapping of bytes to the IDs of their byte-fallback pieces (`<0xNN>`), empty if the
+    /// SentencePiece model does not define byte pieces
+    pub byte_values: HashMap<u8, i64>,
+
+    /// Log-probability scores of the SentencePiece pieces, indexed by piece id
+    pub scores: Vec<f32>,
+
+    /// Normalization rules of the SentencePiece model (precompiled character map), applied to the
+    /// text before segmentation and to the tokens not found as is on lookup. Special values are never
+    /// normalized.
+    pub normalizer: SentencePieceNormalizer,
+
+    /// Flag indicating if tokens not found as is are lowercased on lookup (for uncased
+    /// vocabularies), set with `with_lower_case`
+    #[serde(default)]
+    pub(crate) do_lower_case: bool,
+
+    /// Special token strings registered when reading the vocabulary, defaulting to the ALBERT
+    /// special tokens
+    #[serde(default = "SpecialTokenConfig::albert")]
+    pub special_token_config: SpecialTokenConfig,
 }
 
 impl AlbertVocab {
@@ -81,15 +112,317 @@ impl AlbertVocab {
     pub fn pad_value() -> &'static str {
         "<pad>"
     }
+
+    /// Returns the ID of the byte-fallback piece for a byte, if the vocabulary defines byte pieces
+    ///
+    /// # Parameters
+    /// - byte (`u8`): byte to look up
+    ///
+    /// # Returns
+    /// - `Option<i64>`: ID of the `<0xNN>` piece for the byte
+    pub fn byte_to_id(&self, byte: u8) -> Option<i64> {
+        self.byte_values.get(&byte).copied()
+    }
+
+    /// Converts a token to ids, decomposing out-of-vocabulary tokens into the byte-fallback pieces
+    /// (`<0xNN>`) of their UTF-8 bytes instead of the unknown id. This is only possible for
+    /// SentencePiece models trained with byte fallback, defining the pieces for all 256 bytes:
+    /// other vocabularies return the unknown id for out-of-vocabulary tokens.
+    ///
+    /// # Parameters
+    /// - token (`&str`): token to convert
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: id of the token if in the vocabulary, ids of its byte pieces otherwise
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap();
+    /// let ids = vocab.token_to_ids_byte_fallback("\u{1F980}");
+    /// ```
+    pub fn token_to_ids_byte_fallback(&self, token: &str) -> Vec<i64> {
+        let token_id = self.token_to_id(token);
+        if self.byte_values.len() < 256
+            || token == self.unknown_value
+            || self.values.get(&self.unknown_value) != Some(&token_id)
+        {
+            return vec![token_id];
+        }
+        token.bytes().map(|byte| self.byte_values[&byte]).collect()
+    }
+
+    /// Converts a sequence of ids to text, joining the SentencePiece pieces and replacing the `▁`
+    /// whitespace markers with spaces. Every marker is replaced, including consecutive markers and
+    /// markers within a piece, and the space of the leading marker is removed. Byte pieces
+    /// (`<0xXX>`, e.g. from `token_to_ids_byte_fallback`) are decoded to their byte and
+    /// consecutive bytes are decoded as UTF-8, invalid sequences being replaced by `U+FFFD`.
+    /// `id_to_token` returns the raw pieces, including the markers.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): sequence of ids to decode
+    ///
+    /// # Returns
+    /// - `String`: decoded text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap();
+    /// let text = vocab.decode(&[13, 1_250, 42]);
+    /// ```
+    pub fn decode(&self, ids: &[i64]) -> String {
+        let mut bytes = Vec::new();
+        for id in ids {
+            let token = self.id_to_token(id);
+            match parse_byte_piece(&token) {
+                Some(byte) if self.byte_values.get(&byte) == Some(id) => bytes.push(byte),
+                _ => bytes.extend_from_slice(token.as_bytes()),
+            }
+        }
+        let text = String::from_utf8_lossy(&bytes).replace('\u{2581}', " ");
+        match text.strip_prefix(' ') {
+            Some(stripped_text) => stripped_text.to_owned(),
+            None => text,
+        }
+    }
+
+    /// Read a vocabulary from a SentencePiece protobuf file, registering the special token strings
+    /// of a custom configuration instead of the ALBERT special tokens (e.g. for fine-tuned
+    /// checkpoints using `<s>` and `</s>` as class and separation tokens).
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the SentencePiece model file
+    /// - special_token_config (`SpecialTokenConfig`): special token strings to register
+    ///
+    /// # Returns
+    /// - `Result<AlbertVocab, TokenizerError>`: vocabulary read, error if a special token of the configuration is missing from the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, SpecialTokenConfig};
+    /// let path = "path/to/spiece.model";
+    ///
+    /// let special_token_config = SpecialTokenConfig {
+    ///     cls_value: Some("<s>".into()),
+    ///     sep_value: Some("</s>".into()),
+    ///     ..SpecialTokenConfig::albert()
+    /// };
+    /// let vocab = AlbertVocab::from_file_with_special_tokens(path, special_token_config).unwrap();
+    /// ```
+    pub fn from_file_with_special_tokens(
+        path: &str,
+        special_token_config: SpecialTokenConfig,
+    ) -> Result<AlbertVocab, TokenizerError> {
+        let proto = read_protobuf_file(path)?;
+        let sentencepiece_values =
+            read_sentencepiece_proto::<AlbertVocab>(&proto, &special_token_config)?;
+        Ok(AlbertVocab::from_sentencepiece_values(
+            sentencepiece_values,
+            special_token_config,
+        ))
+    }
+
+    /// Read a vocabulary from a SentencePiece protobuf with a custom limit on its size, for models
+    /// provided by untrusted sources. `from_reader` and `from_file` apply the default limit
+    /// `MAX_SENTENCEPIECE_MODEL_SIZE`.
+    ///
+    /// # Parameters
+    /// - reader (`R: Read`): reader of the SentencePiece model
+    /// - max_size (`u64`): maximum size of the model in bytes
+    ///
+    /// # Returns
+    /// - `Result<AlbertVocab, TokenizerError>`: vocabulary read, `VocabularyParsingError` if the model exceeds the maximum size or cannot be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::AlbertVocab;
+    /// use std::fs::File;
+    /// let file = File::open("path/to/spiece.model").unwrap();
+    ///
+    /// let vocab = AlbertVocab::from_reader_with_size_limit(file, 16 * 1024 * 1024).unwrap();
+    /// ```
+    pub fn from_reader_with_size_limit<R: Read>(
+        reader: R,
+        max_size: u64,
+    ) -> Result<AlbertVocab, TokenizerError> {
+        let proto = read_protobuf_with_size_limit(reader, max_size)?;
+        let sentencepiece_values =
+            read_sentencepiece_proto::<AlbertVocab>(&proto, &SpecialTokenConfig::albert())?;
+        Ok(AlbertVocab::from_sentencepiece_values(
+            sentencepiece_values,
+            SpecialTokenConfig::albert(),
+        ))
+    }
+
+    /// Read a vocabulary from a SentencePiece protobuf file mapped in memory, parsing the mapped
+    /// bytes instead of reading the whole file to the heap first. The returned vocabulary owns its
+    /// strings: the file is unmapped before returning and can be modified or removed afterwards.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated (by this or another process) while the vocabulary
+    /// is read: changes to a mapped file are visible through the map, and truncating it makes
+    /// accesses to the mapped bytes undefined behaviour. Use `from_file` if this cannot be
+    /// guaranteed.
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the SentencePiece model file
+    ///
+    /// # Returns
+    /// - `Result<AlbertVocab, TokenizerError>`: vocabulary read
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::AlbertVocab;
+    /// let path = "path/to/spiece.model";
+    ///
+    /// let vocab = unsafe { AlbertVocab::from_mmap_file(path) }.unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub unsafe fn from_mmap_file(path: &str) -> Result<AlbertVocab, TokenizerError> {
+        let proto = read_protobuf_mmap_file(path)?;
+        let sentencepiece_values =
+            read_sentencepiece_proto::<AlbertVocab>(&proto, &SpecialTokenConfig::albert())?;
+        Ok(AlbertVocab::from_sentencepiece_values(
+            sentencepiece_values,
+            SpecialTokenConfig::albert(),
+        ))
+    }
+
+    fn from_sentencepiece_values(
+        sentencepiece_values: SentencePieceValues,
+        special_token_config: SpecialTokenConfig,
+    ) -> AlbertVocab {
+        let SentencePieceValues {
+            values,
+            unknown_value,
+            special_values,
+            byte_values,
+            scores,
+            normalizer,
+        } = sentencepiece_values;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        AlbertVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+            byte_values,
+            scores,
+            normalizer,
+            do_lower_case: false,
+            special_token_config,
+        }
+    }
+
+    /// Returns the log-probability score of a SentencePiece piece
+    ///
+    /// # Parameters
+    /// - id (`i64`): piece id
+    ///
+    /// # Returns
+    /// - `Option<f32>`: score of the piece, `None` if the id is not a piece of the model
+    pub fn score(&self, id: i64) -> Option<f32> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|index| self.scores.get(index))
+            .copied()
+    }
+
+    /// Enable or disable the lowercasing of tokens on lookup (for uncased vocabularies). Tokens
+    /// not found as is by `token_to_id`, `try_token_to_id` and `contains_token` are looked up again
+    /// lowercased, after the normalization rules of the model, while `id_to_token` is unaffected.
+    /// Special values are always matched case-sensitively. Lowercasing is disabled by default.
+    ///
+    /// # Parameters
+    /// - do_lower_case (`bool`): flag indicating if tokens not found as is are lowercased on lookup
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/spiece.model";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap().with_lower_case(true);
+    /// let token_id = vocab.token_to_id("\u{2581}Hello");
+    /// ```
+    pub fn with_lower_case(mut self, do_lower_case: bool) -> AlbertVocab {
+        self.do_lower_case = do_lower_case;
+        self
+    }
+
+    /// Returns `true` if tokens not found as is are lowercased on lookup
+    pub fn do_lower_case(&self) -> bool {
+        self.do_lower_case
+    }
+
+    /// Returns the id of a token, looked up as is and, on a miss, in its form normalized with the
+    /// rules of the model and lowercased if enabled. Tokens produced by the `AlbertTokenizer` are
+    /// already normalized and found without normalizing them again.
+    fn lookup_id(&self, token: &str) -> Option<i64> {
+        let find = |token: &str| {
+            self.special_values
+                .get(token)
+                .or_else(|| self.values.get(token))
+                .copied()
+        };
+        find(token).or_else(|| {
+            if !self.do_lower_case && !self.normalizer.has_rules() {
+                return None;
+            }
+            let normalized_token = self.normalizer.normalize(token);
+            if self.do_lower_case {
+                find(&normalized_token.to_lowercase())
+            } else {
+                find(&normalized_token)
+            }
+        })
+    }
 }
 
+vocab_partial_eq!(
+    AlbertVocab,
+    scores,
+    do_lower_case,
+    special_token_config,
+    normalizer
+);
+
 impl Vocab for AlbertVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn get_unknown_value(&self) -> &str {
+        &self.unknown_value
+    }
+
+    fn expected_special_values(&self) -> Vec<&str> {
+        let mut special_values = vec![self.get_unknown_value()];
+        special_values.extend(
+            self.special_token_config
+                .special_values()
+                .into_iter()
+                .skip(1),
+        );
+        special_values
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        self.special_token_config.clone()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -108,72 +441,729 @@ impl Vocab for AlbertVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<AlbertVocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
+    fn from_reader<R: Read>(reader: R) -> Result<AlbertVocab, TokenizerError> {
+        let sentencepiece_values =
+            read_sentencepiece::<AlbertVocab, _>(reader, &SpecialTokenConfig::albert())?;
+        Ok(AlbertVocab::from_sentencepiece_values(
+            sentencepiece_values,
+            SpecialTokenConfig::albert(),
+        ))
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self.lookup_id(token)
+            .unwrap_or_else(|| *self.values.get(&self.unknown_value).unwrap())
+    }
+
+    fn try_token_to_id(&self, token: &str) -> Option<i64> {
+        self.lookup_id(token)
+    }
+
+    fn contains_token(&self, token: &str) -> bool {
+        self.lookup_id(token).is_some()
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self._id_to_token(
+            id,
+            &self.indices,
+            &self.special_indices,
+            &self.unknown_value,
+        )
+    }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for AlbertVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+
+    /// Inserts a token with a score of 0. Byte pieces (`<0xXX>`) are registered as byte fallback
+    /// tokens unless the vocabulary already has a piece for their byte.
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.values.insert(token.to_owned(), id);
+        self.indices.insert(id, token.to_owned());
+        if self.scores.len() <= id as usize {
+            self.scores.resize(id as usize + 1, 0.0);
+        }
+        if let Some(byte) = parse_byte_piece(token) {
+            self.byte_values.entry(byte).or_insert(id);
+        }
+    }
+
+    fn merge(&mut self, other: &Self) -> Result<(Vec<Option<i64>>, usize), TokenizerError> {
+        let first_new_id = self
+            .indices
+            .keys()
+            .chain(self.special_indices.keys())
+            .max()
+            .map_or(0, |id| id + 1);
+        let (id_mapping, conflicts) = merge_vocab(self, other)?;
+        for (old_id, new_id) in id_mapping.iter().enumerate() {
+            if let Some(new_id) = new_id.filter(|new_id| *new_id >= first_new_id) {
+                self.scores[new_id as usize] = other.scores.get(old_id).copied().unwrap_or(0.0);
             }
-        };
+        }
+        Ok((id_mapping, conflicts))
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
 
-        let mut values = HashMap::new();
-        for (idx, piece) in proto.get_pieces().iter().enumerate() {
-            values.insert(piece.get_piece().to_owned(), idx as i64);
+    use super::*;
+    use crate::vocab::sentence_piece_normalizer::tests::build_precompiled_charsmap;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece, ModelProto_SentencePiece_Type, NormalizerSpec,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    fn generate_test_proto_file(
+        pieces: &[(&str, ModelProto_SentencePiece_Type)],
+    ) -> anyhow::Result<tempfile::TempPath> {
+        let mut proto = ModelProto::new();
+        for (piece, piece_type) in pieces {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_field_type(*piece_type);
+            proto.mut_pieces().push(sentence_piece);
         }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        Ok(model_file.into_temp_path())
+    }
 
-        let mut special_values = HashMap::new();
-        let unknown_value = AlbertVocab::unknown_value();
-        AlbertVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+    #[test]
+    fn test_register_piece_types() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("<user>", ModelProto_SentencePiece_Type::USER_DEFINED),
+            ("<0x41>", ModelProto_SentencePiece_Type::BYTE),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
 
-        let bos_value = AlbertVocab::bos_value();
-        AlbertVocab::_register_as_special_value(bos_value, &values, &mut special_values)?;
+        //        When
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
-        let eos_value = AlbertVocab::eos_value();
-        AlbertVocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
+        //        Then
+        assert_eq!(albert_vocab.special_values.len(), 6);
+        assert_eq!(albert_vocab.special_values.get("<user>"), Some(&5));
+        assert!(!albert_vocab.special_values.contains_key("\u{2581}hello"));
+        assert!(!albert_vocab.special_values.contains_key("<0x41>"));
+        assert_eq!(albert_vocab.byte_to_id(0x41), Some(6));
+        assert_eq!(albert_vocab.byte_to_id(0x42), None);
+        drop(path);
+        Ok(())
+    }
 
-        let cls_value = AlbertVocab::cls_value();
-        AlbertVocab::_register_as_special_value(cls_value, &values, &mut special_values)?;
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn test_create_object_from_mmap_file() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let path_str = path.to_path_buf().to_str().unwrap().to_owned();
+        let expected_vocab = AlbertVocab::from_file(&path_str)?;
 
-        let mask_value = AlbertVocab::mask_value();
-        AlbertVocab::_register_as_special_value(mask_value, &values, &mut special_values)?;
+        //        When
+        let albert_vocab = unsafe { AlbertVocab::from_mmap_file(&path_str) }?;
+        drop(path);
 
-        let pad_value = AlbertVocab::pad_value();
-        AlbertVocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
+        //        Then
+        assert_eq!(albert_vocab.values, expected_vocab.values);
+        assert_eq!(albert_vocab.special_values, expected_vocab.special_values);
+        assert_eq!(albert_vocab.token_to_id("\u{2581}hello"), 5);
+        assert_eq!(albert_vocab.id_to_token(&5), "\u{2581}hello");
+        Ok(())
+    }
 
-        let sep_value = AlbertVocab::sep_value();
-        AlbertVocab::_register_as_special_value(sep_value, &values, &mut special_values)?;
+    #[test]
+    fn test_serde_round_trip() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("<0x41>", ModelProto_SentencePiece_Type::BYTE),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
-        let indices = swap_key_values(&values);
-        let special_indices = swap_key_values(&special_values);
+        //        When
+        let serialized = serde_json::to_string(&albert_vocab.clone().with_lower_case(true))?;
+        let deserialized_vocab: AlbertVocab = serde_json::from_str(&serialized)?;
+        let mut legacy_value: serde_json::Value = serde_json::from_str(&serialized)?;
+        legacy_value
+            .as_object_mut()
+            .unwrap()
+            .remove("do_lower_case");
+        let legacy_vocab: AlbertVocab = serde_json::from_value(legacy_value)?;
 
-        Ok(AlbertVocab {
-            values,
-            indices,
-            unknown_value,
-            special_values,
-            special_indices,
-        })
+        //        Then
+        assert_eq!(deserialized_vocab.values, albert_vocab.values);
+        assert_eq!(deserialized_vocab.indices, albert_vocab.indices);
+        assert_eq!(
+            deserialized_vocab.special_values,
+            albert_vocab.special_values
+        );
+        assert_eq!(deserialized_vocab.byte_to_id(0x41), Some(5));
+        assert_eq!(deserialized_vocab.scores, albert_vocab.scores);
+        for token in ["\u{2581}hello", "[CLS]", "oov_value"].iter() {
+            assert_eq!(
+                deserialized_vocab.token_to_id(token),
+                albert_vocab.token_to_id(token)
+            );
+        }
+        assert!(deserialized_vocab.do_lower_case());
+        assert!(!legacy_vocab.do_lower_case());
+        assert_eq!(legacy_vocab.values, albert_vocab.values);
+        drop(path);
+        Ok(())
     }
 
-    fn token_to_id(&self, token: &str) -> i64 {
-        self._token_to_id(
-            token,
-            &self.values,
-            &self.special_values,
-            self.unknown_value,
-        )
+    #[test]
+    fn test_token_to_ids_byte_fallback() -> anyhow::Result<()> {
+        //        Given
+        let byte_pieces: Vec<String> = (0..=255u8)
+            .map(|byte| format!("<0x{:02X}>", byte))
+            .collect();
+        let mut pieces = vec![
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ];
+        let path_without_bytes = generate_test_proto_file(&pieces)?;
+        pieces.extend(
+            byte_pieces
+                .iter()
+                .map(|piece| (piece.as_str(), ModelProto_SentencePiece_Type::BYTE)),
+        );
+        let path = generate_test_proto_file(&pieces)?;
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let albert_vocab_without_bytes =
+            AlbertVocab::from_file(path_without_bytes.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let ids = albert_vocab.token_to_ids_byte_fallback("\u{1F980}");
+        let decoded_bytes = ids
+            .iter()
+            .map(|id| parse_byte_piece(&albert_vocab.id_to_token(id)))
+            .collect::<Option<Vec<u8>>>()
+            .unwrap();
+
+        //        Then
+        assert_eq!(ids, vec![6 + 0xF0, 6 + 0x9F, 6 + 0xA6, 6 + 0x80]);
+        assert_eq!(String::from_utf8(decoded_bytes)?, "\u{1F980}");
+        assert_eq!(albert_vocab.decode(&ids), "\u{1F980}");
+        assert_eq!(
+            albert_vocab.decode(&[5, 6 + 0xF0, 6 + 0x9F, 6 + 0xA6, 6 + 0x80]),
+            "hello\u{1F980}"
+        );
+        assert_eq!(albert_vocab.decode(&[6 + 0xF0, 5]), "\u{FFFD} hello");
+        assert_eq!(
+            albert_vocab.token_to_ids_byte_fallback("\u{2581}hello"),
+            vec![5]
+        );
+        assert_eq!(albert_vocab.token_to_ids_byte_fallback("<unk>"), vec![1]);
+        assert_eq!(
+            albert_vocab_without_bytes.token_to_ids_byte_fallback("\u{1F980}"),
+            vec![1]
+        );
+        drop(path);
+        drop(path_without_bytes);
+        Ok(())
     }
 
-    fn id_to_token(&self, id: &i64) -> String {
-        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+    #[test]
+    fn test_decode() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+            ("\u{2581}world", ModelProto_SentencePiece_Type::NORMAL),
+            ("s", ModelProto_SentencePiece_Type::NORMAL),
+            ("\u{2581}", ModelProto_SentencePiece_Type::NORMAL),
+            ("new\u{2581}york", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(albert_vocab.decode(&[5, 6, 7]), "hello worlds");
+        assert_eq!(albert_vocab.decode(&[8, 8, 5]), "  hello");
+        assert_eq!(albert_vocab.decode(&[5, 8, 9]), "hello new york");
+        assert_eq!(albert_vocab.decode(&[7, 5]), "s hello");
+        assert_eq!(albert_vocab.id_to_token(&5), "\u{2581}hello");
+        assert_eq!(albert_vocab.decode(&[]), "");
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_unknown_value() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2047}", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+
+        //        When
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(albert_vocab.get_unknown_value(), "\u{2047}");
+        assert_eq!(albert_vocab.token_to_id("\u{2581}world"), 1);
+        assert_eq!(albert_vocab.id_to_token(&42), "\u{2047}");
+        assert!(!albert_vocab.special_values.contains_key("<unk>"));
+        assert!(albert_vocab.missing_special_tokens().is_empty());
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let other_path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("<0x41>", ModelProto_SentencePiece_Type::BYTE),
+            ("\u{2581}world", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let mut albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let mut other_vocab = AlbertVocab::from_file(other_path.to_path_buf().to_str().unwrap())?;
+        other_vocab.scores[6] = -2.5;
+
+        //        When
+        let (id_mapping, conflicts) = albert_vocab.merge(&other_vocab)?;
+
+        //        Then
+        assert_eq!(conflicts, 0);
+        assert_eq!(
+            id_mapping,
+            vec![
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(4),
+                Some(6),
+                Some(7)
+            ]
+        );
+        assert_eq!(albert_vocab.byte_to_id(b'A'), Some(6));
+        assert_eq!(albert_vocab.token_to_id("\u{2581}world"), 7);
+        assert_eq!(albert_vocab.scores.len(), 8);
+        assert_eq!(albert_vocab.score(7), Some(-2.5));
+        drop(path);
+        drop(other_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eq() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let mut rescored_vocab = albert_vocab.clone();
+        rescored_vocab.scores[5] = -1.0;
+        let mut renormalized_vocab = albert_vocab.clone();
+        renormalized_vocab.normalizer.name = "nmt_nfkc_cf".to_string();
+
+        //        When & Then
+        assert_eq!(albert_vocab, albert_vocab.clone());
+        assert_ne!(albert_vocab, rescored_vocab);
+        assert_ne!(albert_vocab, renormalized_vocab);
+        assert_ne!(albert_vocab, albert_vocab.clone().with_lower_case(true));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_tokens() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let mut albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let ids = albert_vocab.add_tokens(&[
+            "\u{2581}world".to_string(),
+            "<0xF0>".to_string(),
+            "\u{2581}hello".to_string(),
+        ]);
+
+        //        Then
+        assert_eq!(ids, vec![6, 7, 5]);
+        assert_eq!(albert_vocab.scores.len(), 8);
+        assert_eq!(albert_vocab.score(6), Some(0.0));
+        assert_eq!(albert_vocab.byte_to_id(0xF0), Some(7));
+        assert_eq!(albert_vocab.byte_to_id(b'A'), None);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_file_with_added_tokens() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let mut added_tokens_file = tempfile::NamedTempFile::new()?;
+        write!(added_tokens_file, "{{\"\u{2581}world\": 6, \"<0x41>\": 7}}")?;
+        let added_tokens_path = added_tokens_file.into_temp_path();
+        let mut negative_id_file = tempfile::NamedTempFile::new()?;
+        write!(negative_id_file, "{{\"<0x41>\": -1}}")?;
+        let negative_id_path = negative_id_file.into_temp_path();
+
+        //        When
+        let albert_vocab = AlbertVocab::from_file_with_added_tokens(
+            path.to_path_buf().to_str().unwrap(),
+            added_tokens_path.to_path_buf().to_str().unwrap(),
+        )?;
+        let negative_id_vocab = AlbertVocab::from_file_with_added_tokens(
+            path.to_path_buf().to_str().unwrap(),
+            negative_id_path.to_path_buf().to_str().unwrap(),
+        );
+
+        //        Then
+        assert_eq!(albert_vocab.token_to_id("\u{2581}world"), 6);
+        assert_eq!(albert_vocab.score(6), Some(0.0));
+        assert_eq!(albert_vocab.score(7), Some(0.0));
+        assert_eq!(albert_vocab.byte_to_id(b'A'), Some(7));
+        assert_eq!(albert_vocab.decode(&[5, 7]), "helloA");
+        assert!(matches!(
+            negative_id_vocab,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        drop(path);
+        drop(added_tokens_path);
+        drop(negative_id_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_piece_scores() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for (piece, score) in [
+            ("<pad>", 0.0),
+            ("<unk>", 0.0),
+            ("[CLS]", 0.0),
+            ("[SEP]", 0.0),
+            ("[MASK]", 0.0),
+            ("\u{2581}hello", -8.5),
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(*score);
+            proto.mut_pieces().push(sentence_piece);
+        }
+
+        //        When
+        let albert_vocab = AlbertVocab::from_reader(proto.write_to_bytes()?.as_slice())?;
+
+        //        Then
+        assert_eq!(albert_vocab.scores.len(), 6);
+        assert_eq!(albert_vocab.score(5), Some(-8.5));
+        assert_eq!(albert_vocab.score(0), Some(0.0));
+        assert_eq!(albert_vocab.score(6), None);
+        assert_eq!(albert_vocab.score(-1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_word_start() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+            ("world", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+
+        //        When
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert!(albert_vocab.is_word_start(2));
+        assert!(albert_vocab.is_word_start(5));
+        assert!(!albert_vocab.is_word_start(6));
+        assert!(!albert_vocab.is_word_start(7));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_normalizer_spec() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for piece in ["<pad>", "<unk>", "[CLS]", "[SEP]", "[MASK]"].iter() {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            proto.mut_pieces().push(sentence_piece);
+        }
+        proto.mut_normalizer_spec().set_name("nmt_nfkc".to_string());
+        proto.mut_normalizer_spec().set_add_dummy_prefix(true);
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+
+        //        When
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(albert_vocab.normalizer.name, "nmt_nfkc");
+        assert!(albert_vocab.normalizer.add_dummy_prefix);
+        assert!(!albert_vocab.normalizer.has_rules());
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_to_id_normalized() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("[CLS]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[SEP]", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("123", ModelProto_SentencePiece_Type::NORMAL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let raw_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let mut albert_vocab = raw_vocab.clone();
+        let mut normalizer_spec = NormalizerSpec::new();
+        normalizer_spec.set_name("nmt_nfkc_cf".to_string());
+        normalizer_spec.set_precompiled_charsmap(build_precompiled_charsmap(&[
+            ("\u{FF11}", "1"),
+            ("\u{FF12}", "2"),
+            ("\u{FF13}", "3"),
+            ("H", "h"),
+            ("[", "("),
+        ]));
+        albert_vocab.normalizer = SentencePieceNormalizer::from_proto(&normalizer_spec)?;
+        let lower_case_vocab = raw_vocab.clone().with_lower_case(true);
+
+        //        When & Then
+        assert_eq!(raw_vocab.token_to_id("\u{FF11}\u{FF12}\u{FF13}"), 1);
+        assert_eq!(albert_vocab.token_to_id("\u{FF11}\u{FF12}\u{FF13}"), 5);
+        assert_eq!(albert_vocab.token_to_id("123"), 5);
+        assert_eq!(albert_vocab.token_to_id("\u{2581}Hello"), 6);
+        assert_eq!(albert_vocab.token_to_id("[CLS]"), 2);
+        assert_eq!(lower_case_vocab.try_token_to_id("\u{2581}HELLO"), Some(6));
+        assert!(lower_case_vocab.contains_token("\u{2581}Hello"));
+        assert!(!lower_case_vocab.contains_token("[cls]"));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_with_special_tokens() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            ("<pad>", ModelProto_SentencePiece_Type::CONTROL),
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("<s>", ModelProto_SentencePiece_Type::CONTROL),
+            ("</s>", ModelProto_SentencePiece_Type::CONTROL),
+            ("[MASK]", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+        ])?;
+        let special_token_config = SpecialTokenConfig {
+            bos_value: Some("<s>".into()),
+            eos_value: Some("</s>".into()),
+            cls_value: Some("<s>".into()),
+            sep_value: Some("</s>".into()),
+            ..SpecialTokenConfig::albert()
+        };
+
+        //        When
+        let default_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap());
+        let albert_vocab = AlbertVocab::from_file_with_special_tokens(
+            path.to_path_buf().to_str().unwrap(),
+            special_token_config,
+        )?;
+
+        //        Then
+        assert!(matches!(
+            default_vocab,
+            Err(TokenizerError::TokenNotFound(_))
+        ));
+        assert_eq!(albert_vocab.cls_id(), Some(2));
+        assert_eq!(albert_vocab.sep_id(), Some(3));
+        assert_eq!(albert_vocab.pad_id(), Some(0));
+        assert!(albert_vocab.missing_special_tokens().is_empty());
+        let deserialized_vocab: AlbertVocab =
+            serde_json::from_str(&serde_json::to_string(&albert_vocab)?)?;
+        assert_eq!(
+            deserialized_vocab.special_token_config,
+            albert_vocab.special_token_config
+        );
+        assert_eq!(deserialized_vocab.cls_id(), Some(2));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_malformed_proto() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for piece in [
+            "<pad>",
+            "<unk>",
+            "[CLS]",
+            "[SEP]",
+            "[MASK]",
+            "\u{2581}hello",
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(-1.5);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        proto.mut_normalizer_spec().set_name("nmt_nfkc".to_string());
+        proto
+            .mut_normalizer_spec()
+            .set_precompiled_charsmap(vec![8, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 0]);
+        let bytes = proto.write_to_bytes()?;
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut next_random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        //        When & Then
+        for length in 0..bytes.len() {
+            let _ = AlbertVocab::from_reader(&bytes[..length]);
+        }
+        for _ in 0..2_000 {
+            let mut garbage = bytes.clone();
+            let length = (next_random() % 512) as usize;
+            garbage.truncate(length);
+            garbage.resize(length, 0);
+            for _ in 0..(next_random() % 8) {
+                let position = (next_random() % (length as u64 + 1)) as usize;
+                if position < length {
+                    garbage[position] = next_random() as u8;
+                }
+            }
+            let _ = AlbertVocab::from_reader(garbage.as_slice());
+        }
+        assert!(matches!(
+            AlbertVocab::from_reader([0xFF_u8; 16].as_ref()),
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        assert!(matches!(
+            AlbertVocab::from_reader_with_size_limit(bytes.as_slice(), bytes.len() as u64 - 1),
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        assert!(
+            AlbertVocab::from_reader_with_size_limit(bytes.as_slice(), bytes.len() as u64).is_ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_from_empty_proto() -> anyhow::Result<()> {
+        //        Given
+        let proto = ModelProto::new();
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+
+        //        When
+        let albert_vocab = AlbertVocab::from_file(path.to_path_buf().to_str().unwrap());
+
+        //        Then
+        match albert_vocab {
+            Err(TokenizerError::VocabularyParsingError(message)) => {
+                assert_eq!(message, "SentencePiece model contains no pieces")
+            }
+            _ => panic!("expected a vocabulary parsing error"),
+        }
+        drop(path);
+        Ok(())
     }
 }
@@ -10,11 +10,17 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use std::collections::HashMap;
+use crate::vocab::SpecialTokenConfig;
+use itertools::Itertools;
+#[cfg(feature = "parallel-vocab")]
+use rayon::prelude::*;
+use serde::Serializer;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::hash::Hash;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 
+#[cfg(not(feature = "parallel-vocab"))]
 pub(crate) fn swap_key_values<T: Clone, U: Hash + Eq + Copy>(
     input_hashmap: &HashMap<T, U>,
 ) -> HashMap<U, T> {
@@ -24,14 +30,359 @@ pub(crate) fn swap_key_values<T: Clone, U: Hash + Eq + Copy>(
         .collect()
 }
 
+/// Builds the reverse mapping in parallel, for large vocabularies. Each thread builds a partial map
+/// of consecutive entries and the partial maps are merged in the iteration order of the input, so
+/// that the resulting map is identical to the one built by the serial version, including when the
+/// values of the input are not unique (the last entry in iteration order is kept).
+#[cfg(feature = "parallel-vocab")]
+pub(crate) fn swap_key_values<T, U>(input_hashmap: &HashMap<T, U>) -> HashMap<U, T>
+where
+    T: Hash + Eq + Clone + Send + Sync,
+    U: Hash + Eq + Copy + Send + Sync,
+{
+    let entries = input_hashmap.iter().collect::<Vec<(&T, &U)>>();
+    entries
+        .par_iter()
+        .fold(HashMap::new, |mut partial_hashmap, (key, &value)| {
+            partial_hashmap.insert(value, (*key).clone());
+            partial_hashmap
+        })
+        .reduce(HashMap::new, |mut left, mut right| {
+            if left.len() >= right.len() {
+                left.extend(right);
+                left
+            } else {
+                for (value, key) in left {
+                    right.entry(value).or_insert(key);
+                }
+                right
+            }
+        })
+}
+
+/// Checks that the ids of a mapping of tokens to ids are unique
+pub(crate) fn check_unique_ids(values: &HashMap<String, i64>) -> Result<(), TokenizerError> {
+    let mut tokens_by_id: HashMap<i64, &str> = HashMap::with_capacity(values.len());
+    for (token, id) in values.iter().sorted_by_key(|(token, _)| token.as_str()) {
+        if let Some(existing_token) = tokens_by_id.insert(*id, token.as_str()) {
+            return Err(TokenizerError::VocabularyParsingError(format!(
+                "Tokens {} and {} are both mapped to id {}",
+                existing_token, token, id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Reads the token to id entries of a JSON object, rejecting negative ids, ids outside of the
+/// `i64` range and ids shared by several tokens. Entries holding a non-integer value are skipped if
+/// `skip_non_integer_ids` is set, and rejected otherwise.
+fn parse_json_vocab_entries(
+    value: &serde_json::Value,
+    skip_non_integer_ids: bool,
+) -> Result<HashMap<String, i64>, TokenizerError> {
+    let entries = value.as_object().ok_or_else(|| {
+        TokenizerError::VocabularyParsingError(
+            "Vocabulary file should contain a JSON object".to_string(),
+        )
+    })?;
+    let mut data: HashMap<String, i64> = HashMap::with_capacity(entries.len());
+    let mut tokens_by_id: HashMap<i64, &str> = HashMap::with_capacity(entries.len());
+    for (token, id) in entries {
+        let id = match (id.as_i64(), id.as_f64()) {
+            (Some(id), _) => id,
+            (None, Some(id)) if id.fract() == 0.0 => {
+                if id < i64::MIN as f64 || id >= i64::MAX as f64 {
+                    return Err(TokenizerError::VocabularyParsingError(format!(
+                        "Id {} of token {} is out of the i64 range",
+                        id, token
+                    )));
+                }
+                id as i64
+            }
+            _ if skip_non_integer_ids => continue,
+            _ => {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Id {} of token {} is not an integer",
+                    id, token
+                )));
+            }
+        };
+        if id < 0 {
+            return Err(TokenizerError::VocabularyParsingError(format!(
+                "Negative id {} for token {}",
+                id, token
+            )));
+        }
+        if let Some(existing_token) = tokens_by_id.insert(id, token.as_str()) {
+            return Err(TokenizerError::VocabularyParsingError(format!(
+                "Tokens {} and {} are both mapped to id {}",
+                existing_token, token, id
+            )));
+        }
+        data.insert(token.clone(), id);
+    }
+    if data.is_empty() {
+        return Err(TokenizerError::VocabularyParsingError(
+            "Vocabulary file does not contain any token to id entry".to_string(),
+        ));
+    }
+    Ok(data)
+}
+
+/// Converts a JSON parsing error to a `VocabularyParsingError` reporting the line and column of
+/// the error (e.g. `parse error at line 4123 column 7: expected value`)
+pub(crate) fn json_parse_error(error: serde_json::Error) -> TokenizerError {
+    if error.line() == 0 {
+        return TokenizerError::VocabularyParsingError(error.to_string());
+    }
+    let location = format!(" at line {} column {}", error.line(), error.column());
+    let message = error.to_string();
+    TokenizerError::VocabularyParsingError(format!(
+        "parse error at line {} column {}: {}",
+        error.line(),
+        error.column(),
+        message.strip_suffix(&location).unwrap_or(&message)
+    ))
+}
+
+/// Parses a SentencePiece byte piece (e.g. `<0x0A>`) into the byte it represents
+pub(crate) fn parse_byte_piece(piece: &str) -> Option<u8> {
+    piece
+        .strip_prefix("<0x")
+        .and_then(|value| value.strip_suffix('>'))
+        .filter(|value| value.len() == 2)
+        .and_then(|value| u8::from_str_radix(value, 16).ok())
+}
+
+/// Merges the tokens of `other` into `vocab`, see `VocabMut::merge`. The special values of `other`
+/// are checked before `vocab` is modified.
+pub(crate) fn merge_vocab<V: VocabMut>(
+    vocab: &mut V,
+    other: &V,
+) -> Result<(Vec<Option<i64>>, usize), TokenizerError> {
+    for (token, _) in other.iter_special() {
+        if !other.values().contains_key(token)
+            && !vocab.values().contains_key(token)
+            && !vocab.special_values().contains_key(token)
+        {
+            return Err(TokenizerError::TokenNotFound(format!(
+                "The special value {} could not be found in the vocabulary",
+                token
+            )));
+        }
+    }
+
+    let mapping_len = match other.indices().keys().min() {
+        Some(&min_id) if min_id < 0 => {
+            return Err(TokenizerError::ValueError(format!(
+                "Invalid negative id {} in the vocabulary to merge",
+                min_id
+            )));
+        }
+        Some(_) => *other.indices().keys().max().unwrap() as usize + 1,
+        None => 0,
+    };
+    let mut id_mapping = Vec::new();
+    id_mapping.try_reserve_exact(mapping_len).map_err(|_| {
+        TokenizerError::ValueError(format!(
+            "Could not allocate the id mapping of the vocabulary to merge for its largest id {} \
+            ({} tokens)",
+            mapping_len - 1,
+            other.len()
+        ))
+    })?;
+    id_mapping.resize(mapping_len, None);
+    let mut conflicts = 0;
+    let mut new_tokens = Vec::new();
+    let mut new_token_old_ids = Vec::new();
+    for (id, token) in other.iter_sorted() {
+        match vocab
+            .special_values()
+            .get(token)
+            .or_else(|| vocab.values().get(token))
+        {
+            Some(existing_id) => {
+                if *existing_id != id {
+                    conflicts += 1;
+                }
+                id_mapping[id as usize] = Some(*existing_id);
+            }
+            None => {
+                new_tokens.push(token.to_owned());
+                new_token_old_ids.push(id);
+            }
+        }
+    }
+    for (old_id, new_id) in new_token_old_ids
+        .into_iter()
+        .zip(vocab.add_tokens(&new_tokens))
+    {
+        id_mapping[old_id as usize] = Some(new_id);
+    }
+
+    for (token, _) in other.iter_special() {
+        let token_id = *vocab
+            .special_values()
+            .get(token)
+            .or_else(|| vocab.values().get(token))
+            .ok_or_else(|| {
+                TokenizerError::TokenNotFound(format!(
+                    "The special value {} could not be found in the merged vocabulary",
+                    token
+                ))
+            })?;
+        vocab
+            .special_values_mut()
+            .insert(token.to_owned(), token_id);
+        vocab
+            .special_indices_mut()
+            .insert(token_id, token.to_owned());
+    }
+    Ok((id_mapping, conflicts))
+}
+
+/// # Token kind
+/// Token resolved from an id by `Vocab::render_ids`, classified for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Regular token
+    Normal(String),
+    /// Special token (e.g. BOS/EOS or mask markers)
+    Special(String),
+    /// Language prefix token of a multilingual vocabulary (e.g. `>>fr.<<` for M2M100)
+    LanguagePrefix(String),
+}
+
+/// # Vocab diff
+/// Differences between the values of two vocabularies, returned by `Vocab::diff`. Entries are
+/// sorted by id (the id in the new vocabulary for added tokens, in the old vocabulary otherwise).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VocabDiff {
+    /// Tokens present in the new vocabulary only, with their id
+    pub added: Vec<(String, i64)>,
+    /// Tokens present in the old vocabulary only, with their id
+    pub removed: Vec<(String, i64)>,
+    /// Tokens present in both vocabularies with a different id, with their old and new ids
+    pub reindexed: Vec<(String, i64, i64)>,
+}
+
+impl VocabDiff {
+    /// Returns `true` if the two vocabularies have the same values
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reindexed.is_empty()
+    }
+}
+
 /// # Base Vocab trait
 /// Defines a common interface to the vocabularies for use in the tokenizers.
 pub trait Vocab {
     /// Associative function returning the unknown value for the vocabulary
     fn unknown_value() -> &'static str;
 
-    /// Returns the unknown value on an instance
-    fn get_unknown_value(&self) -> &'static str;
+    /// Returns the unknown value on an instance. This may differ from the unknown value of the
+    /// vocabulary type for vocabularies reading their unknown token from the model file, which
+    /// override this method. Defaults to `unknown_value`.
+    fn get_unknown_value(&self) -> &str {
+        Self::unknown_value()
+    }
+
+    /// Returns the special values the vocabulary type expects to find in its vocabulary file
+    /// (including the unknown value). Vocabularies defining additional special values (e.g. BOS/EOS
+    /// markers, class markers, mask markers and padding markers) override this method.
+    fn expected_special_values(&self) -> Vec<&str> {
+        vec![self.get_unknown_value()]
+    }
+
+    /// Returns the special token strings defined by the vocabulary type for each role (BOS/EOS
+    /// markers, class and separation markers, mask and padding markers). Roles the vocabulary does not
+    /// define are set to `None` (e.g. the class token of `M2M100Vocab`).
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::new(Self::unknown_value())
+    }
+
+    /// Returns the id of the padding token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let pad_id = vocab.pad_id();
+    /// let has_cls_token = vocab.cls_id().is_some();
+    /// ```
+    fn pad_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .pad_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the id of the beginning of sequence token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    fn bos_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .bos_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the id of the end of sequence token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    fn eos_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .eos_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the id of the separation token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    fn sep_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .sep_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the id of the class token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    fn cls_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .cls_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the id of the mask token, `None` if the vocabulary does not define one or if it is not
+    /// registered as a special value
+    fn mask_id(&self) -> Option<i64> {
+        self.special_token_config()
+            .mask_value
+            .and_then(|token| self.special_values().get(token.as_ref()).copied())
+    }
+
+    /// Returns the special values expected by the vocabulary type (provided by `expected_special_values`)
+    /// that are not registered in the special values of this instance. This is useful to diagnose
+    /// vocabularies that are missing some of the special tokens required by the tokenizer.
+    ///
+    /// # Returns
+    /// - `Vec<&str>`: expected special values missing from the special values, without duplicates
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let missing_special_tokens = vocab.missing_special_tokens();
+    /// ```
+    fn missing_special_tokens(&self) -> Vec<&str> {
+        self.expected_special_values()
+            .into_iter()
+            .unique()
+            .filter(|token| !self.special_values().contains_key(*token))
+            .collect()
+    }
 
     /// Return the map of token strings to IDs
     fn values(&self) -> &HashMap<String, i64>;
@@ -45,7 +396,31 @@ pub trait Vocab {
     ///Return the map of token IDs to strings for special values
     fn special_indices(&self) -> &HashMap<i64, String>;
 
-    ///Read a vocabulary from file
+    ///Read a vocabulary from a reader (e.g. an in-memory buffer or an archive entry), expecting the
+    ///same format as the vocabulary file read by `from_file`. The default implementation returns an
+    ///error, for vocabularies implementing `from_file` only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let bytes: &[u8] = b"[PAD]\n[UNK]\n[CLS]\n[SEP]\n[MASK]\nhello";
+    ///
+    /// let base_vocab = BertVocab::from_reader(bytes).unwrap();
+    /// assert_eq!(base_vocab.token_to_id("hello"), 5);
+    /// ```
+    fn from_reader<R: Read>(_reader: R) -> Result<Self, TokenizerError>
+    where
+        Self: std::marker::Sized,
+    {
+        Err(TokenizerError::ValueError(
+            "Reading from a reader is not supported by this vocabulary, use from_file".to_string(),
+        ))
+    }
+
+    ///Read a vocabulary from file. The default implementation reads the file with `from_reader`.
+    ///With the `gzip` feature enabled, files with a `.gz` extension are then decompressed while
+    ///reading (e.g. `vocab.json.gz` or `spiece.model.gz`).
     ///
     /// # Example
     ///
@@ -57,7 +432,70 @@ pub trait Vocab {
     /// ```
     fn from_file(path: &str) -> Result<Self, TokenizerError>
     where
-        Self: std::marker::Sized;
+        Self: std::marker::Sized,
+    {
+        let f = File::open(path).map_err(|e| {
+            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+        })?;
+        #[cfg(feature = "gzip")]
+        {
+            if path.ends_with(".gz") {
+                return Self::from_reader(flate2::read::GzDecoder::new(BufReader::new(f)));
+            }
+        }
+        Self::from_reader(f)
+    }
+
+    /// Read a vocabulary from file after verifying the SHA-256 digest of the file, for example to
+    /// detect corrupted downloads. The digest is computed on the raw file bytes, which are then parsed
+    /// with `from_reader`. The returned vocabulary exposes the digest with `source_digest`.
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the vocabulary file
+    /// - expected_sha256 (`&[u8; 32]`): expected SHA-256 digest of the file
+    ///
+    /// # Returns
+    /// - `Result<VerifiedVocab<Self>, TokenizerError>` vocabulary read, error if the digest of the file does not match the expected digest
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{file_sha256, BertVocab, Vocab};
+    /// let path = "path/to/file";
+    /// let expected_sha256 = file_sha256(path).unwrap();
+    ///
+    /// let base_vocab = BertVocab::from_file_verified(path, &expected_sha256).unwrap();
+    /// assert_eq!(base_vocab.source_digest(), Some(expected_sha256));
+    /// ```
+    #[cfg(feature = "sha2")]
+    fn from_file_verified(
+        path: &str,
+        expected_sha256: &[u8; 32],
+    ) -> Result<crate::vocab::VerifiedVocab<Self>, TokenizerError>
+    where
+        Self: std::marker::Sized,
+    {
+        let bytes = std::fs::read(path).map_err(|e| {
+            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+        })?;
+        let digest = crate::vocab::checksum::sha256(&bytes);
+        if &digest != expected_sha256 {
+            return Err(TokenizerError::VocabularyParsingError(format!(
+                "SHA-256 digest of {} does not match the expected digest",
+                path
+            )));
+        }
+        Ok(crate::vocab::VerifiedVocab::new(
+            Self::from_reader(&bytes[..])?,
+            digest,
+        ))
+    }
+
+    /// Returns the SHA-256 digest of the file the vocabulary was read from if it was verified when
+    /// loading (see `from_file_verified`), `None` otherwise
+    fn source_digest(&self) -> Option<[u8; 32]> {
+        None
+    }
 
     /// Read a Bert-style vocab.txt file (single column, one token per line)
     /// The `from_file` method should be preferred, and needs to be implemented by the specific vocabularies
@@ -65,7 +503,13 @@ pub trait Vocab {
         let f = File::open(path).map_err(|e| {
             TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
         })?;
-        let br = BufReader::new(f);
+        Self::read_vocab(f)
+    }
+
+    /// Read a Bert-style vocabulary (single column, one token per line) from a reader
+    /// The `from_reader` method should be preferred, and needs to be implemented by the specific vocabularies
+    fn read_vocab<R: Read>(reader: R) -> Result<HashMap<String, i64>, TokenizerError> {
+        let br = BufReader::new(reader);
         let mut data = HashMap::new();
 
         for (index, line) in br.lines().enumerate() {
@@ -80,6 +524,42 @@ pub trait Vocab {
         Ok(data)
     }
 
+    /// Read a GPT2-style vocab.json file (JSON object mapping tokens to ids).
+    /// Entries that do not hold an integer id (e.g. additional metadata fields) are ignored, so that
+    /// files containing extra fields can still be loaded. Negative ids, ids outside of the `i64`
+    /// range and ids shared by several tokens are rejected.
+    /// The `from_file` method should be preferred, and needs to be implemented by the specific vocabularies
+    fn read_json_vocab_file(path: &str) -> Result<HashMap<String, i64>, TokenizerError> {
+        let f = File::open(path).map_err(|e| {
+            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+        })?;
+        Self::read_json_vocab(f)
+    }
+
+    /// Read a GPT2-style vocabulary (JSON object mapping tokens to ids) from a reader, with the
+    /// same validation as `read_json_vocab_file`
+    /// The `from_reader` method should be preferred, and needs to be implemented by the specific vocabularies
+    fn read_json_vocab<R: Read>(reader: R) -> Result<HashMap<String, i64>, TokenizerError> {
+        let br = BufReader::new(reader);
+        let value: serde_json::Value = serde_json::from_reader(br).map_err(json_parse_error)?;
+        parse_json_vocab_entries(&value, true)
+    }
+
+    /// Read a GPT2-style vocabulary from an already parsed JSON value (e.g. a field of a larger
+    /// configuration document), with the same validation as `read_json_vocab_file`. Unlike
+    /// `read_json_vocab_file`, entries that do not hold an integer id are rejected.
+    ///
+    /// # Parameters
+    /// - value (`&serde_json::Value`): JSON object mapping tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<HashMap<String, i64>, TokenizerError>`: mapping of tokens to ids, error if the value is not an object or contains invalid or non-integer ids
+    fn read_json_vocab_value(
+        value: &serde_json::Value,
+    ) -> Result<HashMap<String, i64>, TokenizerError> {
+        parse_json_vocab_entries(value, false)
+    }
+
     /// Converts a token to an id, provided a `HashMap` of values, a `HashMap` of special values and
     /// the unknown value token string representation. This is not meant to be directly used, the method
     /// `token_to_id` offers a more convenient interface for most vocabularies, but needs to be implemented
@@ -144,6 +624,9 @@ pub trait Vocab {
     /// - token (`&str`): token to register as a special value
     /// - values (`&HashMap<String, i64>`): mapping from tokens to ids. This should contain the token to add and will be used to read the id for registration in `special_values`
     /// - special_values (`&HashMap<String, i64>`): mapping from special tokens to ids
+    ///
+    /// Registering a token that is already a special value is a no-op, allowing vocabularies to
+    /// alias special tokens (e.g. identical SEP and EOS tokens).
     fn _register_as_special_value(
         token: &str,
         values: &HashMap<String, i64>,
@@ -180,207 +663,2238 @@ pub trait Vocab {
     /// - `String`: token value for the index provided. If not found in the indices, returns the unknown token value
     fn id_to_token(&self, id: &i64) -> String;
 
-    /// Converts a list of tokens to a list of indices.
+    /// Converts a token to an id, without falling back to the unknown token. This distinguishes a
+    /// token absent from the vocabulary from the unknown token itself.
     ///
     /// # Parameters
-    /// - tokens (`&[&str]`): list of tokens to convert
+    /// - token (`&str`): token to convert
     ///
     /// # Returns
-    /// - `Vec<i64>`: Vector containing the indices for the tokens provided
-    fn convert_tokens_to_ids(&self, tokens: &[&str]) -> Vec<i64> {
-        tokens.iter().map(|v| self.token_to_id(v)).collect()
+    /// - `Option<i64>`: token index for the value provided, `None` if not found in the values or special values
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let token_id = vocab.try_token_to_id("hello");
+    /// ```
+    fn try_token_to_id(&self, token: &str) -> Option<i64> {
+        self.special_values()
+            .get(token)
+            .or_else(|| self.values().get(token))
+            .copied()
     }
-}
-
-/// # BaseVocab
-/// Base vocabulary with [UNK] unknown token used as a pre-tokenization step for BERT-class tokenizers.
-/// Expects a flat text vocabulary when created from file.
-#[derive(Debug, Clone)]
-pub struct BaseVocab {
-    /// A mapping of tokens as string to indices (i.e. the encoder base)
-    pub values: HashMap<String, i64>,
-
-    /// A mapping of token ids to strings (i.e. the decoder base)
-    pub indices: HashMap<i64, String>,
-
-    /// The string to use for unknown (out of vocabulary) tokens
-    pub unknown_value: &'static str,
-
-    /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
-    /// values), special values typically include things like BOS/EOS markers, class markers, mask
-    /// markers and padding markers
-    pub special_values: HashMap<String, i64>,
-
-    /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
-    pub special_indices: HashMap<i64, String>,
-}
 
-impl Vocab for BaseVocab {
-    fn unknown_value() -> &'static str {
-        "[UNK]"
+    /// Converts the UTF-8 bytes of a token to its id without allocating a `String`, for lookups
+    /// from a byte buffer. Does not fall back to the unknown token.
+    ///
+    /// # Parameters
+    /// - bytes (`&[u8]`): UTF-8 bytes of the token to convert
+    ///
+    /// # Returns
+    /// - `Option<i64>`: token index for the value provided, `None` if the bytes are not valid UTF-8 or the token is not found in the values or special values
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let token_id = vocab.token_bytes_to_id(b">>fr.<<");
+    /// ```
+    fn token_bytes_to_id(&self, bytes: &[u8]) -> Option<i64> {
+        std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|token| self.try_token_to_id(token))
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "[UNK]"
+    /// Converts an id to a token, without falling back to the unknown token.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id to convert
+    ///
+    /// # Returns
+    /// - `Option<String>`: token value for the index provided, `None` if not found in the indices or special indices
+    fn try_id_to_token(&self, id: i64) -> Option<String> {
+        self.special_indices()
+            .get(&id)
+            .or_else(|| self.indices().get(&id))
+            .cloned()
     }
 
-    fn values(&self) -> &HashMap<String, i64> {
-        &self.values
+    /// Returns the id of the unknown token to use for an out-of-vocabulary character. Vocabularies
+    /// defining a single unknown token return its id for every character, while
+    /// `ScriptUnknownVocab` resolves script-specific unknown tokens.
+    ///
+    /// # Parameters
+    /// - character (`char`): character that could not be matched in the vocabulary
+    ///
+    /// # Returns
+    /// - `i64`: id of the unknown token for the character
+    fn unknown_id_for_char(&self, _character: char) -> i64 {
+        self.token_to_id(self.get_unknown_value())
     }
 
-    fn indices(&self) -> &HashMap<i64, String> {
-        &self.indices
+    /// Returns `true` if the token for an id starts a new word. The default implementation follows
+    /// the WordPiece convention, where tokens continuing a word are prefixed with `##`. Vocabularies
+    /// marking the beginning of words instead (SentencePiece `▁`, byte-level BPE `Ġ`) override this
+    /// method. Special tokens always start a word, and ids missing from the vocabulary never do.
+    ///
+    /// Vocabularies marking the end of words (e.g. GPT with `</w>`) do not carry this information
+    /// at the token level: all their tokens are considered to start a word.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the token starts a new word
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let is_word_start = vocab.is_word_start(42);
+    /// ```
+    fn is_word_start(&self, id: i64) -> bool {
+        match self.indices().get(&id) {
+            Some(token) => self.special_indices().contains_key(&id) || !token.starts_with("##"),
+            None => false,
+        }
     }
 
-    fn special_values(&self) -> &HashMap<String, i64> {
-        &self.special_values
+    /// Returns `true` if the token for an id starts a new word, for vocabularies marking the
+    /// beginning of words with a prefix character. Special tokens always start a word, and ids
+    /// missing from the vocabulary never do.
+    /// The `is_word_start` method should be preferred, and needs to be implemented by the specific vocabularies
+    fn _is_word_start_with_prefix(&self, id: i64, word_start_prefix: char) -> bool {
+        match self.indices().get(&id) {
+            Some(token) => {
+                self.special_indices().contains_key(&id) || token.starts_with(word_start_prefix)
+            }
+            None => false,
+        }
     }
 
-    fn special_indices(&self) -> &HashMap<i64, String> {
-        &self.special_indices
+    /// Partitions a sequence of ids into per-word groups, each group made of a word-initial token
+    /// followed by its continuation tokens (as defined by `is_word_start`). Continuation tokens at
+    /// the start of the sequence (e.g. for a truncated input) form their own group.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): sequence of token ids
+    ///
+    /// # Returns
+    /// - `Vec<Vec<i64>>`: id groups, one per word, in the order of the input sequence
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let word_groups = vocab.group_subwords(&[101, 4895, 10354, 3085, 102]);
+    /// ```
+    fn group_subwords(&self, ids: &[i64]) -> Vec<Vec<i64>> {
+        let mut groups: Vec<Vec<i64>> = vec![];
+        for id in ids {
+            match groups.last_mut() {
+                Some(group) if !self.is_word_start(*id) => group.push(*id),
+                _ => groups.push(vec![*id]),
+            }
+        }
+        groups
     }
 
-    fn from_file(path: &str) -> Result<BaseVocab, TokenizerError> {
-        let values = BaseVocab::read_vocab_file(path)?;
-        let mut special_values = HashMap::new();
-        let unknown_value = BaseVocab::unknown_value();
-        BaseVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let indices = swap_key_values(&values);
-        let special_indices = swap_key_values(&special_values);
+    /// Converts a list of tokens to a list of indices.
+    ///
+    /// # Parameters
+    /// - tokens (`&[&str]`): list of tokens to convert
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: Vector containing the indices for the tokens provided
+    fn convert_tokens_to_ids(&self, tokens: &[&str]) -> Vec<i64> {
+        tokens.iter().map(|v| self.token_to_id(v)).collect()
+    }
 
-        Ok(BaseVocab {
-            values,
-            indices,
+    /// Converts a list of tokens to a list of indices, counting the tokens absent from the vocabulary
+    /// (neither regular nor special values) that were mapped to the unknown id. The unknown token
+    /// itself is in the vocabulary and is not counted. This allows computing the out-of-vocabulary
+    /// rate of a corpus.
+    ///
+    /// # Parameters
+    /// - tokens (`&[&str]`): list of tokens to convert
+    ///
+    /// # Returns
+    /// - `(Vec<i64>, usize)`: indices for the tokens provided and number of out-of-vocabulary tokens
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let tokens = ["hello", "wrld", "!"];
+    /// let (ids, unknown_count) = vocab.tokens_to_ids_counting_unknown(&tokens);
+    /// let oov_rate = unknown_count as f64 / tokens.len() as f64;
+    /// ```
+    fn tokens_to_ids_counting_unknown(&self, tokens: &[&str]) -> (Vec<i64>, usize) {
+        let mut unknown_count = 0;
+        let ids = tokens
+            .iter()
+            .map(|token| {
+                if !self.contains_token(token) {
+                    unknown_count += 1;
+                }
+                self.token_to_id(token)
+            })
+            .collect();
+        (ids, unknown_count)
+    }
+
+    /// Converts a list of indices to a list of tokens.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): list of indices to convert
+    ///
+    /// # Returns
+    /// - `Vec<String>`: Vector containing the tokens for the indices provided
+    fn convert_ids_to_tokens(&self, ids: &[i64]) -> Vec<String> {
+        ids.iter().map(|id| self.id_to_token(id)).collect()
+    }
+
+    /// Converts a list of indices to a list of tokens, skipping the special tokens (e.g. padding,
+    /// BOS/EOS markers or language codes registered as special values).
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): list of indices to convert
+    ///
+    /// # Returns
+    /// - `Vec<String>`: Vector containing the tokens for the non-special indices provided
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let tokens = vocab.decode_skip_special(&[0, 128_028, 4, 5, 2, 1]);
+    /// ```
+    fn decode_skip_special(&self, ids: &[i64]) -> Vec<String> {
+        ids.iter()
+            .filter(|id| !self.special_indices().contains_key(*id))
+            .map(|id| self.id_to_token(id))
+            .collect()
+    }
+
+    /// Converts a list of indices to a list of tokens classified as regular or special tokens, for
+    /// rendering model outputs. Padding ids are skipped.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): list of indices to convert
+    ///
+    /// # Returns
+    /// - `Vec<TokenKind>`: Vector containing the classified tokens for the indices provided
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, TokenKind, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// for token in vocab.render_ids(&[128_028, 4, 5, 2, 1, 1]) {
+    ///     match token {
+    ///         TokenKind::Normal(text) => print!("{}", text),
+    ///         TokenKind::Special(text) | TokenKind::LanguagePrefix(text) => print!("[{}]", text),
+    ///     }
+    /// }
+    /// ```
+    fn render_ids(&self, ids: &[i64]) -> Vec<TokenKind> {
+        let pad_id = self.pad_id();
+        ids.iter()
+            .filter(|id| Some(**id) != pad_id)
+            .map(|id| match self.special_indices().get(id) {
+                Some(token) => TokenKind::Special(token.clone()),
+                None => TokenKind::Normal(self.id_to_token(id)),
+            })
+            .collect()
+    }
+
+    /// Returns the number of entries in the vocabulary. Special values are a subset of the
+    /// regular values and are not counted separately.
+    ///
+    /// # Returns
+    /// - `usize`: number of tokens in the vocabulary
+    fn len(&self) -> usize {
+        self.values().len()
+    }
+
+    /// Returns `true` if the vocabulary contains no entries
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the vocabulary is empty
+    fn is_empty(&self) -> bool {
+        self.values().is_empty()
+    }
+
+    /// Returns `true` if the token is present in the vocabulary, either as a regular or as a special
+    /// value. Unlike `token_to_id`, this distinguishes tokens absent from the vocabulary from the
+    /// unknown token.
+    ///
+    /// # Parameters
+    /// - token (`&str`): token to look up
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the token is in the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let has_french_code = vocab.contains_token(">>fr.<<");
+    /// ```
+    fn contains_token(&self, token: &str) -> bool {
+        self.special_values().contains_key(token) || self.values().contains_key(token)
+    }
+
+    /// Returns `true` if the id is present in the vocabulary, either as a regular or as a special
+    /// value.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id to look up
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the id is in the vocabulary
+    fn contains_id(&self, id: i64) -> bool {
+        self.special_indices().contains_key(&id) || self.indices().contains_key(&id)
+    }
+
+    /// Returns the ids of all tokens (including special tokens) whose string representation
+    /// satisfies a predicate, for example to build logit masks for constrained decoding.
+    /// This scans the entire vocabulary and is O(vocab size) for every call: callers needing the
+    /// same set repeatedly should cache the result.
+    ///
+    /// # Parameters
+    /// - pred (`Fn(&str) -> bool`): predicate applied to each token string
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: ids of the matching tokens, sorted in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let numeric_ids = vocab.ids_matching(|token| token.chars().all(|c| c.is_ascii_digit()));
+    /// ```
+    fn ids_matching<F: Fn(&str) -> bool>(&self, pred: F) -> Vec<i64>
+    where
+        Self: Sized,
+    {
+        let mut ids: Vec<i64> = self
+            .values()
+            .iter()
+            .filter(|(token, _)| pred(token))
+            .map(|(_, id)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Returns an iterator over the (token, id) pairs of the vocabulary, without cloning the token
+    /// strings. Special values are included as they are a subset of the regular values. The
+    /// iteration order is arbitrary: use `iter_sorted` for entries in id order.
+    ///
+    /// # Returns
+    /// - `Box<dyn Iterator<Item = (&str, i64)>>`: iterator over the (token, id) pairs
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// for (token, id) in vocab.iter() {
+    ///     println!("{}\t{}", token, id);
+    /// }
+    /// ```
+    fn iter(&self) -> Box<dyn Iterator<Item = (&str, i64)> + '_> {
+        Box::new(
+            self.values()
+                .iter()
+                .map(|(token, id)| (token.as_str(), *id)),
+        )
+    }
+
+    /// Returns an iterator over the (token, id) pairs of the special values of the vocabulary. The
+    /// iteration order is arbitrary.
+    ///
+    /// # Returns
+    /// - `Box<dyn Iterator<Item = (&str, i64)>>`: iterator over the special (token, id) pairs
+    fn iter_special(&self) -> Box<dyn Iterator<Item = (&str, i64)> + '_> {
+        Box::new(
+            self.special_values()
+                .iter()
+                .map(|(token, id)| (token.as_str(), *id)),
+        )
+    }
+
+    /// Returns the entries of the vocabulary in ascending id order, for example to write the
+    /// vocabulary in a stable format or to check that the ids are contiguous. The ids are collected
+    /// and sorted on every call (O(n log n) in the vocabulary size).
+    ///
+    /// # Returns
+    /// - `std::vec::IntoIter<(i64, &str)>`: iterator over the (id, token) pairs sorted by id
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// for (id, token) in vocab.iter_sorted() {
+    ///     println!("{}\t{}", id, token);
+    /// }
+    /// ```
+    fn iter_sorted(&self) -> std::vec::IntoIter<(i64, &str)> {
+        let mut entries: Vec<(i64, &str)> = self
+            .indices()
+            .iter()
+            .map(|(id, token)| (*id, token.as_str()))
+            .collect();
+        entries.sort_unstable_by_key(|(id, _)| *id);
+        entries.into_iter()
+    }
+
+    /// Returns the entries of the vocabulary sorted by token (in byte order), for example to walk
+    /// candidate substrings in order or to find the position where a token would be inserted with
+    /// `binary_search_by`. The tokens are borrowed from the values and sorted on every call.
+    ///
+    /// # Returns
+    /// - `Vec<(&str, i64)>`: (token, id) pairs sorted by token
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// let sorted_tokens = vocab.sorted_tokens();
+    /// let position = sorted_tokens.binary_search_by(|(token, _)| token.cmp(&"hello"));
+    /// ```
+    fn sorted_tokens(&self) -> Vec<(&str, i64)> {
+        let mut entries: Vec<(&str, i64)> = self
+            .values()
+            .iter()
+            .map(|(token, id)| (token.as_str(), *id))
+            .collect();
+        entries.sort_unstable_by_key(|(token, _)| *token);
+        entries
+    }
+
+    /// Resolves a special token string to its id. Several special token strings may map to the same
+    /// id (for example the SEP and EOS tokens of M2M100): this id is returned for any of them, so
+    /// that masks built from the special tokens do not treat aliases as separate tokens.
+    ///
+    /// # Parameters
+    /// - token (`&str`): special token string to resolve
+    ///
+    /// # Returns
+    /// - `Option<i64>`: id of the special token, `None` if the token is not a special token
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let sep_id = vocab.canonical_special_id(M2M100Vocab::sep_value());
+    /// let eos_id = vocab.canonical_special_id(M2M100Vocab::eos_value());
+    /// assert_eq!(sep_id, eos_id);
+    /// ```
+    fn canonical_special_id(&self, token: &str) -> Option<i64> {
+        self.special_values().get(token).copied()
+    }
+
+    /// Returns the ids of the special tokens, with ids shared by several special token strings
+    /// appearing only once.
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: deduplicated ids of the special tokens, sorted in ascending order
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let special_ids = vocab.unique_special_ids();
+    /// ```
+    fn unique_special_ids(&self) -> Vec<i64> {
+        let mut ids: Vec<i64> = self.special_values().values().copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the set of ids of the special tokens, for example to build a special tokens mask or
+    /// to strip control tokens from a sequence of ids.
+    ///
+    /// # Returns
+    /// - `HashSet<i64>`: ids of the special tokens
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let special_ids = vocab.special_token_ids();
+    /// let mask: Vec<bool> = [0, 4, 5, 2]
+    ///     .iter()
+    ///     .map(|id| special_ids.contains(id))
+    ///     .collect();
+    /// ```
+    fn special_token_ids(&self) -> HashSet<i64> {
+        self.special_values().values().copied().collect()
+    }
+
+    /// Returns the number of special tokens. Aliased special tokens sharing an id are counted
+    /// separately: use `special_token_ids` for the number of distinct ids.
+    ///
+    /// # Returns
+    /// - `usize`: number of special values
+    fn num_special_tokens(&self) -> usize {
+        self.special_values().len()
+    }
+
+    /// Returns a one-line summary of the vocabulary (size, number of special tokens and unknown
+    /// value), to confirm which vocabulary was loaded without printing its content.
+    ///
+    /// # Returns
+    /// - `String`: summary of the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap();
+    /// println!("Loaded vocabulary: {}", vocab.summary());
+    /// ```
+    fn summary(&self) -> String {
+        format!(
+            "size: {}, special tokens: {}, unknown value: {}",
+            self.len(),
+            self.num_special_tokens(),
+            self.get_unknown_value()
+        )
+    }
+
+    /// Returns the raw UTF-8 bytes of the token for an id, for inspection of tokens containing
+    /// non-printable or combining characters.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id
+    ///
+    /// # Returns
+    /// - `Option<Vec<u8>>`: UTF-8 bytes of the token, `None` if the id is not in the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap();
+    /// let bytes = vocab.token_bytes(42);
+    /// ```
+    fn token_bytes(&self, id: i64) -> Option<Vec<u8>> {
+        self.indices()
+            .get(&id)
+            .map(|token| token.as_bytes().to_vec())
+    }
+
+    /// Returns the characters of the token for an id, with their byte position in the token.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id
+    ///
+    /// # Returns
+    /// - `Option<Vec<(usize, char)>>`: byte positions and characters of the token, `None` if the id is not in the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap();
+    /// let char_indices = vocab.token_char_indices(42);
+    /// ```
+    fn token_char_indices(&self, id: i64) -> Option<Vec<(usize, char)>> {
+        self.indices()
+            .get(&id)
+            .map(|token| token.char_indices().collect())
+    }
+
+    /// Converts an id to a human-readable representation of its token, for display and debugging
+    /// purposes. In contrast with `id_to_token`, the SentencePiece meta symbol `▁` is rendered as a
+    /// space and byte pieces of ASCII characters (e.g. `<0x0A>`) are rendered as the corresponding
+    /// character. Other byte pieces and special tokens are returned unchanged.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id to render
+    ///
+    /// # Returns
+    /// - `String`: display string for the token
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{AlbertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = AlbertVocab::from_file(path).unwrap();
+    /// let token = vocab.pretty_token(42);
+    /// ```
+    fn pretty_token(&self, id: i64) -> String {
+        let token = self.id_to_token(&id);
+        if self.special_values().contains_key(&token) {
+            return token;
+        }
+        match parse_byte_piece(&token) {
+            Some(byte) if byte.is_ascii() => (byte as char).to_string(),
+            Some(_) => token,
+            None => token.replace('\u{2581}', " "),
+        }
+    }
+
+    /// Checks the internal consistency of the vocabulary, for example after loading an untrusted
+    /// vocabulary file or after modifying the vocabulary. The following invariants are checked:
+    /// - ids of the values are unique
+    /// - `values` and `indices` are inverse mappings of each other
+    /// - every special value is present in `values` with the same id
+    /// - every entry of `special_indices` is the inverse of a special value
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: `VocabularyParsingError` describing the first inconsistency found
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// vocab.validate().unwrap();
+    /// ```
+    fn validate(&self) -> Result<(), TokenizerError> {
+        let mut entries: Vec<(&str, i64)> = self.iter().collect();
+        entries.sort_unstable_by_key(|(token, id)| (*id, *token));
+        for window in entries.windows(2) {
+            if window[0].1 == window[1].1 {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Tokens {} and {} are both mapped to id {}",
+                    window[0].0, window[1].0, window[0].1
+                )));
+            }
+        }
+        for (token, id) in entries.iter() {
+            match self.indices().get(id) {
+                Some(indexed_token) if indexed_token == token => {}
+                Some(indexed_token) => {
+                    return Err(TokenizerError::VocabularyParsingError(format!(
+                        "Token {} is mapped to id {}, but id {} is mapped to token {}",
+                        token, id, id, indexed_token
+                    )));
+                }
+                None => {
+                    return Err(TokenizerError::VocabularyParsingError(format!(
+                        "Token {} is mapped to id {}, which is missing from the indices",
+                        token, id
+                    )));
+                }
+            }
+        }
+        if let Some((id, token)) = self
+            .iter_sorted()
+            .find(|(id, token)| self.values().get(*token) != Some(id))
+        {
+            return Err(TokenizerError::VocabularyParsingError(format!(
+                "Id {} is mapped to token {}, which is missing from the values",
+                id, token
+            )));
+        }
+
+        let mut special_entries: Vec<(&str, i64)> = self.iter_special().collect();
+        special_entries.sort_unstable_by_key(|(token, id)| (*id, *token));
+        for (token, id) in special_entries {
+            if self.values().get(token) != Some(&id) {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Special value {} is mapped to id {}, which does not match the values",
+                    token, id
+                )));
+            }
+        }
+        let mut special_indices: Vec<(&i64, &String)> = self.special_indices().iter().collect();
+        special_indices.sort_unstable();
+        for (id, token) in special_indices {
+            if self.special_values().get(token) != Some(id) {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Special id {} is mapped to token {}, which does not match the special values",
+                    id, token
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the values of this vocabulary with another one (e.g. the vocabulary of an updated
+    /// checkpoint), to remap embedding rows. Special values are part of the values and are compared
+    /// as regular tokens.
+    ///
+    /// # Parameters
+    /// - other (`&Self`): new vocabulary to compare with this one
+    ///
+    /// # Returns
+    /// - `VocabDiff`: tokens added in the other vocabulary, removed from this one and mapped to a different id
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    ///
+    /// let old_vocab = M2M100Vocab::from_file("path/to/old/vocab").unwrap();
+    /// let new_vocab = M2M100Vocab::from_file("path/to/new/vocab").unwrap();
+    /// let diff = old_vocab.diff(&new_vocab);
+    /// for (token, old_id, new_id) in diff.reindexed {
+    ///     println!("{}: {} -> {}", token, old_id, new_id);
+    /// }
+    /// ```
+    fn diff(&self, other: &Self) -> VocabDiff
+    where
+        Self: Sized,
+    {
+        let mut diff = VocabDiff::default();
+        for (token, id) in self.values() {
+            match other.values().get(token) {
+                Some(other_id) if other_id != id => {
+                    diff.reindexed.push((token.clone(), *id, *other_id))
+                }
+                Some(_) => {}
+                None => diff.removed.push((token.clone(), *id)),
+            }
+        }
+        for (token, id) in other.values() {
+            if !self.values().contains_key(token) {
+                diff.added.push((token.clone(), *id));
+            }
+        }
+        diff.added.sort_unstable_by_key(|(_, id)| *id);
+        diff.removed.sort_unstable_by_key(|(_, id)| *id);
+        diff.reindexed.sort_unstable_by_key(|(_, id, _)| *id);
+        diff
+    }
+}
+
+/// # JsonVocab
+/// Vocabularies read from a JSON file mapping tokens to ids, which can be written back in the same
+/// format. Vocabularies read from other formats (flat text files or SentencePiece models) do not
+/// implement this trait, as the JSON output could not be read back by their `from_file`.
+pub trait JsonVocab: Vocab {
+    /// Write the vocabulary (including the special values, which are a subset of the regular values)
+    /// to a writer as a JSON object mapping tokens to ids, in the format read by `read_json_vocab`.
+    /// Entries are written in ascending id order.
+    ///
+    /// # Parameters
+    /// - writer (`W: Write`): writer receiving the JSON vocabulary
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: error if the vocabulary could not be written
+    fn to_writer<W: Write>(&self, writer: W) -> Result<(), TokenizerError>
+    where
+        Self: Sized,
+    {
+        let mut serializer = serde_json::Serializer::new(writer);
+        serializer
+            .collect_map(self.values().iter().sorted_by_key(|(_, id)| **id))
+            .map_err(|e| TokenizerError::IOError(e.to_string()))
+    }
+
+    /// Write the vocabulary to a JSON file, in the format read by `read_json_vocab_file` and by the
+    /// `from_file` method of the vocabulary
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the output file
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: error if the file could not be written
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{JsonVocab, M2M100Vocab, Vocab};
+    /// let vocab = M2M100Vocab::from_file("path/to/vocab/file").unwrap();
+    ///
+    /// vocab.to_file("path/to/output/file").unwrap();
+    /// ```
+    fn to_file(&self, path: &str) -> Result<(), TokenizerError>
+    where
+        Self: Sized,
+    {
+        let f = File::create(path).map_err(|e| {
+            TokenizerError::IOError(format!(
+                "{} vocabulary file could not be created: {}",
+                path, e
+            ))
+        })?;
+        let mut writer = BufWriter::new(f);
+        self.to_writer(&mut writer)?;
+        writer
+            .flush()
+            .map_err(|e| TokenizerError::IOError(e.to_string()))
+    }
+}
+
+/// # VocabMut
+/// Mutable access to the mappings of a vocabulary, and the methods editing a vocabulary (adding,
+/// renaming or merging tokens). Vocabularies implement this trait in addition to `Vocab`.
+pub trait VocabMut: Vocab {
+    ///Return a mutable reference to the map of token strings to IDs
+    fn values_mut(&mut self) -> &mut HashMap<String, i64>;
+
+    ///Return a mutable reference to the map of token IDs to strings
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String>;
+
+    ///Return a mutable reference to the map of special token strings to IDs
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64>;
+
+    ///Return a mutable reference to the map of special token IDs to strings
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String>;
+
+    /// Read a vocabulary from file and merge the additional tokens of a HuggingFace-style
+    /// `added_tokens.json` file (JSON object mapping tokens absent from the vocabulary file to their
+    /// ids). The file is validated as the vocabulary files read by `read_json_vocab_value`, and the
+    /// added tokens are inserted with `insert_token`. Added tokens matching one of the special
+    /// values expected by the vocabulary are registered as special values.
+    ///
+    /// # Parameters
+    /// - vocab_path (`&str`): path to the vocabulary file
+    /// - added_tokens_path (`&str`): path to the added tokens JSON file
+    ///
+    /// # Returns
+    /// - `Result<Self, TokenizerError>`: vocabulary read, error if the added tokens file is not a valid JSON vocabulary, if the id of an added token is already used by another token, or if an added token is already in the vocabulary with a different id
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab, VocabMut};
+    ///
+    /// let vocab = M2M100Vocab::from_file_with_added_tokens(
+    ///     "path/to/vocab/file",
+    ///     "path/to/added_tokens/file",
+    /// );
+    /// ```
+    fn from_file_with_added_tokens(
+        vocab_path: &str,
+        added_tokens_path: &str,
+    ) -> Result<Self, TokenizerError>
+    where
+        Self: std::marker::Sized,
+    {
+        let mut vocab = Self::from_file(vocab_path)?;
+        let f = File::open(added_tokens_path).map_err(|e| {
+            TokenizerError::FileNotFound(format!(
+                "{} added tokens file not found :{}",
+                added_tokens_path, e
+            ))
+        })?;
+        let value: serde_json::Value =
+            serde_json::from_reader(BufReader::new(f)).map_err(json_parse_error)?;
+        if matches!(value.as_object(), Some(entries) if entries.is_empty()) {
+            return Ok(vocab);
+        }
+        let added_tokens = parse_json_vocab_entries(&value, false)?;
+
+        let expected_special_values: Vec<String> = vocab
+            .expected_special_values()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        for (token, id) in added_tokens.into_iter().sorted_by_key(|(_, id)| *id) {
+            if let Some(existing_token) = vocab.indices().get(&id) {
+                if existing_token != &token {
+                    return Err(TokenizerError::VocabularyParsingError(format!(
+                        "Id {} of added token {} is already used by token {}",
+                        id, token, existing_token
+                    )));
+                }
+            } else if let Some(existing_id) = vocab.values().get(&token) {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Added token {} with id {} is already in the vocabulary with id {}",
+                    token, id, existing_id
+                )));
+            } else {
+                vocab.insert_token(&token, id);
+            }
+            if expected_special_values.contains(&token) {
+                vocab.special_values_mut().insert(token.clone(), id);
+                vocab.special_indices_mut().insert(id, token);
+            }
+        }
+        Ok(vocab)
+    }
+
+    /// Rewrites every token of the vocabulary by applying a mapping function to its string
+    /// representation, keeping the token ids unchanged. Special tokens are left untouched as their
+    /// string values are defined by the vocabulary type. The reverse `indices` mapping is rebuilt
+    /// after the transformation.
+    ///
+    /// # Parameters
+    /// - f (`Fn(&str) -> String`): function mapping the existing token string to its new value
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: error if two tokens are mapped to the same string. The vocabulary is then left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab, VocabMut};
+    /// let path = "path/to/file";
+    ///
+    /// let mut vocab = BertVocab::from_file(path).unwrap();
+    /// vocab.map_tokens(|token| token.to_lowercase()).unwrap();
+    /// ```
+    fn map_tokens<F: Fn(&str) -> String>(&mut self, f: F) -> Result<(), TokenizerError>
+    where
+        Self: Sized,
+    {
+        let mut new_values: HashMap<String, i64> = HashMap::with_capacity(self.values().len());
+        for (token, id) in self.values() {
+            let new_token = if self.special_values().contains_key(token) {
+                token.clone()
+            } else {
+                f(token)
+            };
+            if let Some(existing_id) = new_values.get(&new_token) {
+                return Err(TokenizerError::ValueError(format!(
+                    "Mapping tokens results in a collision for {} (ids {} and {})",
+                    new_token, existing_id, id
+                )));
+            }
+            new_values.insert(new_token, *id);
+        }
+        *self.indices_mut() = swap_key_values(&new_values);
+        *self.values_mut() = new_values;
+        Ok(())
+    }
+
+    /// Inserts a token with the given id in the values and reverse `indices` mapping of the
+    /// vocabulary. This is the insertion step of `add_tokens` and `from_file_with_added_tokens`, and
+    /// vocabularies with state derived from their tokens (e.g. byte fallback pieces or language
+    /// codes) override it to update that state. The caller ensures that neither the token nor the
+    /// id is already used.
+    ///
+    /// # Parameters
+    /// - token (`&str`): token to insert
+    /// - id (`i64`): id of the token
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.values_mut().insert(token.to_owned(), id);
+        self.indices_mut().insert(id, token.to_owned());
+    }
+
+    /// Adds tokens to the vocabulary after loading (e.g. domain-specific tokens or sentinel tokens
+    /// added when fine-tuning a model). New tokens are assigned the next free ids, following the
+    /// largest id of the vocabulary, and the reverse `indices` mapping is updated. Tokens already
+    /// present in the vocabulary keep their existing id. New tokens are inserted with
+    /// `insert_token`.
+    ///
+    /// # Parameters
+    /// - tokens (`&[String]`): tokens to add
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: ids of the tokens, in the order provided
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{T5Vocab, Vocab, VocabMut};
+    /// let path = "path/to/file";
+    ///
+    /// let mut vocab = T5Vocab::from_file(path).unwrap();
+    /// let ids = vocab.add_tokens(&["<extra_id_100>".to_string(), "<extra_id_101>".to_string()]);
+    /// ```
+    fn add_tokens(&mut self, tokens: &[String]) -> Vec<i64> {
+        let mut next_id = self
+            .indices()
+            .keys()
+            .chain(self.special_indices().keys())
+            .max()
+            .map_or(0, |id| id + 1);
+        let mut ids = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if let Some(id) = self
+                .special_values()
+                .get(token)
+                .or_else(|| self.values().get(token))
+            {
+                ids.push(*id);
+                continue;
+            }
+            self.insert_token(token, next_id);
+            ids.push(next_id);
+            next_id += 1;
+        }
+        ids
+    }
+
+    /// Merges another vocabulary into this one (e.g. a domain-specific extension of a shared base
+    /// vocabulary). Tokens of the other vocabulary absent from this one are added with the next
+    /// free ids, in the id order of the other vocabulary, while existing tokens keep their id.
+    /// Tokens present in both vocabularies with different ids keep the id of this vocabulary and
+    /// are counted as conflicts. Special values of the other vocabulary are registered as special
+    /// values. The vocabulary is left unchanged if an error is returned.
+    ///
+    /// The ids of the other vocabulary are remapped by the merge: the mapping returned is indexed by
+    /// the id of a token in the other vocabulary and gives its id in the merged vocabulary (`None`
+    /// for ids not used by the other vocabulary), so that the embedding rows of a model trained
+    /// with the other vocabulary can be scattered to their merged ids.
+    ///
+    /// # Parameters
+    /// - other (`&Self`): vocabulary to merge into this one
+    ///
+    /// # Returns
+    /// - `Result<(Vec<Option<i64>>, usize), TokenizerError>`: mapping of the ids of the other vocabulary to the merged ids and number of tokens with conflicting ids, error if a special value of the other vocabulary is not one of its values, if the other vocabulary has a negative id or if its largest id is too large for the mapping to be allocated
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab, VocabMut};
+    ///
+    /// let mut vocab = M2M100Vocab::from_file("path/to/base/vocab").unwrap();
+    /// let domain_vocab = M2M100Vocab::from_file("path/to/domain/vocab").unwrap();
+    /// let (id_mapping, conflicts) = vocab.merge(&domain_vocab).unwrap();
+    /// ```
+    fn merge(&mut self, other: &Self) -> Result<(Vec<Option<i64>>, usize), TokenizerError>
+    where
+        Self: Sized,
+    {
+        merge_vocab(self, other)
+    }
+}
+
+/// Implements the `Vocab` methods reading the mappings and special tokens of a wrapper vocabulary,
+/// and the token lookups other than `token_to_id`, by forwarding them to the wrapped vocabulary
+/// stored in the field `$vocab`. The wrapper then uses the implementations of the wrapped
+/// vocabulary, including the default methods it overrides.
+macro_rules! forward_vocab_methods {
+    ($vocab:ident) => {
+        fn get_unknown_value(&self) -> &str {
+            self.$vocab.get_unknown_value()
+        }
+
+        fn expected_special_values(&self) -> Vec<&str> {
+            self.$vocab.expected_special_values()
+        }
+
+        fn special_token_config(&self) -> crate::vocab::SpecialTokenConfig {
+            self.$vocab.special_token_config()
+        }
+
+        fn values(&self) -> &std::collections::HashMap<String, i64> {
+            self.$vocab.values()
+        }
+
+        fn indices(&self) -> &std::collections::HashMap<i64, String> {
+            self.$vocab.indices()
+        }
+
+        fn special_values(&self) -> &std::collections::HashMap<String, i64> {
+            self.$vocab.special_values()
+        }
+
+        fn special_indices(&self) -> &std::collections::HashMap<i64, String> {
+            self.$vocab.special_indices()
+        }
+
+        fn id_to_token(&self, id: &i64) -> String {
+            self.$vocab.id_to_token(id)
+        }
+
+        fn unknown_id_for_char(&self, character: char) -> i64 {
+            self.$vocab.unknown_id_for_char(character)
+        }
+
+        fn is_word_start(&self, id: i64) -> bool {
+            self.$vocab.is_word_start(id)
+        }
+
+        fn try_token_to_id(&self, token: &str) -> Option<i64> {
+            self.$vocab.try_token_to_id(token)
+        }
+
+        fn contains_token(&self, token: &str) -> bool {
+            self.$vocab.contains_token(token)
+        }
+
+        fn render_ids(&self, ids: &[i64]) -> Vec<crate::vocab::TokenKind> {
+            self.$vocab.render_ids(ids)
+        }
+
+        fn summary(&self) -> String {
+            self.$vocab.summary()
+        }
+    };
+}
+
+pub(crate) use forward_vocab_methods;
+
+/// Implements `Vocab::special_token_config` for a vocabulary type from its associated functions
+/// returning the special token strings (e.g. `BertVocab::cls_value()`). The roles defined by the
+/// vocabulary are listed by the name of their function, the other roles are set to `None`.
+macro_rules! special_token_config {
+    ($vocab:ident, $($role:ident),*) => {
+        fn special_token_config(&self) -> crate::vocab::SpecialTokenConfig {
+            crate::vocab::SpecialTokenConfig {
+                $($role: Some($vocab::$role().into()),)*
+                ..crate::vocab::SpecialTokenConfig::new($vocab::unknown_value())
+            }
+        }
+    };
+}
+
+pub(crate) use special_token_config;
+
+/// Implements `PartialEq` for a vocabulary type. Vocabularies are equal if their values, special
+/// values and the additional fields listed are equal (the indices are derived from the values).
+macro_rules! vocab_partial_eq {
+    ($vocab:ident $(, $field:ident)*) => {
+        impl PartialEq for $vocab {
+            fn eq(&self, other: &Self) -> bool {
+                self.values == other.values
+                    && self.special_values == other.special_values
+                    $(&& self.$field == other.$field)*
+            }
+        }
+    };
+}
+
+pub(crate) use vocab_partial_eq;
+
+/// # BaseVocab
+/// Base vocabulary with [UNK] unknown token used as a pre-tokenization step for BERT-class tokenizers.
+/// Expects a flat text vocabulary when created from file.
+#[derive(Debug, Clone)]
+pub struct BaseVocab {
+    /// A mapping of tokens as string to indices (i.e. the encoder base)
+    pub values: HashMap<String, i64>,
+
+    /// A mapping of token ids to strings (i.e. the decoder base)
+    pub indices: HashMap<i64, String>,
+
+    /// The string to use for unknown (out of vocabulary) tokens
+    pub unknown_value: &'static str,
+
+    /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
+    /// values), special values typically include things like BOS/EOS markers, class markers, mask
+    /// markers and padding markers
+    pub special_values: HashMap<String, i64>,
+
+    /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
+    pub special_indices: HashMap<i64, String>,
+}
+
+impl BaseVocab {
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a flat text vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<BaseVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<BaseVocab, TokenizerError> {
+        check_unique_ids(&values)?;
+        let mut special_values = HashMap::new();
+        let unknown_value = BaseVocab::unknown_value();
+        BaseVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(BaseVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
+    }
+}
+
+vocab_partial_eq!(BaseVocab);
+
+impl Vocab for BaseVocab {
+    fn unknown_value() -> &'static str {
+        "[UNK]"
+    }
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<BaseVocab, TokenizerError> {
+        BaseVocab::from_values(BaseVocab::read_vocab(reader)?)
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self._token_to_id(
+            token,
+            &self.values,
+            &self.special_values,
+            self.unknown_value,
+        )
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+    }
+}
+
+impl VocabMut for BaseVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::Gpt2Vocab;
+    use std::io::Write;
+
+    #[test]
+    fn test_swap_key_values() {
+        //        Given
+        let values: HashMap<String, i64> = (0..1000)
+            .map(|index| (format!("token_{}", index), index))
+            .collect();
+
+        //        When
+        let indices = swap_key_values(&values);
+
+        //        Then
+        assert_eq!(indices.len(), values.len());
+        for (token, index) in values.iter() {
+            assert_eq!(indices.get(index), Some(token));
+        }
+    }
+
+    #[test]
+    fn test_swap_key_values_duplicate_ids() {
+        //        Given
+        let values: HashMap<String, i64> = (0..1000)
+            .map(|index| (format!("token_{}", index), index % 10))
+            .collect();
+
+        //        When
+        let indices = swap_key_values(&values);
+
+        //        Then
+        let expected_indices: HashMap<i64, String> = values
+            .iter()
+            .map(|(token, &index)| (index, token.clone()))
+            .collect();
+        assert_eq!(indices, expected_indices);
+    }
+
+    #[test]
+    fn test_vocab_implementing_from_file_only() -> anyhow::Result<()> {
+        //        Given
+        struct FileOnlyVocab {
+            values: HashMap<String, i64>,
+            indices: HashMap<i64, String>,
+            special_values: HashMap<String, i64>,
+            special_indices: HashMap<i64, String>,
+        }
+
+        impl Vocab for FileOnlyVocab {
+            fn unknown_value() -> &'static str {
+                "[UNK]"
+            }
+
+            fn values(&self) -> &HashMap<String, i64> {
+                &self.values
+            }
+
+            fn indices(&self) -> &HashMap<i64, String> {
+                &self.indices
+            }
+
+            fn special_values(&self) -> &HashMap<String, i64> {
+                &self.special_values
+            }
+
+            fn special_indices(&self) -> &HashMap<i64, String> {
+                &self.special_indices
+            }
+
+            fn from_file(path: &str) -> Result<FileOnlyVocab, TokenizerError> {
+                let values = Self::read_vocab_file(path)?;
+                let mut special_values = HashMap::new();
+                Self::_register_as_special_value("[UNK]", &values, &mut special_values)?;
+                Ok(FileOnlyVocab {
+                    indices: swap_key_values(&values),
+                    special_indices: swap_key_values(&special_values),
+                    values,
+                    special_values,
+                })
+            }
+
+            fn token_to_id(&self, token: &str) -> i64 {
+                self._token_to_id(token, &self.values, &self.special_values, "[UNK]")
+            }
+
+            fn id_to_token(&self, id: &i64) -> String {
+                self._id_to_token(id, &self.indices, &self.special_indices, "[UNK]")
+            }
+        }
+
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello\nworld\n[UNK]\n!")?;
+        let path = vocab_file.into_temp_path();
+
+        //        When
+        let vocab = FileOnlyVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let from_reader = FileOnlyVocab::from_reader("hello\n[UNK]".as_bytes());
+
+        //        Then
+        assert_eq!(vocab.token_to_id("world"), 1);
+        assert_eq!(vocab.token_to_id("oov_value"), 2);
+        assert!(matches!(from_reader, Err(TokenizerError::ValueError(_))));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object() {
+        //        Given
+        let values: HashMap<String, i64> = HashMap::new();
+        let special_values: HashMap<String, i64> = HashMap::new();
+        let indices: HashMap<i64, String> = HashMap::new();
+        let special_indices: HashMap<i64, String> = HashMap::new();
+        let unknown_value = BaseVocab::unknown_value();
+
+        //        When
+        let base_vocab = BaseVocab {
+            values,
+            indices,
             unknown_value,
             special_values,
             special_indices,
-        })
+        };
+
+        //        Then
+        assert_eq!(base_vocab.unknown_value, "[UNK]");
+        assert_eq!(base_vocab.unknown_value, BaseVocab::unknown_value());
+        assert_eq!(base_vocab.values, *base_vocab.values());
+        assert_eq!(base_vocab.special_values, *base_vocab.special_values());
+    }
+
+    #[test]
+    fn test_create_object_from_file() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let target_values: HashMap<String, i64> = [
+            ("hello".to_owned(), 0),
+            ("world".to_owned(), 1),
+            ("[UNK]".to_owned(), 2),
+            ("!".to_owned(), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let special_values: HashMap<String, i64> =
+            [("[UNK]".to_owned(), 2)].iter().cloned().collect();
+
+        //        When
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(base_vocab.unknown_value, "[UNK]");
+        assert_eq!(base_vocab.values, target_values);
+        assert_eq!(base_vocab.special_values, special_values);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_from_reader() -> anyhow::Result<()> {
+        //        Given
+        let bytes: &[u8] = b"hello \n world \n [UNK] \n !";
+        let missing_path = "path/to/missing/vocab/file";
+
+        //        When
+        let base_vocab = BaseVocab::from_reader(bytes)?;
+        let json_values = BaseVocab::read_json_vocab(r#"{"hello": 0, "[UNK]": 1}"#.as_bytes())?;
+        let missing = BaseVocab::from_file(missing_path);
+
+        //        Then
+        assert_eq!(base_vocab.token_to_id("!"), 3);
+        assert_eq!(base_vocab.special_values.get("[UNK]"), Some(&2));
+        assert_eq!(json_values.get("[UNK]"), Some(&1));
+        assert!(matches!(missing, Err(TokenizerError::FileNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "sha2")]
+    fn test_create_object_from_file_verified() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello\nworld\n[UNK]\n!")?;
+        let path = vocab_file.into_temp_path();
+        let path_str = path.to_path_buf().to_str().unwrap().to_owned();
+        let expected_sha256 = crate::vocab::file_sha256(&path_str)?;
+        let mut wrong_sha256 = expected_sha256;
+        wrong_sha256[0] ^= 1;
+
+        //        When
+        let base_vocab = BaseVocab::from_file_verified(&path_str, &expected_sha256)?;
+        let corrupted = BaseVocab::from_file_verified(&path_str, &wrong_sha256);
+
+        //        Then
+        assert_eq!(base_vocab.token_to_id("world"), 1);
+        assert_eq!(base_vocab.source_digest(), Some(expected_sha256));
+        assert_eq!(base_vocab.into_inner().source_digest(), None);
+        assert!(corrupted.is_err());
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_create_object_from_file_without_unknown_token() {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new().unwrap();
+        write!(vocab_file, "hello \n world \n !").unwrap();
+        let path = vocab_file.into_temp_path();
+
+        //        When & Then
+        let _base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_encode_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(base_vocab.token_to_id("hello"), 0);
+        assert_eq!(base_vocab.token_to_id("world"), 1);
+        assert_eq!(base_vocab.token_to_id("!"), 3);
+        assert_eq!(base_vocab.token_to_id("[UNK]"), 2);
+        assert_eq!(base_vocab.token_to_id("oov_value"), 2);
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(base_vocab.id_to_token(&(0_i64)), "hello");
+        assert_eq!(base_vocab.id_to_token(&(1_i64)), "world");
+        assert_eq!(base_vocab.id_to_token(&(3_i64)), "!");
+        assert_eq!(base_vocab.id_to_token(&(2_i64)), "[UNK]");
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_tokens_and_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let ids = base_vocab.convert_tokens_to_ids(&["hello", "oov_value", "!", "world"]);
+        let tokens = base_vocab.convert_ids_to_tokens(&[1, 42, 0]);
+
+        //        Then
+        assert_eq!(ids, vec![0, 2, 3, 1]);
+        assert_eq!(tokens, vec!["world", "[UNK]", "hello"]);
+        assert!(base_vocab.convert_tokens_to_ids(&[]).is_empty());
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokens_to_ids_counting_unknown() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+
+        //        When
+        let (ids, unknown_count) =
+            base_vocab.tokens_to_ids_counting_unknown(&["hello", "oov_value", "[UNK]", "wrld"]);
+
+        //        Then
+        assert_eq!(ids, vec![0, 2, 2, 2]);
+        assert_eq!(unknown_count, 2);
+        assert_eq!(base_vocab.tokens_to_ids_counting_unknown(&[]), (vec![], 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_token_to_id() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+
+        //        When & Then
+        assert_eq!(base_vocab.try_token_to_id("world"), Some(1));
+        assert_eq!(base_vocab.try_token_to_id("[UNK]"), Some(2));
+        assert_eq!(base_vocab.try_token_to_id("oov_value"), None);
+        assert_eq!(base_vocab.try_id_to_token(3), Some("!".to_string()));
+        assert_eq!(base_vocab.try_id_to_token(2), Some("[UNK]".to_string()));
+        assert_eq!(base_vocab.try_id_to_token(42), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_bytes_to_id() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n w\u{f6}rld \n [UNK] \n !".as_bytes())?;
+
+        //        When & Then
+        assert_eq!(base_vocab.token_bytes_to_id(b"hello"), Some(0));
+        assert_eq!(
+            base_vocab.token_bytes_to_id("w\u{f6}rld".as_bytes()),
+            Some(1)
+        );
+        assert_eq!(base_vocab.token_bytes_to_id(b"[UNK]"), Some(2));
+        assert_eq!(base_vocab.token_bytes_to_id(b"oov_value"), None);
+        assert_eq!(base_vocab.token_bytes_to_id(&[b'w', 0xC3]), None);
+        assert_eq!(base_vocab.token_bytes_to_id(&[0xFF, 0xFE]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let mut duplicate_id_vocab = base_vocab.clone();
+        duplicate_id_vocab.values.insert("hi".to_string(), 0);
+        let mut missing_index_vocab = base_vocab.clone();
+        missing_index_vocab.indices.remove(&1);
+        let mut extra_index_vocab = base_vocab.clone();
+        extra_index_vocab.indices.insert(4, "hi".to_string());
+        let mut special_value_vocab = base_vocab.clone();
+        special_value_vocab
+            .special_values
+            .insert("[UNK]".to_string(), 3);
+
+        //        When & Then
+        assert!(base_vocab.validate().is_ok());
+        assert!(matches!(
+            duplicate_id_vocab.validate(),
+            Err(TokenizerError::VocabularyParsingError(message))
+                if message == "Tokens hello and hi are both mapped to id 0"
+        ));
+        assert!(matches!(
+            missing_index_vocab.validate(),
+            Err(TokenizerError::VocabularyParsingError(message)) if message.contains("world")
+        ));
+        assert!(matches!(
+            extra_index_vocab.validate(),
+            Err(TokenizerError::VocabularyParsingError(message)) if message.contains("hi")
+        ));
+        assert!(matches!(
+            special_value_vocab.validate(),
+            Err(TokenizerError::VocabularyParsingError(message)) if message.contains("[UNK]")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff() -> anyhow::Result<()> {
+        //        Given
+        let old_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let mut new_values = old_vocab.values.clone();
+        new_values.insert("world".to_string(), 4);
+        new_values.insert("new_token".to_string(), 5);
+        let new_vocab = BaseVocab::from_values(new_values)?;
+
+        //        When
+        let diff = old_vocab.diff(&new_vocab);
+        let reverse_diff = new_vocab.diff(&old_vocab);
+
+        //        Then
+        assert_eq!(diff.added, vec![("new_token".to_string(), 5)]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.reindexed, vec![("world".to_string(), 1, 4)]);
+        assert_eq!(reverse_diff.removed, vec![("new_token".to_string(), 5)]);
+        assert_eq!(reverse_diff.reindexed, vec![("world".to_string(), 4, 1)]);
+        assert!(reverse_diff.added.is_empty());
+        assert!(old_vocab.diff(&old_vocab).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_len() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let empty_vocab = BaseVocab {
+            values: HashMap::new(),
+            indices: HashMap::new(),
+            unknown_value: "[UNK]",
+            special_values: HashMap::new(),
+            special_indices: HashMap::new(),
+        };
+
+        //        When & Then
+        assert_eq!(base_vocab.len(), 4);
+        assert!(!base_vocab.is_empty());
+        assert_eq!(empty_vocab.len(), 0);
+        assert!(empty_vocab.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_contains_token_and_id() -> anyhow::Result<()> {
+        //        Given
+        let mut base_vocab = BaseVocab::from_reader("hello \n world \n [UNK]".as_bytes())?;
+        base_vocab
+            .special_values
+            .insert("[SPECIAL]".to_string(), 10);
+        base_vocab
+            .special_indices
+            .insert(10, "[SPECIAL]".to_string());
+
+        //        When & Then
+        assert!(base_vocab.contains_token("hello"));
+        assert!(base_vocab.contains_token("[UNK]"));
+        assert!(base_vocab.contains_token("[SPECIAL]"));
+        assert!(!base_vocab.contains_token("oov_value"));
+        assert!(base_vocab.contains_id(1));
+        assert!(base_vocab.contains_id(10));
+        assert!(!base_vocab.contains_id(3));
+        assert!(!base_vocab.contains_id(-1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let mut base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        base_vocab.map_tokens(|token| token.to_uppercase())?;
+
+        //        Then
+        assert_eq!(base_vocab.token_to_id("HELLO"), 0);
+        assert_eq!(base_vocab.token_to_id("WORLD"), 1);
+        assert_eq!(base_vocab.token_to_id("hello"), 2);
+        assert_eq!(base_vocab.id_to_token(&(1_i64)), "WORLD");
+        assert_eq!(base_vocab.id_to_token(&(2_i64)), "[UNK]");
+        assert_eq!(base_vocab.values.len(), base_vocab.indices.len());
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut base_vocab = BaseVocab::from_reader("hello \n world \n [UNK]".as_bytes())?;
+        let new_tokens = [
+            "<extra_id_0>".to_string(),
+            "world".to_string(),
+            "<extra_id_1>".to_string(),
+            "<extra_id_0>".to_string(),
+        ];
+
+        //        When
+        let ids = base_vocab.add_tokens(&new_tokens);
+
+        //        Then
+        assert_eq!(ids, vec![3, 1, 4, 3]);
+        assert_eq!(base_vocab.len(), 5);
+        assert_eq!(base_vocab.token_to_id("<extra_id_1>"), 4);
+        assert_eq!(base_vocab.id_to_token(&3), "<extra_id_0>");
+        Ok(())
     }
 
-    fn token_to_id(&self, token: &str) -> i64 {
-        self._token_to_id(
-            token,
-            &self.values,
-            &self.special_values,
-            self.unknown_value,
-        )
+    #[test]
+    fn test_merge_id_mapping() -> anyhow::Result<()> {
+        //        Given
+        let mut base_vocab = BaseVocab::from_reader("hello \n world \n [UNK]".as_bytes())?;
+        let other_values: HashMap<String, i64> =
+            [("[UNK]", 0), ("world", 1), ("!", 3), ("hello", 5), ("?", 6)]
+                .iter()
+                .map(|(token, id)| (token.to_string(), *id))
+                .collect();
+        let other_vocab = BaseVocab::from_values(other_values)?;
+
+        //        When
+        let (id_mapping, conflicts) = base_vocab.merge(&other_vocab)?;
+
+        //        Then
+        assert_eq!(conflicts, 2);
+        assert_eq!(
+            id_mapping,
+            vec![Some(2), Some(1), None, Some(3), None, Some(0), Some(4)]
+        );
+        let mapped_ids = id_mapping.iter().flatten().collect::<HashSet<_>>();
+        assert_eq!(mapped_ids.len(), other_vocab.len());
+        for (old_id, new_id) in id_mapping.iter().enumerate() {
+            match new_id {
+                Some(new_id) => assert_eq!(
+                    base_vocab.id_to_token(new_id),
+                    other_vocab.id_to_token(&(old_id as i64))
+                ),
+                None => assert!(!other_vocab.contains_id(old_id as i64)),
+            }
+        }
+        Ok(())
     }
 
-    fn id_to_token(&self, id: &i64) -> String {
-        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+    #[test]
+    fn test_merge_special_value_only_in_special_values() -> anyhow::Result<()> {
+        //        Given
+        let mut base_vocab = BaseVocab::from_reader("hello \n world \n [UNK]".as_bytes())?;
+        base_vocab.values_mut().remove("[UNK]");
+        base_vocab.indices_mut().remove(&2);
+        let other_vocab = BaseVocab::from_reader("[UNK] \n hello \n !".as_bytes())?;
+
+        //        When
+        let (id_mapping, conflicts) = base_vocab.merge(&other_vocab)?;
+
+        //        Then
+        assert_eq!(conflicts, 2);
+        assert_eq!(id_mapping, vec![Some(2), Some(0), Some(3)]);
+        assert_eq!(base_vocab.token_to_id("[UNK]"), 2);
+        assert_eq!(base_vocab.token_to_id("!"), 3);
+        Ok(())
     }
-}
 
-//==============================
-// Unit tests
-//==============================
-#[cfg(test)]
-mod tests {
-    extern crate anyhow;
+    #[test]
+    fn test_merge_invalid_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut base_vocab = BaseVocab::from_reader("hello \n world \n [UNK]".as_bytes())?;
+        let sparse_values: HashMap<String, i64> = [("[UNK]", 0), ("hello", 1 << 62)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let negative_values: HashMap<String, i64> = [("[UNK]", 0), ("hello", -1)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let sparse_vocab = BaseVocab::from_values(sparse_values)?;
+        let negative_vocab = BaseVocab::from_values(negative_values)?;
 
-    use super::*;
-    use std::io::Write;
+        //        When
+        let sparse_result = base_vocab.merge(&sparse_vocab);
+        let negative_result = base_vocab.merge(&negative_vocab);
+
+        //        Then
+        assert!(matches!(sparse_result, Err(TokenizerError::ValueError(_))));
+        assert!(matches!(
+            negative_result,
+            Err(TokenizerError::ValueError(_))
+        ));
+        assert_eq!(base_vocab.len(), 3);
+        Ok(())
+    }
 
     #[test]
-    fn test_create_object() {
+    fn test_map_tokens_collision() -> anyhow::Result<()> {
         //        Given
-        let values: HashMap<String, i64> = HashMap::new();
-        let special_values: HashMap<String, i64> = HashMap::new();
-        let indices: HashMap<i64, String> = HashMap::new();
-        let special_indices: HashMap<i64, String> = HashMap::new();
-        let unknown_value = BaseVocab::unknown_value();
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n Hello \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let mut base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
         //        When
-        let base_vocab = BaseVocab {
-            values,
-            indices,
-            unknown_value,
-            special_values,
-            special_indices,
-        };
+        let result = base_vocab.map_tokens(|token| token.to_lowercase());
 
         //        Then
-        assert_eq!(base_vocab.unknown_value, "[UNK]");
-        assert_eq!(base_vocab.unknown_value, BaseVocab::unknown_value());
-        assert_eq!(base_vocab.values, *base_vocab.values());
-        assert_eq!(base_vocab.special_values, *base_vocab.special_values());
+        assert!(result.is_err());
+        assert_eq!(base_vocab.token_to_id("Hello"), 1);
+
+        drop(path);
+        Ok(())
     }
 
     #[test]
-    fn test_create_object_from_file() -> anyhow::Result<()> {
+    fn test_ids_matching() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n 42 \n [UNK] \n 7 \n w0rld")?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let numeric_ids =
+            base_vocab.ids_matching(|token| token.chars().all(|c| c.is_ascii_digit()));
+        let digit_ids = base_vocab.ids_matching(|token| token.chars().any(|c| c.is_ascii_digit()));
+        let no_ids = base_vocab.ids_matching(|token| token.is_empty());
+
+        //        Then
+        assert_eq!(numeric_ids, vec![1, 3]);
+        assert_eq!(digit_ids, vec![1, 3, 4]);
+        assert!(no_ids.is_empty());
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_sorted() -> anyhow::Result<()> {
         //        Given
         let mut vocab_file = tempfile::NamedTempFile::new()?;
         write!(vocab_file, "hello \n world \n [UNK] \n !")?;
         let path = vocab_file.into_temp_path();
-        let target_values: HashMap<String, i64> = [
-            ("hello".to_owned(), 0),
-            ("world".to_owned(), 1),
-            ("[UNK]".to_owned(), 2),
-            ("!".to_owned(), 3),
-        ]
-        .iter()
-        .cloned()
-        .collect();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
-        let special_values: HashMap<String, i64> =
-            [("[UNK]".to_owned(), 2)].iter().cloned().collect();
+        //        When
+        let entries = base_vocab.iter_sorted().collect::<Vec<(i64, &str)>>();
+
+        //        Then
+        assert_eq!(
+            entries,
+            vec![(0, "hello"), (1, "world"), (2, "[UNK]"), (3, "!")]
+        );
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eq() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let reloaded_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let mut extended_vocab = base_vocab.clone();
+        extended_vocab.add_tokens(&["new_token".to_string()]);
+        let mut special_vocab = base_vocab.clone();
+        special_vocab.special_values.insert("!".to_string(), 3);
+
+        //        When & Then
+        assert_eq!(base_vocab, reloaded_vocab);
+        assert_ne!(base_vocab, extended_vocab);
+        assert_ne!(base_vocab, special_vocab);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorted_tokens() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n ! \n help".as_bytes())?;
+
+        //        When
+        let sorted_tokens = base_vocab.sorted_tokens();
+
+        //        Then
+        assert_eq!(
+            sorted_tokens,
+            vec![
+                ("!", 3),
+                ("[UNK]", 2),
+                ("hello", 0),
+                ("help", 4),
+                ("world", 1)
+            ]
+        );
+        assert_eq!(
+            sorted_tokens.binary_search_by(|(token, _)| token.cmp(&"helm")),
+            Err(3)
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn test_create_object_from_gz_file() -> anyhow::Result<()> {
+        //        Given
+        let vocab_file = tempfile::Builder::new().suffix(".gz").tempfile()?;
+        let mut encoder =
+            flate2::write::GzEncoder::new(vocab_file.reopen()?, flate2::Compression::default());
+        write!(encoder, "hello \n world \n [UNK] \n !")?;
+        encoder.finish()?;
+        let path = vocab_file.into_temp_path();
 
         //        When
         let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
         //        Then
-        assert_eq!(base_vocab.unknown_value, "[UNK]");
-        assert_eq!(base_vocab.values, target_values);
-        assert_eq!(base_vocab.special_values, special_values);
+        assert_eq!(base_vocab.token_to_id("hello"), 0);
+        assert_eq!(base_vocab.token_to_id("!"), 3);
+        assert_eq!(base_vocab.len(), 4);
+
         drop(path);
         Ok(())
     }
 
     #[test]
-    #[should_panic]
-    fn test_create_object_from_file_without_unknown_token() {
+    fn test_iter() -> anyhow::Result<()> {
         //        Given
-        let mut vocab_file = tempfile::NamedTempFile::new().unwrap();
-        write!(vocab_file, "hello \n world \n !").unwrap();
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let mut entries = base_vocab.iter().collect::<Vec<(&str, i64)>>();
+        entries.sort_unstable_by_key(|(_, id)| *id);
+        let special_entries = base_vocab.iter_special().collect::<Vec<(&str, i64)>>();
+
+        //        Then
+        assert_eq!(
+            entries,
+            vec![("hello", 0), ("world", 1), ("[UNK]", 2), ("!", 3)]
+        );
+        assert_eq!(special_entries, vec![("[UNK]", 2)]);
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_token_bytes_and_char_indices() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "\u{2581}e\u{0301} \n [UNK]")?;
         let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
         //        When & Then
-        let _base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap()).unwrap();
+        assert_eq!(
+            base_vocab.token_bytes(0),
+            Some(vec![0xE2, 0x96, 0x81, b'e', 0xCC, 0x81])
+        );
+        assert_eq!(
+            base_vocab.token_char_indices(0),
+            Some(vec![(0, '\u{2581}'), (3, 'e'), (4, '\u{0301}')])
+        );
+        assert_eq!(base_vocab.token_bytes(2), None);
+        assert_eq!(base_vocab.token_char_indices(2), None);
+
+        drop(path);
+        Ok(())
     }
 
     #[test]
-    fn test_encode_tokens() -> anyhow::Result<()> {
+    fn test_is_word_start() -> anyhow::Result<()> {
         //        Given
         let mut vocab_file = tempfile::NamedTempFile::new()?;
-        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        write!(vocab_file, "un \n ##aff \n [UNK] \n ##able")?;
         let path = vocab_file.into_temp_path();
         let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
         //        When & Then
-        assert_eq!(base_vocab.token_to_id("hello"), 0);
-        assert_eq!(base_vocab.token_to_id("world"), 1);
-        assert_eq!(base_vocab.token_to_id("!"), 3);
-        assert_eq!(base_vocab.token_to_id("[UNK]"), 2);
-        assert_eq!(base_vocab.token_to_id("oov_value"), 2);
+        assert!(base_vocab.is_word_start(0));
+        assert!(!base_vocab.is_word_start(1));
+        assert!(base_vocab.is_word_start(2));
+        assert!(!base_vocab.is_word_start(3));
+        assert!(!base_vocab.is_word_start(4));
 
         drop(path);
         Ok(())
     }
 
     #[test]
-    fn test_decode_tokens() -> anyhow::Result<()> {
+    fn test_group_subwords() -> anyhow::Result<()> {
         //        Given
         let mut vocab_file = tempfile::NamedTempFile::new()?;
-        write!(vocab_file, "hello \n world \n [UNK] \n !")?;
+        write!(vocab_file, "un \n ##aff \n [UNK] \n ##able \n word")?;
         let path = vocab_file.into_temp_path();
         let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
 
         //        When & Then
-        assert_eq!(base_vocab.id_to_token(&(0_i64)), "hello");
-        assert_eq!(base_vocab.id_to_token(&(1_i64)), "world");
-        assert_eq!(base_vocab.id_to_token(&(3_i64)), "!");
-        assert_eq!(base_vocab.id_to_token(&(2_i64)), "[UNK]");
+        assert_eq!(
+            base_vocab.group_subwords(&[0, 1, 3, 4, 2, 0, 3]),
+            vec![vec![0, 1, 3], vec![4], vec![2], vec![0, 3]]
+        );
+        assert_eq!(
+            base_vocab.group_subwords(&[1, 3, 4]),
+            vec![vec![1, 3], vec![4]]
+        );
+        assert!(base_vocab.group_subwords(&[]).is_empty());
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pretty_token() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "\u{2581}hello \n <0x0A> \n [UNK] \n <0xE2> \n world\u{2581}!"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(base_vocab.pretty_token(0), " hello");
+        assert_eq!(base_vocab.pretty_token(1), "\n");
+        assert_eq!(base_vocab.pretty_token(2), "[UNK]");
+        assert_eq!(base_vocab.pretty_token(3), "<0xE2>");
+        assert_eq!(base_vocab.pretty_token(4), "world !");
+        assert_eq!(base_vocab.pretty_token(42), "[UNK]");
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_json_vocab_file_with_metadata() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "{{\"hello\": 0, \"world\": 1.0, \"metadata\": {{\"version\": 2}}, \"name\": \"test\"}}"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let target_values: HashMap<String, i64> =
+            [("hello".to_owned(), 0), ("world".to_owned(), 1)]
+                .iter()
+                .cloned()
+                .collect();
+
+        //        When
+        let values = BaseVocab::read_json_vocab_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(values, target_values);
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_json_vocab_parse_error_location() {
+        //        Given
+        let json_vocab = "{\n  \"hello\": 0,\n  \"world\": ,\n  \"[UNK]\": 2\n}";
+
+        //        When
+        let values = BaseVocab::read_json_vocab(json_vocab.as_bytes());
+        let truncated_values = BaseVocab::read_json_vocab("{\"hello\": 0".as_bytes());
+
+        //        Then
+        assert!(matches!(
+            values,
+            Err(TokenizerError::VocabularyParsingError(message))
+                if message == "parse error at line 3 column 12: expected value"
+        ));
+        assert!(matches!(
+            truncated_values,
+            Err(TokenizerError::VocabularyParsingError(message))
+                if message.starts_with("parse error at line 1 column ")
+                    && message.ends_with(": EOF while parsing an object")
+        ));
+    }
+
+    #[test]
+    fn test_read_json_vocab_file_with_invalid_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut negative_vocab_file = tempfile::NamedTempFile::new()?;
+        write!(negative_vocab_file, "{{\"hello\": 0, \"world\": -1}}")?;
+        let negative_path = negative_vocab_file.into_temp_path();
+        let mut overflow_vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            overflow_vocab_file,
+            "{{\"hello\": 0, \"world\": 9223372036854775808}}"
+        )?;
+        let overflow_path = overflow_vocab_file.into_temp_path();
+        let mut duplicate_vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            duplicate_vocab_file,
+            "{{\"hello\": 0, \"world\": 1, \"!\": 1}}"
+        )?;
+        let duplicate_path = duplicate_vocab_file.into_temp_path();
+
+        //        When
+        let negative_values =
+            BaseVocab::read_json_vocab_file(negative_path.to_path_buf().to_str().unwrap());
+        let overflow_values =
+            BaseVocab::read_json_vocab_file(overflow_path.to_path_buf().to_str().unwrap());
+        let duplicate_values =
+            BaseVocab::read_json_vocab_file(duplicate_path.to_path_buf().to_str().unwrap());
+
+        //        Then
+        assert!(matches!(
+            negative_values,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        assert!(matches!(
+            overflow_values,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        match duplicate_values {
+            Err(TokenizerError::VocabularyParsingError(message)) => {
+                assert_eq!(message, "Tokens ! and world are both mapped to id 1")
+            }
+            _ => panic!("expected a vocabulary parsing error"),
+        }
+
+        drop(negative_path);
+        drop(overflow_path);
+        drop(duplicate_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_json_vocab_file_without_entries() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "{{\"metadata\": {{\"version\": 2}}}}")?;
+        let path = vocab_file.into_temp_path();
+
+        //        When
+        let values = BaseVocab::read_json_vocab_file(path.to_path_buf().to_str().unwrap());
+
+        //        Then
+        assert!(matches!(
+            values,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_json_vocab() -> anyhow::Result<()> {
+        //        Given
+        let gpt2_vocab = Gpt2Vocab::from_reader(
+            r#"{"hello": 0, "world": 1, "<|endoftext|>": 2, "\"!\"": 3}"#.as_bytes(),
+        )?;
+        let mut buffer = Vec::new();
+
+        //        When
+        gpt2_vocab.to_writer(&mut buffer)?;
+        let read_vocab = Gpt2Vocab::from_reader(buffer.as_slice())?;
+
+        //        Then
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            r#"{"hello":0,"world":1,"<|endoftext|>":2,"\"!\"":3}"#
+        );
+        assert_eq!(read_vocab, gpt2_vocab);
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_special_value_twice() -> anyhow::Result<()> {
+        //        Given
+        let values: HashMap<String, i64> = [("</s>".to_owned(), 2), ("<unk>".to_owned(), 3)]
+            .iter()
+            .cloned()
+            .collect();
+        let mut special_values = HashMap::new();
+
+        //        When
+        BaseVocab::_register_as_special_value("</s>", &values, &mut special_values)?;
+        BaseVocab::_register_as_special_value("</s>", &values, &mut special_values)?;
+
+        //        Then
+        assert_eq!(special_values.len(), 1);
+        assert_eq!(special_values.get("</s>"), Some(&2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_special_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n </s>")?;
+        let path = vocab_file.into_temp_path();
+        let mut base_vocab = BaseVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        base_vocab
+            .special_values_mut()
+            .insert("[SEP]".to_owned(), 3);
+        base_vocab.special_values_mut().insert("</s>".to_owned(), 3);
+
+        //        When & Then
+        assert_eq!(base_vocab.canonical_special_id("[SEP]"), Some(3));
+        assert_eq!(base_vocab.canonical_special_id("</s>"), Some(3));
+        assert_eq!(base_vocab.canonical_special_id("[UNK]"), Some(2));
+        assert_eq!(base_vocab.canonical_special_id("hello"), None);
+        assert_eq!(base_vocab.unique_special_ids(), vec![2, 3]);
 
         drop(path);
         Ok(())
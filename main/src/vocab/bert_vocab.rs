@@ -12,8 +12,12 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::{swap_key_values, Vocab};
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq, Vocab, VocabMut,
+};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io::Read;
 
 /// # BERT Vocab
 /// Vocabulary for BERT tokenizer. Contains the following special values:
@@ -41,6 +45,10 @@ pub struct BertVocab {
 
     /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
     pub special_indices: HashMap<i64, String>,
+
+    /// Flag indicating if tokens are lowercased before lookup (for uncased vocabularies), set with
+    /// `with_lower_case`
+    pub(crate) do_lower_case: bool,
 }
 
 impl BertVocab {
@@ -63,35 +71,19 @@ impl BertVocab {
     pub fn mask_value() -> &'static str {
         "[MASK]"
     }
-}
 
-impl Vocab for BertVocab {
-    fn unknown_value() -> &'static str {
-        "[UNK]"
-    }
-
-    fn get_unknown_value(&self) -> &'static str {
-        "[UNK]"
-    }
-
-    fn values(&self) -> &HashMap<String, i64> {
-        &self.values
-    }
-
-    fn indices(&self) -> &HashMap<i64, String> {
-        &self.indices
-    }
-
-    fn special_values(&self) -> &HashMap<String, i64> {
-        &self.special_values
-    }
-
-    fn special_indices(&self) -> &HashMap<i64, String> {
-        &self.special_indices
-    }
-
-    fn from_file(path: &str) -> Result<BertVocab, TokenizerError> {
-        let values = BertVocab::read_vocab_file(path)?;
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a flat text vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<BertVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<BertVocab, TokenizerError> {
+        check_unique_ids(&values)?;
         let mut special_values = HashMap::new();
 
         let unknown_value = BertVocab::unknown_value();
@@ -118,23 +110,132 @@ impl Vocab for BertVocab {
             unknown_value,
             special_values,
             special_indices,
+            do_lower_case: false,
         })
     }
 
+    /// Enable or disable the lowercasing of tokens before lookup (for uncased vocabularies). The
+    /// lowercasing applies to `token_to_id`, `try_token_to_id` and `contains_token`, while
+    /// `id_to_token` is unaffected. Special values are always matched case-sensitively. Lowercasing
+    /// is disabled by default.
+    ///
+    /// # Parameters
+    /// - do_lower_case (`bool`): flag indicating if tokens are lowercased before lookup
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = BertVocab::from_file(path).unwrap().with_lower_case(true);
+    /// let token_id = vocab.token_to_id("Hello");
+    /// ```
+    pub fn with_lower_case(mut self, do_lower_case: bool) -> BertVocab {
+        self.do_lower_case = do_lower_case;
+        self
+    }
+
+    /// Returns `true` if tokens are lowercased before lookup
+    pub fn do_lower_case(&self) -> bool {
+        self.do_lower_case
+    }
+
+    /// Returns the form of a token used for lookup: lowercased if enabled, unless the token is a
+    /// special value
+    fn lookup_form<'a>(&self, token: &'a str) -> Cow<'a, str> {
+        if self.do_lower_case && !self.special_values.contains_key(token) {
+            Cow::Owned(token.to_lowercase())
+        } else {
+            Cow::Borrowed(token)
+        }
+    }
+}
+
+vocab_partial_eq!(BertVocab, do_lower_case);
+
+impl Vocab for BertVocab {
+    fn unknown_value() -> &'static str {
+        "[UNK]"
+    }
+
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            BertVocab::unknown_value(),
+            BertVocab::pad_value(),
+            BertVocab::sep_value(),
+            BertVocab::cls_value(),
+            BertVocab::mask_value(),
+        ]
+    }
+
+    special_token_config!(BertVocab, cls_value, sep_value, mask_value, pad_value);
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<BertVocab, TokenizerError> {
+        BertVocab::from_values(BertVocab::read_vocab(reader)?)
+    }
+
     fn token_to_id(&self, token: &str) -> i64 {
         self._token_to_id(
-            token,
+            &self.lookup_form(token),
             &self.values,
             &self.special_values,
             self.unknown_value,
         )
     }
 
+    fn try_token_to_id(&self, token: &str) -> Option<i64> {
+        let token = self.lookup_form(token);
+        self.special_values
+            .get(token.as_ref())
+            .or_else(|| self.values.get(token.as_ref()))
+            .copied()
+    }
+
+    fn contains_token(&self, token: &str) -> bool {
+        let token = self.lookup_form(token);
+        self.special_values.contains_key(token.as_ref()) || self.values.contains_key(token.as_ref())
+    }
+
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
 }
 
+impl VocabMut for BertVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
 //==============================
 // Unit tests
 //==============================
@@ -160,6 +261,7 @@ mod tests {
             unknown_value,
             special_values,
             special_indices,
+            do_lower_case: false,
         };
 
         //        Then
@@ -230,6 +332,48 @@ mod tests {
         let _base_vocab = BertVocab::from_file(path.to_path_buf().to_str().unwrap()).unwrap();
     }
 
+    #[test]
+    fn test_create_object_from_values() -> anyhow::Result<()> {
+        //        Given
+        let values: HashMap<String, i64> = [
+            ("[PAD]", 0),
+            ("[UNK]", 1),
+            ("[CLS]", 2),
+            ("[SEP]", 3),
+            ("[MASK]", 4),
+            ("hello", 5),
+        ]
+        .iter()
+        .map(|(token, id)| (token.to_string(), *id))
+        .collect();
+        let mut duplicate_values = values.clone();
+        duplicate_values.insert("world".to_string(), 5);
+        let mut missing_values = values.clone();
+        missing_values.remove("[MASK]");
+
+        //        When
+        let base_vocab = BertVocab::from_values(values.clone())?;
+        let duplicate_vocab = BertVocab::from_values(duplicate_values);
+        let missing_vocab = BertVocab::from_values(missing_values);
+
+        //        Then
+        assert_eq!(base_vocab.values, values);
+        assert_eq!(base_vocab.indices, swap_key_values(&values));
+        assert_eq!(base_vocab.special_values.len(), 5);
+        assert_eq!(base_vocab.token_to_id("hello"), 5);
+        assert_eq!(base_vocab.token_to_id("oov_value"), 1);
+        assert!(matches!(
+            duplicate_vocab,
+            Err(TokenizerError::VocabularyParsingError(message))
+                if message == "Tokens hello and world are both mapped to id 5"
+        ));
+        assert!(matches!(
+            missing_vocab,
+            Err(TokenizerError::TokenNotFound(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_encode_tokens() -> anyhow::Result<()> {
         //        Given
@@ -256,6 +400,45 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_encode_tokens_lower_case() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n [cls] \n [CLS] \n [SEP] \n [MASK] \n [PAD]"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = BertVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let cased_id = base_vocab.token_to_id("Hello");
+        let cased_contains_token = base_vocab.contains_token("Hello");
+        let base_vocab = base_vocab.with_lower_case(true);
+
+        //        Then
+        assert_eq!(cased_id, 2);
+        assert!(!cased_contains_token);
+        assert!(base_vocab.do_lower_case());
+        assert_eq!(base_vocab.token_to_id("Hello"), 0);
+        assert_eq!(base_vocab.try_token_to_id("Hello"), Some(0));
+        assert_eq!(base_vocab.try_token_to_id("[Cls]"), Some(3));
+        assert!(base_vocab.contains_token("WORLD"));
+        assert!(!base_vocab.contains_token("[Sep]"));
+        assert_eq!(
+            base_vocab.tokens_to_ids_counting_unknown(&["Hello", "WORLD", "Oov_value"]),
+            (vec![0, 1, 2], 1)
+        );
+        assert_eq!(base_vocab.token_to_id("WORLD"), 1);
+        assert_eq!(base_vocab.token_to_id("[CLS]"), 4);
+        assert_eq!(base_vocab.token_to_id("[Cls]"), 3);
+        assert_eq!(base_vocab.token_to_id("Oov_value"), 2);
+        assert_eq!(base_vocab.id_to_token(&0), "hello");
+
+        drop(path);
+        Ok(())
+    }
+
     #[test]
     fn test_decode_tokens() -> anyhow::Result<()> {
         //        Given
@@ -280,4 +463,44 @@ mod tests {
         drop(path);
         Ok(())
     }
+
+    #[test]
+    fn test_missing_special_tokens() {
+        //        Given
+        let values: HashMap<String, i64> = [
+            ("hello".to_owned(), 0),
+            ("[UNK]".to_owned(), 1),
+            ("[CLS]".to_owned(), 2),
+            ("[SEP]".to_owned(), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let special_values: HashMap<String, i64> = [
+            ("[UNK]".to_owned(), 1),
+            ("[CLS]".to_owned(), 2),
+            ("[SEP]".to_owned(), 3),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        //        When
+        let base_vocab = BertVocab {
+            values,
+            indices,
+            unknown_value: BertVocab::unknown_value(),
+            special_values,
+            special_indices,
+            do_lower_case: false,
+        };
+
+        //        Then
+        assert_eq!(
+            base_vocab.missing_special_tokens(),
+            vec![BertVocab::pad_value(), BertVocab::mask_value()]
+        );
+    }
 }
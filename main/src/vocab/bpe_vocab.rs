@@ -11,11 +11,10 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use protobuf::Message;
+use crate::vocab::sentence_piece_vocab::read_protobuf_file;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader};
 use std::mem::ManuallyDrop;
 use std::ptr;
 
@@ -84,21 +83,7 @@ impl BpePairVocab {
     /// let bpe_vocab = BpePairVocab::from_sentencepiece_file(path);
     /// ```
     pub fn from_sentencepiece_file(path: &str) -> Result<BpePairVocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+        let proto = read_protobuf_file(path)?;
         let mut values = HashMap::new();
         for (idx, piece) in proto.get_pieces().iter().enumerate() {
             values.insert(piece.get_piece().to_owned(), idx as i64);
@@ -0,0 +1,297 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::base_vocab::special_token_config;
+use crate::vocab::base_vocab::swap_key_values;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::{Vocab, VocabMut};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// # ByteVocab
+/// Byte-level vocabulary, as used by the character-level Reformer models (e.g. trained on enwik8).
+/// The special tokens are assigned the first ids, followed by one token per byte value (256
+/// tokens, represented as `<0xNN>`). The id of a byte is therefore its value offset by the number
+/// of special tokens. Contains the following special values:
+/// - PAD token
+/// - UNK token
+///
+/// Any text can be encoded without unknown tokens and decoded back losslessly.
+///
+/// Expects a text file listing the special tokens (one per line) when created from file.
+#[derive(Debug, Clone)]
+pub struct ByteVocab {
+    /// A mapping of tokens as string to indices (i.e. the encoder base)
+    pub values: HashMap<String, i64>,
+
+    /// A mapping of token ids to strings (i.e. the decoder base)
+    pub indices: HashMap<i64, String>,
+
+    /// The string to use for unknown (out of vocabulary) tokens
+    pub unknown_value: &'static str,
+
+    /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
+    /// values), special values typically include things like BOS/EOS markers, class markers, mask
+    /// markers and padding markers
+    pub special_values: HashMap<String, i64>,
+
+    /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
+    pub special_indices: HashMap<i64, String>,
+
+    /// Id of the first byte token (byte `0x00`)
+    pub byte_offset: i64,
+}
+
+impl ByteVocab {
+    /// Returns the PAD token for the byte-level vocabulary (`<pad>`)
+    pub fn pad_value() -> &'static str {
+        "<pad>"
+    }
+
+    /// Create a new byte-level vocabulary with the special tokens of the character-level Reformer
+    /// models: `<pad>` (id 0) and `<unk>` (id 1), the byte tokens starting at id 2.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_tokenizers::vocab::{ByteVocab, Vocab};
+    /// let vocab = ByteVocab::new();
+    ///
+    /// assert_eq!(vocab.encode_bytes("a"), vec![99]);
+    /// ```
+    pub fn new() -> ByteVocab {
+        ByteVocab::from_special_tokens(&[ByteVocab::pad_value(), ByteVocab::unknown_value()])
+            .unwrap()
+    }
+
+    fn from_special_tokens<S: AsRef<str>>(
+        special_tokens: &[S],
+    ) -> Result<ByteVocab, TokenizerError> {
+        let mut values = HashMap::with_capacity(special_tokens.len() + 256);
+        for special_token in special_tokens {
+            let id = values.len() as i64;
+            values.insert(special_token.as_ref().to_owned(), id);
+        }
+        if values.len() != special_tokens.len() {
+            return Err(TokenizerError::VocabularyParsingError(
+                "Duplicate special token in byte-level vocabulary".to_string(),
+            ));
+        }
+        let byte_offset = values.len() as i64;
+        for byte in 0..=u8::MAX {
+            let byte_token = format!("<0x{:02X}>", byte);
+            if values.contains_key(&byte_token) {
+                return Err(TokenizerError::VocabularyParsingError(format!(
+                    "Special token {} collides with a byte token",
+                    byte_token
+                )));
+            }
+            values.insert(byte_token, byte_offset + byte as i64);
+        }
+
+        let mut special_values = HashMap::new();
+        for special_token in special_tokens {
+            ByteVocab::_register_as_special_value(
+                special_token.as_ref(),
+                &values,
+                &mut special_values,
+            )?;
+        }
+        let unknown_value = ByteVocab::unknown_value();
+        ByteVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(ByteVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+            byte_offset,
+        })
+    }
+
+    /// Encodes a text to the ids of its UTF-8 bytes
+    ///
+    /// # Parameters
+    /// - text (`&str`): text to encode
+    ///
+    /// # Returns
+    /// - `Vec<i64>` ids of the bytes of the text
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_tokenizers::vocab::ByteVocab;
+    /// let vocab = ByteVocab::new();
+    ///
+    /// let ids = vocab.encode_bytes("héllo");
+    /// assert_eq!(vocab.decode_bytes(&ids), "héllo");
+    /// ```
+    pub fn encode_bytes(&self, text: &str) -> Vec<i64> {
+        text.bytes()
+            .map(|byte| self.byte_offset + byte as i64)
+            .collect()
+    }
+
+    /// Decodes a sequence of byte ids to a text. Ids that do not correspond to a byte (e.g. special
+    /// tokens) are skipped, and invalid UTF-8 sequences are replaced by `U+FFFD`.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): ids to decode
+    ///
+    /// # Returns
+    /// - `String` decoded text
+    pub fn decode_bytes(&self, ids: &[i64]) -> String {
+        let bytes = ids
+            .iter()
+            .filter_map(|id| self.id_to_byte(*id))
+            .collect::<Vec<u8>>();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Returns the byte value for a token id, `None` if the id is not a byte token
+    pub fn id_to_byte(&self, id: i64) -> Option<u8> {
+        let byte = id - self.byte_offset;
+        if (0..=u8::MAX as i64).contains(&byte) {
+            Some(byte as u8)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for ByteVocab {
+    fn default() -> Self {
+        ByteVocab::new()
+    }
+}
+
+vocab_partial_eq!(ByteVocab, byte_offset);
+
+impl Vocab for ByteVocab {
+    fn unknown_value() -> &'static str {
+        "<unk>"
+    }
+
+    special_token_config!(ByteVocab, pad_value);
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<ByteVocab, TokenizerError> {
+        let special_tokens = ByteVocab::read_vocab(reader)?;
+        let mut special_tokens = special_tokens.into_iter().collect::<Vec<(String, i64)>>();
+        special_tokens.sort_unstable_by_key(|(_, id)| *id);
+        let special_tokens = special_tokens
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect::<Vec<String>>();
+        ByteVocab::from_special_tokens(&special_tokens)
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self._token_to_id(
+            token,
+            &self.values,
+            &self.special_values,
+            self.unknown_value,
+        )
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+    }
+}
+
+impl VocabMut for ByteVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_byte_vocab_round_trip() {
+        //        Given
+        let byte_vocab = ByteVocab::new();
+        let text = "Hello, wörld! \u{1F600} \u{0000}\n日本語";
+
+        //        When
+        let ids = byte_vocab.encode_bytes(text);
+
+        //        Then
+        assert_eq!(byte_vocab.values.len(), 258);
+        assert_eq!(ids.len(), text.len());
+        assert_eq!(ids[0], b'H' as i64 + 2);
+        assert_eq!(byte_vocab.id_to_token(&ids[0]), "<0x48>");
+        assert_eq!(byte_vocab.decode_bytes(&ids), text);
+        assert_eq!(byte_vocab.decode_bytes(&[0, ids[0], 1]), "H");
+    }
+
+    #[test]
+    fn test_create_object_from_file() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "<pad>\n</s>\n<unk>")?;
+        let path = vocab_file.into_temp_path();
+
+        //        When
+        let byte_vocab = ByteVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(byte_vocab.byte_offset, 3);
+        assert_eq!(byte_vocab.special_values.len(), 3);
+        assert_eq!(byte_vocab.token_to_id("</s>"), 1);
+        assert_eq!(byte_vocab.token_to_id("<0x00>"), 3);
+        assert_eq!(byte_vocab.token_to_id("<0xFF>"), 258);
+        assert_eq!(byte_vocab.encode_bytes("a"), vec![b'a' as i64 + 3]);
+        drop(path);
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::base_vocab::forward_vocab_methods;
+use crate::vocab::{JsonVocab, Vocab, VocabMut};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+
+/// Number of cache slots of a `CachedVocab` created with `from_file` or `from_reader`
+pub const DEFAULT_CACHE_CAPACITY: usize = 4096;
+
+/// # CachedVocab
+/// Vocabulary wrapper caching the ids of recently looked up tokens, for streams where a small set
+/// of frequent tokens is converted repeatedly. The cache is direct-mapped: each token is assigned a
+/// slot from a cheap hash of its bytes and replaces the previous entry of the slot. Lookups missing
+/// the cache (or finding their slot locked by another thread) are resolved by the wrapped vocabulary,
+/// and the results are identical to the wrapped vocabulary. Replacing the entry of a slot reuses its
+/// buffer, so that only lookups filling an empty slot allocate. The cache is cleared when the
+/// mappings are borrowed mutably.
+#[derive(Debug)]
+pub struct CachedVocab<V: Vocab> {
+    vocab: V,
+    cache: Vec<Mutex<Option<(String, i64)>>>,
+}
+
+impl<V: Vocab> CachedVocab<V> {
+    /// Create a new `CachedVocab` wrapping an existing vocabulary
+    ///
+    /// # Parameters
+    /// - vocab (`V`): vocabulary to wrap
+    /// - capacity (`usize`): number of cache slots, rounded up to the next power of two
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, CachedVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = CachedVocab::new(BertVocab::from_file(path).unwrap(), 1024);
+    /// let token_id = vocab.token_to_id("the");
+    /// ```
+    pub fn new(vocab: V, capacity: usize) -> CachedVocab<V> {
+        let cache = (0..capacity.max(1).next_power_of_two())
+            .map(|_| Mutex::new(None))
+            .collect();
+        CachedVocab { vocab, cache }
+    }
+
+    /// Returns a reference to the wrapped vocabulary
+    pub fn inner(&self) -> &V {
+        &self.vocab
+    }
+
+    /// Returns the wrapped vocabulary, discarding the cache
+    pub fn into_inner(self) -> V {
+        self.vocab
+    }
+
+    /// Returns the number of cache slots
+    pub fn capacity(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Removes all entries from the cache
+    pub fn clear_cache(&mut self) {
+        for slot in self.cache.iter_mut() {
+            *slot.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+
+    /// Returns the cache slot of a token, using the FNV-1a hash of its bytes
+    fn slot(&self, token: &str) -> &Mutex<Option<(String, i64)>> {
+        let hash = token.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+        &self.cache[hash as usize & (self.cache.len() - 1)]
+    }
+}
+
+impl<V: Vocab + Clone> Clone for CachedVocab<V> {
+    fn clone(&self) -> Self {
+        CachedVocab::new(self.vocab.clone(), self.capacity())
+    }
+}
+
+impl<V: Vocab> Vocab for CachedVocab<V> {
+    fn unknown_value() -> &'static str {
+        V::unknown_value()
+    }
+
+    forward_vocab_methods!(vocab);
+
+    fn from_reader<R: Read>(reader: R) -> Result<CachedVocab<V>, TokenizerError> {
+        Ok(CachedVocab::new(
+            V::from_reader(reader)?,
+            DEFAULT_CACHE_CAPACITY,
+        ))
+    }
+
+    fn from_file(path: &str) -> Result<CachedVocab<V>, TokenizerError> {
+        Ok(CachedVocab::new(
+            V::from_file(path)?,
+            DEFAULT_CACHE_CAPACITY,
+        ))
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        let slot = self.slot(token);
+        if let Ok(mut entry) = slot.try_lock() {
+            match entry.as_mut() {
+                Some((cached_token, cached_id)) => {
+                    if cached_token != token {
+                        cached_token.clear();
+                        cached_token.push_str(token);
+                        *cached_id = self.vocab.token_to_id(token);
+                    }
+                    return *cached_id;
+                }
+                None => {
+                    let token_id = self.vocab.token_to_id(token);
+                    *entry = Some((token.to_owned(), token_id));
+                    return token_id;
+                }
+            }
+        }
+        self.vocab.token_to_id(token)
+    }
+
+    fn source_digest(&self) -> Option<[u8; 32]> {
+        self.vocab.source_digest()
+    }
+}
+
+impl<V: VocabMut> VocabMut for CachedVocab<V> {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.clear_cache();
+        self.vocab.values_mut()
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.clear_cache();
+        self.vocab.indices_mut()
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.clear_cache();
+        self.vocab.special_values_mut()
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.clear_cache();
+        self.vocab.special_indices_mut()
+    }
+
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.clear_cache();
+        self.vocab.insert_token(token, id)
+    }
+
+    fn add_tokens(&mut self, tokens: &[String]) -> Vec<i64> {
+        self.clear_cache();
+        self.vocab.add_tokens(tokens)
+    }
+}
+
+impl<V: JsonVocab> JsonVocab for CachedVocab<V> {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::{BaseVocab, BertVocab, M2M100Vocab};
+    use std::time::Instant;
+
+    #[test]
+    fn test_cached_token_to_id() -> anyhow::Result<()> {
+        //        Given
+        let base_vocab = BaseVocab::from_reader("hello \n world \n [UNK] \n !".as_bytes())?;
+        let mut cached_vocab = CachedVocab::new(base_vocab.clone(), 3);
+        let tokens = [
+            "hello",
+            "world",
+            "hello",
+            "oov_value",
+            "!",
+            "world",
+            "[UNK]",
+            "hello",
+        ];
+
+        //        When
+        let first_pass_ids = tokens
+            .iter()
+            .map(|token| cached_vocab.token_to_id(token))
+            .collect::<Vec<i64>>();
+        let second_pass_ids = tokens
+            .iter()
+            .map(|token| cached_vocab.token_to_id(token))
+            .collect::<Vec<i64>>();
+        cached_vocab.values_mut().insert("hello".to_string(), 3);
+        let updated_id = cached_vocab.token_to_id("hello");
+
+        //        Then
+        assert_eq!(cached_vocab.capacity(), 4);
+        let expected_ids = tokens
+            .iter()
+            .map(|token| base_vocab.token_to_id(token))
+            .collect::<Vec<i64>>();
+        assert_eq!(first_pass_ids, expected_ids);
+        assert_eq!(second_pass_ids, expected_ids);
+        assert_eq!(updated_id, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forward_wrapped_vocab_methods() -> anyhow::Result<()> {
+        //        Given
+        let values: HashMap<String, i64> = [("<s>", 0), ("<pad>", 1), ("</s>", 2), ("<unk>", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let m2m100_vocab = M2M100Vocab::from_values(values)?;
+        let cached_vocab = CachedVocab::new(m2m100_vocab.clone(), 16);
+        let fr_id = m2m100_vocab.token_to_id(">>fr.<<");
+
+        //        When & Then
+        assert_eq!(cached_vocab.summary(), m2m100_vocab.summary());
+        assert_eq!(
+            cached_vocab.render_ids(&[fr_id, 2]),
+            m2m100_vocab.render_ids(&[fr_id, 2])
+        );
+        assert_eq!(cached_vocab.inner(), &m2m100_vocab);
+        assert_eq!(cached_vocab.into_inner(), m2m100_vocab);
+        Ok(())
+    }
+
+    #[test]
+    fn test_forward_lower_case_lookups() -> anyhow::Result<()> {
+        //        Given
+        let bert_vocab = BertVocab::from_reader(
+            "hello \n [UNK] \n [CLS] \n [SEP] \n [MASK] \n [PAD]".as_bytes(),
+        )?
+        .with_lower_case(true);
+        let cached_vocab = CachedVocab::new(bert_vocab, 16);
+
+        //        When & Then
+        assert_eq!(cached_vocab.token_to_id("Hello"), 0);
+        assert_eq!(cached_vocab.try_token_to_id("Hello"), Some(0));
+        assert!(cached_vocab.contains_token("HELLO"));
+        assert!(!cached_vocab.contains_token("[cls]"));
+        Ok(())
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_cached_vocab() -> anyhow::Result<()> {
+        // Run with `cargo test --release -- --ignored bench_cached_vocab --nocapture`
+        let values: HashMap<String, i64> = (0..50_000)
+            .map(|index| (format!("token_{}", index), index))
+            .chain(std::iter::once(("[UNK]".to_string(), 50_000)))
+            .collect();
+        let base_vocab = BaseVocab::from_values(values)?;
+        let cached_vocab = CachedVocab::new(base_vocab.clone(), DEFAULT_CACHE_CAPACITY);
+        // Zipfian token stream: the probability of the token of rank r is proportional to 1 / r
+        let cumulative_weights = (1..=50_000)
+            .scan(0.0, |sum, rank| {
+                *sum += 1.0 / rank as f64;
+                Some(*sum)
+            })
+            .collect::<Vec<f64>>();
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let tokens = (0..1_000_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let threshold = (state >> 11) as f64 / (1u64 << 53) as f64
+                    * cumulative_weights[cumulative_weights.len() - 1];
+                let rank = cumulative_weights.partition_point(|&weight| weight < threshold);
+                format!("token_{}", rank)
+            })
+            .collect::<Vec<String>>();
+
+        let start = Instant::now();
+        let uncached_ids = tokens
+            .iter()
+            .map(|token| base_vocab.token_to_id(token))
+            .collect::<Vec<i64>>();
+        let uncached_duration = start.elapsed();
+
+        let start = Instant::now();
+        let cached_ids = tokens
+            .iter()
+            .map(|token| cached_vocab.token_to_id(token))
+            .collect::<Vec<i64>>();
+        let cached_duration = start.elapsed();
+
+        assert_eq!(cached_ids, uncached_ids);
+        println!(
+            "uncached: {:?}, cached: {:?}, speedup: {:.2}x",
+            uncached_duration,
+            cached_duration,
+            uncached_duration.as_secs_f64() / cached_duration.as_secs_f64()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::base_vocab::forward_vocab_methods;
+use crate::vocab::{JsonVocab, Vocab, VocabMut};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+
+/// Computes the SHA-256 digest of a byte sequence
+pub(crate) fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&Sha256::digest(bytes));
+    digest
+}
+
+/// Computes the SHA-256 digest of a file, for example to record the digest of a vocabulary file
+/// later loaded with `Vocab::from_file_verified`.
+///
+/// # Parameters
+/// - path (`&str`): path to the file
+///
+/// # Returns
+/// - `Result<[u8; 32], TokenizerError>` SHA-256 digest of the file bytes
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_tokenizers::vocab::file_sha256;
+/// let digest = file_sha256("path/to/vocab/file").unwrap();
+/// ```
+pub fn file_sha256(path: &str) -> Result<[u8; 32], TokenizerError> {
+    let bytes = fs::read(path).map_err(|e| {
+        TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+    })?;
+    Ok(sha256(&bytes))
+}
+
+/// # VerifiedVocab
+/// Vocabulary read from a file whose SHA-256 digest was verified when loading, as returned by
+/// `Vocab::from_file_verified`. The wrapper behaves as the wrapped vocabulary and records the digest
+/// of the file bytes, available from `source_digest`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedVocab<V: Vocab> {
+    vocab: V,
+    digest: [u8; 32],
+}
+
+impl<V: Vocab> VerifiedVocab<V> {
+    pub(crate) fn new(vocab: V, digest: [u8; 32]) -> VerifiedVocab<V> {
+        VerifiedVocab { vocab, digest }
+    }
+
+    /// Returns a reference to the wrapped vocabulary
+    pub fn inner(&self) -> &V {
+        &self.vocab
+    }
+
+    /// Returns the wrapped vocabulary, discarding the digest
+    pub fn into_inner(self) -> V {
+        self.vocab
+    }
+}
+
+impl<V: Vocab> Vocab for VerifiedVocab<V> {
+    fn unknown_value() -> &'static str {
+        V::unknown_value()
+    }
+
+    forward_vocab_methods!(vocab);
+
+    fn from_reader<R: Read>(mut reader: R) -> Result<VerifiedVocab<V>, TokenizerError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| TokenizerError::IOError(e.to_string()))?;
+        let digest = sha256(&bytes);
+        Ok(VerifiedVocab::new(V::from_reader(&bytes[..])?, digest))
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self.vocab.token_to_id(token)
+    }
+
+    fn source_digest(&self) -> Option<[u8; 32]> {
+        Some(self.digest)
+    }
+}
+
+impl<V: VocabMut> VocabMut for VerifiedVocab<V> {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.vocab.values_mut()
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.vocab.indices_mut()
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.vocab.special_values_mut()
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.vocab.special_indices_mut()
+    }
+
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.vocab.insert_token(token, id)
+    }
+
+    fn add_tokens(&mut self, tokens: &[String]) -> Vec<i64> {
+        self.vocab.add_tokens(tokens)
+    }
+}
+
+impl<V: JsonVocab> JsonVocab for VerifiedVocab<V> {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_hex(digest: &[u8]) -> String {
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_sha256() {
+        //        Given
+        let test_tuples = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+
+        //        When & Then
+        for (source_text, expected_digest) in test_tuples.iter() {
+            assert_eq!(to_hex(&sha256(source_text.as_bytes())), *expected_digest);
+        }
+    }
+}
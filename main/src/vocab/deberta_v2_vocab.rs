@@ -0,0 +1,191 @@
+// Copyright 2020 Microsoft and the HuggingFace Inc. team.
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::base_vocab::swap_key_values;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{
+    piece_values, read_protobuf, read_sentencepiece_proto_with_values, SentencePieceValues,
+};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// # DeBERTaV2Vocab
+/// Vocabulary for DeBERTa (v2) tokenizer. Contains the following special values:
+/// - BOS token
+/// - EOS token
+/// - CLS token
+/// - SEP token
+/// - PAD token
+/// - MASK token
+///
+/// Expects a SentencePiece protobuf file when created from file. The MASK token is appended at the
+/// end of the vocabulary if it is not part of the SentencePiece model pieces.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub struct DeBERTaV2Vocab {
+    /// A mapping of tokens as string to indices (i.e. the encoder base)
+    pub values: HashMap<String, i64>,
+
+    /// A mapping of token ids to strings (i.e. the decoder base)
+    pub indices: HashMap<i64, String>,
+
+    /// The string to use for unknown (out of vocabulary) tokens
+    pub unknown_value: &'static str,
+
+    /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
+    /// values), special values typically include things like BOS/EOS markers, class markers, mask
+    /// markers and padding markers
+    pub special_values: HashMap<String, i64>,
+
+    /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
+    pub special_indices: HashMap<i64, String>,
+}
+
+impl DeBERTaV2Vocab {
+    /// Returns the BOS token for DeBERTa (v2) (`[CLS]`)
+    pub fn bos_value() -> &'static str {
+        "[CLS]"
+    }
+
+    /// Returns the EOS token for DeBERTa (v2) (`[SEP]`)
+    pub fn eos_value() -> &'static str {
+        "[SEP]"
+    }
+
+    /// Returns the SEP token for DeBERTa (v2) (`[SEP]`)
+    pub fn sep_value() -> &'static str {
+        "[SEP]"
+    }
+
+    /// Returns the CLS token for DeBERTa (v2) (`[CLS]`)
+    pub fn cls_value() -> &'static str {
+        "[CLS]"
+    }
+
+    /// Returns the MASK token for DeBERTa (v2) (`[MASK]`)
+    pub fn mask_value() -> &'static str {
+        "[MASK]"
+    }
+
+    /// Returns the PAD token for DeBERTa (v2) (`[PAD]`)
+    pub fn pad_value() -> &'static str {
+        "[PAD]"
+    }
+}
+
+vocab_partial_eq!(DeBERTaV2Vocab);
+
+impl Vocab for DeBERTaV2Vocab {
+    fn unknown_value() -> &'static str {
+        "[UNK]"
+    }
+
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            DeBERTaV2Vocab::unknown_value(),
+            DeBERTaV2Vocab::bos_value(),
+            DeBERTaV2Vocab::eos_value(),
+            DeBERTaV2Vocab::cls_value(),
+            DeBERTaV2Vocab::mask_value(),
+            DeBERTaV2Vocab::pad_value(),
+            DeBERTaV2Vocab::sep_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::deberta_v2()
+    }
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<DeBERTaV2Vocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
+
+        let mut values = piece_values(&proto);
+        if !values.contains_key(DeBERTaV2Vocab::mask_value()) {
+            values.insert(DeBERTaV2Vocab::mask_value().to_owned(), values.len() as i64);
+        }
+
+        let SentencePieceValues {
+            values,
+            special_values,
+            ..
+        } = read_sentencepiece_proto_with_values::<DeBERTaV2Vocab>(
+            &proto,
+            values,
+            &SpecialTokenConfig::deberta_v2(),
+        )?;
+
+        let unknown_value = DeBERTaV2Vocab::unknown_value();
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(DeBERTaV2Vocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self._token_to_id(
+            token,
+            &self.values,
+            &self.special_values,
+            self.unknown_value,
+        )
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+    }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for DeBERTaV2Vocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2021 Google Research and The HuggingFace Inc. team. All rights reserved.
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::base_vocab::swap_key_values;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{read_sentencepiece, SentencePieceValues};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// # FNetVocab
+/// Vocabulary for FNet tokenizer. FNet shares the SentencePiece vocabulary format of ALBERT, with
+/// a different set of special values:
+/// - CLS token
+/// - SEP token
+/// - PAD token
+/// - MASK token
+///
+/// Expects a SentencePiece protobuf file when created from file.
+#[derive(Debug, Clone)]
+pub struct FNetVocab {
+    /// A mapping of tokens as string to indices (i.e. the encoder base)
+    pub values: HashMap<String, i64>,
+
+    /// A mapping of token ids to strings (i.e. the decoder base)
+    pub indices: HashMap<i64, String>,
+
+    /// The string to use for unknown (out of vocabulary) tokens, read from the SentencePiece model
+    pub unknown_value: String,
+
+    /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
+    /// values), special values typically include things like BOS/EOS markers, class markers, mask
+    /// markers and padding markers
+    pub special_values: HashMap<String, i64>,
+
+    /// A mapping of special value tokens as IDs to strings (i.e. the decoder base for special values)
+    pub special_indices: HashMap<i64, String>,
+}
+
+impl FNetVocab {
+    /// Returns the SEP token for FNet (`[SEP]`)
+    pub fn sep_value() -> &'static str {
+        "[SEP]"
+    }
+
+    /// Returns the CLS token for FNet (`[CLS]`)
+    pub fn cls_value() -> &'static str {
+        "[CLS]"
+    }
+
+    /// Returns the MASK token for FNet (`[MASK]`)
+    pub fn mask_value() -> &'static str {
+        "[MASK]"
+    }
+
+    /// Returns the PAD token for FNet (`<pad>`)
+    pub fn pad_value() -> &'static str {
+        "<pad>"
+    }
+}
+
+vocab_partial_eq!(FNetVocab);
+
+impl Vocab for FNetVocab {
+    fn unknown_value() -> &'static str {
+        "<unk>"
+    }
+
+    fn get_unknown_value(&self) -> &str {
+        &self.unknown_value
+    }
+
+    fn expected_special_values(&self) -> Vec<&str> {
+        vec![
+            self.get_unknown_value(),
+            FNetVocab::cls_value(),
+            FNetVocab::sep_value(),
+            FNetVocab::mask_value(),
+            FNetVocab::pad_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::fnet()
+    }
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<FNetVocab, TokenizerError> {
+        let SentencePieceValues {
+            values,
+            unknown_value,
+            special_values,
+            ..
+        } = read_sentencepiece::<FNetVocab, _>(reader, &SpecialTokenConfig::fnet())?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(FNetVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        self._token_to_id(
+            token,
+            &self.values,
+            &self.special_values,
+            &self.unknown_value,
+        )
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self._id_to_token(
+            id,
+            &self.indices,
+            &self.special_indices,
+            &self.unknown_value,
+        )
+    }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for FNetVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    fn generate_test_proto_file(pieces: &[&str]) -> anyhow::Result<tempfile::TempPath> {
+        let mut proto = ModelProto::new();
+        for piece in pieces {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        Ok(model_file.into_temp_path())
+    }
+
+    #[test]
+    fn test_create_object_from_file() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_proto_file(&[
+            "<pad>",
+            "<unk>",
+            "[CLS]",
+            "[SEP]",
+            "[MASK]",
+            "\u{2581}hello",
+        ])?;
+
+        //        When
+        let fnet_vocab = FNetVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(fnet_vocab.special_values.len(), 5);
+        assert_eq!(fnet_vocab.token_to_id(FNetVocab::pad_value()), 0);
+        assert_eq!(fnet_vocab.token_to_id(FNetVocab::cls_value()), 2);
+        assert_eq!(fnet_vocab.token_to_id(FNetVocab::sep_value()), 3);
+        assert_eq!(fnet_vocab.token_to_id(FNetVocab::mask_value()), 4);
+        assert_eq!(fnet_vocab.token_to_id("\u{2581}hello"), 5);
+        assert!(fnet_vocab.missing_special_tokens().is_empty());
+        drop(path);
+        Ok(())
+    }
+}
@@ -12,10 +12,12 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::{swap_key_values, Vocab};
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq, JsonVocab, Vocab,
+    VocabMut,
+};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Read;
 
 /// # GPT2 Vocab
 /// Vocabulary for GPT2 tokenizer. Contains the following special values:
@@ -53,17 +55,59 @@ impl Gpt2Vocab {
     pub fn eos_value() -> &'static str {
         "<|endoftext|>"
     }
+
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a JSON vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<Gpt2Vocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<Gpt2Vocab, TokenizerError> {
+        check_unique_ids(&values)?;
+        let mut special_values = HashMap::new();
+        let unknown_value = Gpt2Vocab::unknown_value();
+        Gpt2Vocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+
+        let bos_value = Gpt2Vocab::bos_value();
+        Gpt2Vocab::_register_as_special_value(bos_value, &values, &mut special_values)?;
+
+        let eos_value = Gpt2Vocab::eos_value();
+        Gpt2Vocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(Gpt2Vocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
+    }
 }
 
+vocab_partial_eq!(Gpt2Vocab);
+
 impl Vocab for Gpt2Vocab {
     fn unknown_value() -> &'static str {
         "<|endoftext|>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<|endoftext|>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            Gpt2Vocab::unknown_value(),
+            Gpt2Vocab::bos_value(),
+            Gpt2Vocab::eos_value(),
+        ]
     }
 
+    special_token_config!(Gpt2Vocab, bos_value, eos_value);
+
     fn values(&self) -> &HashMap<String, i64> {
         &self.values
     }
@@ -80,37 +124,8 @@ impl Vocab for Gpt2Vocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<Gpt2Vocab, TokenizerError> {
-        let f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let br = BufReader::new(f);
-        let values: HashMap<String, i64> = match serde_json::from_reader(br) {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
-        let mut special_values = HashMap::new();
-        let unknown_value = Gpt2Vocab::unknown_value();
-        Gpt2Vocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let bos_value = Gpt2Vocab::bos_value();
-        Gpt2Vocab::_register_as_special_value(bos_value, &values, &mut special_values)?;
-
-        let eos_value = Gpt2Vocab::eos_value();
-        Gpt2Vocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
-
-        let indices = swap_key_values(&values);
-        let special_indices = swap_key_values(&special_values);
-
-        Ok(Gpt2Vocab {
-            values,
-            indices,
-            unknown_value,
-            special_values,
-            special_indices,
-        })
+    fn from_reader<R: Read>(reader: R) -> Result<Gpt2Vocab, TokenizerError> {
+        Gpt2Vocab::from_values(Gpt2Vocab::read_json_vocab(reader)?)
     }
 
     fn token_to_id(&self, token: &str) -> i64 {
@@ -125,8 +140,32 @@ impl Vocab for Gpt2Vocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{0120}')
+    }
+}
+
+impl VocabMut for Gpt2Vocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
 
+impl JsonVocab for Gpt2Vocab {}
+
 //==============================
 // Unit tests
 //==============================
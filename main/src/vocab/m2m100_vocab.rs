@@ -11,11 +11,14 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::Vocab;
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq,
+};
+use crate::vocab::{JsonVocab, TokenKind, Vocab, VocabMut};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Read;
+use std::ops::Range;
 
 pub static FAIRSEQ_LANGUAGE_CODES: [&str; 100] = [
     "af", "am", "ar", "ast", "az", "ba", "be", "bg", "bn", "br", "bs", "ca", "ceb", "cs", "cy",
@@ -34,9 +37,13 @@ pub static FAIRSEQ_LANGUAGE_CODES: [&str; 100] = [
 /// - EOS token
 /// - SEP token
 ///
-/// Expects a JSON-format vocabulary when created from file.
+/// Expects a JSON-format vocabulary when created from file. The language codes missing from the
+/// file are appended to the vocabulary, and loading fails if the id assigned to a language code is
+/// already used by a token of the file. Language codes present in the file (e.g. in a vocabulary
+/// written by `to_file`) keep their id. The vocabulary implements `Serialize` and `Deserialize`,
+/// allowing a fully constructed vocabulary to be cached (e.g. with `bincode`).
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct M2M100Vocab {
     /// A mapping of tokens as string to indices (i.e. the encoder base)
     pub values: HashMap<String, i64>,
@@ -45,7 +52,7 @@ pub struct M2M100Vocab {
     pub indices: HashMap<i64, String>,
 
     /// The string to use for unknown (out of vocabulary) tokens
-    pub unknown_value: &'static str,
+    pub unknown_value: String,
 
     /// A mapping of special value tokens as strings to IDs (i.e. the encoder base for special
     /// values), special values typically include things like BOS/EOS markers, class markers, mask
@@ -79,57 +86,190 @@ impl M2M100Vocab {
     pub fn pad_value() -> &'static str {
         "<pad>"
     }
-}
 
-impl Vocab for M2M100Vocab {
-    fn unknown_value() -> &'static str {
-        "<unk>"
+    /// Returns the language codes supported by M2M100 (e.g. `fr` or `ast`)
+    pub fn language_codes() -> &'static [&'static str] {
+        &FAIRSEQ_LANGUAGE_CODES
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    /// Returns the id of the prefix token for a language code. The prefix token is formatted as
+    /// `>>xx.<<` for 2-character codes and as `>>xxx<<` for codes of any other length.
+    ///
+    /// # Parameters
+    /// - language_code (`&str`): language code (e.g. `fr` or `ast`)
+    ///
+    /// # Returns
+    /// - `Option<i64>`: id of the language prefix token, `None` if the language is not supported
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let french_id = vocab.get_language_id("fr");
+    /// ```
+    pub fn get_language_id(&self, language_code: &str) -> Option<i64> {
+        let language_code = language_code_token(language_code);
+        if self.language_codes_bytes.contains(language_code.as_bytes()) {
+            self.special_values.get(&language_code).copied()
+        } else {
+            None
+        }
     }
 
-    fn values(&self) -> &HashMap<String, i64> {
-        &self.values
+    /// Returns the ids of the language prefix tokens, which are also part of the special token ids
+    ///
+    /// # Returns
+    /// - `HashSet<i64>`: ids of the language prefix tokens
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let language_ids = vocab.language_prefix_ids();
+    /// ```
+    pub fn language_prefix_ids(&self) -> HashSet<i64> {
+        M2M100Vocab::language_codes()
+            .iter()
+            .filter_map(|language_code| self.get_language_id(language_code))
+            .collect()
     }
 
-    fn indices(&self) -> &HashMap<i64, String> {
-        &self.indices
+    /// Returns the range of ids of the language prefix tokens. Language codes missing from the
+    /// vocabulary file are appended with contiguous ids when loading, in which case the range
+    /// contains exactly the language prefix ids (e.g. to mask the logits of the first generated
+    /// token). Use `is_language_code_id` to check individual ids if the vocabulary file defines
+    /// some of the language codes itself.
+    ///
+    /// # Returns
+    /// - `Range<i64>`: range from the smallest to the largest (excluded) language prefix id
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let language_ids = vocab.language_code_id_range();
+    /// ```
+    pub fn language_code_id_range(&self) -> Range<i64> {
+        let language_prefix_ids = self.language_prefix_ids();
+        match (
+            language_prefix_ids.iter().min(),
+            language_prefix_ids.iter().max(),
+        ) {
+            (Some(first_id), Some(last_id)) => *first_id..*last_id + 1,
+            _ => 0..0,
+        }
     }
 
-    fn special_values(&self) -> &HashMap<String, i64> {
-        &self.special_values
+    /// Returns `true` if the id is the id of a language prefix token
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the id is a language prefix id
+    pub fn is_language_code_id(&self, id: i64) -> bool {
+        self.special_indices
+            .get(&id)
+            .is_some_and(|token| self.language_codes_bytes.contains(token.as_bytes()))
     }
 
-    fn special_indices(&self) -> &HashMap<i64, String> {
-        &self.special_indices
+    /// Splits a leading language prefix token off a sequence of token ids (e.g. decoded output
+    /// starting with `>>fr.<<`).
+    ///
+    /// # Parameters
+    /// - tokens (`&[i64]`): sequence of token ids
+    ///
+    /// # Returns
+    /// - `(Option<&'static str>, &[i64])`: language code of the prefix (`None` if the sequence does not start with a language prefix) and the remaining token ids
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{M2M100Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = M2M100Vocab::from_file(path).unwrap();
+    /// let (language_code, token_ids) = vocab.strip_language_prefix(&[128_028, 4, 5, 2]);
+    /// ```
+    pub fn strip_language_prefix<'a>(
+        &self,
+        tokens: &'a [i64],
+    ) -> (Option<&'static str>, &'a [i64]) {
+        let prefix = match tokens.first().and_then(|id| self.special_indices.get(id)) {
+            Some(prefix) if self.language_codes_bytes.contains(prefix.as_bytes()) => prefix,
+            _ => return (None, tokens),
+        };
+        match M2M100Vocab::language_codes()
+            .iter()
+            .find(|language_code| language_code_token(language_code) == *prefix)
+        {
+            Some(language_code) => (Some(*language_code), &tokens[1..]),
+            None => (None, tokens),
+        }
     }
 
-    fn from_file(path: &str) -> Result<M2M100Vocab, TokenizerError> {
-        let f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let br = BufReader::new(f);
-        let mut values: HashMap<String, i64> = match serde_json::from_reader(br) {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+    /// Create a vocabulary from an already parsed JSON value mapping tokens to ids (e.g. a field of
+    /// a larger JSON document), without serializing it back to read it with `from_reader`. The
+    /// language codes are added and the special values registered as when reading from file.
+    ///
+    /// # Parameters
+    /// - value (`&serde_json::Value`): JSON object mapping tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<M2M100Vocab, TokenizerError>`: vocabulary, error if the value is not an object of tokens to ids or if a special value is missing
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::M2M100Vocab;
+    /// use serde_json::json;
+    /// let config = json!({
+    ///     "model_type": "m2m_100",
+    ///     "vocab": {"<s>": 0, "<pad>": 1, "</s>": 2, "<unk>": 3}
+    /// });
+    ///
+    /// let vocab = M2M100Vocab::from_json_value(&config["vocab"]).unwrap();
+    /// ```
+    pub fn from_json_value(value: &serde_json::Value) -> Result<M2M100Vocab, TokenizerError> {
+        M2M100Vocab::from_values(M2M100Vocab::read_json_vocab_value(value)?)
+    }
+
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a JSON vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<M2M100Vocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(mut values: HashMap<String, i64>) -> Result<M2M100Vocab, TokenizerError> {
+        check_unique_ids(&values)?;
         let mut special_values = HashMap::new();
+        let mut ids = values.values().copied().collect::<HashSet<i64>>();
 
         for language_code in FAIRSEQ_LANGUAGE_CODES.iter() {
-            let language_code = if language_code.len() == 2 {
-                format!(">>{}.<<", language_code)
-            } else if language_code.len() == 3 {
-                format!(">>{}<<", language_code)
-            } else {
-                return Err(TokenizerError::VocabularyParsingError(
-                    "M2M100 Vocab only supports language code of length 2 or 3".to_string(),
-                ));
-            };
-            values.insert(language_code.clone(), values.len() as i64);
+            let language_code = language_code_token(language_code);
+            if !values.contains_key(&language_code) {
+                let language_code_id = values.len() as i64;
+                if !ids.insert(language_code_id) {
+                    return Err(TokenizerError::VocabularyParsingError(format!(
+                        "Id {} of language code {} is already used in the vocabulary",
+                        language_code_id, language_code
+                    )));
+                }
+                values.insert(language_code.clone(), language_code_id);
+            }
             M2M100Vocab::_register_as_special_value(
                 language_code.as_str(),
                 &values,
@@ -137,8 +277,8 @@ impl Vocab for M2M100Vocab {
             )?;
         }
 
-        let unknown_value = M2M100Vocab::unknown_value();
-        M2M100Vocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+        let unknown_value = M2M100Vocab::unknown_value().to_owned();
+        M2M100Vocab::_register_as_special_value(&unknown_value, &values, &mut special_values)?;
 
         let sep_value = M2M100Vocab::sep_value();
         M2M100Vocab::_register_as_special_value(sep_value, &values, &mut special_values)?;
@@ -156,15 +296,7 @@ impl Vocab for M2M100Vocab {
         let special_indices = swap_key_values(&special_values);
         let language_codes_bytes = FAIRSEQ_LANGUAGE_CODES
             .iter()
-            .map(|f| {
-                if f.len() == 2 {
-                    format!(">>{}.<<", f)
-                } else {
-                    format!(">>{}<<", f)
-                }
-                .as_bytes()
-                .to_vec()
-            })
+            .map(|f| language_code_token(f).into_bytes())
             .collect::<HashSet<Vec<u8>>>();
 
         Ok(M2M100Vocab {
@@ -176,17 +308,620 @@ impl Vocab for M2M100Vocab {
             language_codes_bytes,
         })
     }
+}
+
+/// Formats a language code as its prefix token: `>>xx.<<` for 2-character codes and `>>xxx<<` for
+/// codes of any other length (e.g. `>>ast<<` or `>>zh_Hans<<`)
+fn language_code_token(language_code: &str) -> String {
+    if language_code.len() == 2 {
+        format!(">>{}.<<", language_code)
+    } else {
+        format!(">>{}<<", language_code)
+    }
+}
+
+vocab_partial_eq!(M2M100Vocab, language_codes_bytes);
+
+impl Vocab for M2M100Vocab {
+    fn unknown_value() -> &'static str {
+        "<unk>"
+    }
+
+    fn get_unknown_value(&self) -> &str {
+        &self.unknown_value
+    }
+
+    fn expected_special_values(&self) -> Vec<&str> {
+        vec![
+            self.get_unknown_value(),
+            M2M100Vocab::sep_value(),
+            M2M100Vocab::bos_value(),
+            M2M100Vocab::eos_value(),
+            M2M100Vocab::pad_value(),
+        ]
+    }
+
+    special_token_config!(M2M100Vocab, bos_value, eos_value, sep_value, pad_value);
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<M2M100Vocab, TokenizerError> {
+        M2M100Vocab::from_values(M2M100Vocab::read_json_vocab(reader)?)
+    }
 
     fn token_to_id(&self, token: &str) -> i64 {
         self._token_to_id(
             token,
             &self.values,
             &self.special_values,
-            self.unknown_value,
+            &self.unknown_value,
         )
     }
 
     fn id_to_token(&self, id: &i64) -> String {
-        self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
+        self._id_to_token(
+            id,
+            &self.indices,
+            &self.special_indices,
+            &self.unknown_value,
+        )
+    }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+
+    fn render_ids(&self, ids: &[i64]) -> Vec<TokenKind> {
+        let pad_id = self.pad_id();
+        ids.iter()
+            .filter(|id| Some(**id) != pad_id)
+            .map(|id| match self.special_indices.get(id) {
+                Some(token) if self.language_codes_bytes.contains(token.as_bytes()) => {
+                    TokenKind::LanguagePrefix(token.clone())
+                }
+                Some(token) => TokenKind::Special(token.clone()),
+                None => TokenKind::Normal(self.id_to_token(id)),
+            })
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let language_code_id_range = self.language_code_id_range();
+        format!(
+            "size: {}, special tokens: {}, unknown value: {}, language codes: {}..={}",
+            self.len(),
+            self.num_special_tokens(),
+            self.get_unknown_value(),
+            language_code_id_range.start,
+            language_code_id_range.end - 1
+        )
+    }
+}
+
+impl VocabMut for M2M100Vocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+
+    /// Inserts a token. Tokens formatted as language prefixes (`>>xx.<<` or `>>xxx<<`) are
+    /// registered as language codes and special values.
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.values.insert(token.to_owned(), id);
+        self.indices.insert(id, token.to_owned());
+        if token.len() > 4 && token.starts_with(">>") && token.ends_with("<<") {
+            self.language_codes_bytes.insert(token.as_bytes().to_vec());
+            self.special_values.insert(token.to_owned(), id);
+            self.special_indices.insert(id, token.to_owned());
+        }
+    }
+}
+
+impl JsonVocab for M2M100Vocab {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use std::io::Write;
+
+    fn generate_test_vocab_file() -> anyhow::Result<tempfile::TempPath> {
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "{{\"<s>\": 0, \"<pad>\": 1, \"</s>\": 2, \"<unk>\": 3, \"\u{2581}hello\": 4}}"
+        )?;
+        Ok(vocab_file.into_temp_path())
+    }
+
+    #[test]
+    fn test_sep_and_eos_aliasing() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+
+        //        When
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        let sep_id = m2m100_vocab.token_to_id(M2M100Vocab::sep_value());
+        let eos_id = m2m100_vocab.token_to_id(M2M100Vocab::eos_value());
+        assert_eq!(sep_id, 2);
+        assert_eq!(sep_id, eos_id);
+        assert_eq!(
+            m2m100_vocab.special_values.len(),
+            FAIRSEQ_LANGUAGE_CODES.len() + 4
+        );
+        assert_eq!(m2m100_vocab.len(), FAIRSEQ_LANGUAGE_CODES.len() + 5);
+        assert!(m2m100_vocab.contains_token(">>fr.<<"));
+        assert!(m2m100_vocab.contains_token(">>ast<<"));
+        assert!(!m2m100_vocab.contains_token(">>xx.<<"));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_to_file() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let output_path = tempfile::NamedTempFile::new()?.into_temp_path();
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        m2m100_vocab.to_file(output_path.to_path_buf().to_str().unwrap())?;
+        let reloaded_vocab = M2M100Vocab::from_file(output_path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(reloaded_vocab.values, m2m100_vocab.values);
+        assert_eq!(reloaded_vocab.special_values, m2m100_vocab.special_values);
+        assert_eq!(reloaded_vocab.token_to_id(">>af.<<"), 5);
+        drop(path);
+        drop(output_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_special_token_ids() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let special_token_ids = m2m100_vocab.special_token_ids();
+        let language_prefix_ids = m2m100_vocab.language_prefix_ids();
+
+        //        Then
+        assert_eq!(
+            m2m100_vocab.num_special_tokens(),
+            FAIRSEQ_LANGUAGE_CODES.len() + 4
+        );
+        assert_eq!(special_token_ids.len(), FAIRSEQ_LANGUAGE_CODES.len() + 4);
+        assert!(special_token_ids.contains(&2));
+        assert!(!special_token_ids.contains(&4));
+        assert_eq!(language_prefix_ids.len(), FAIRSEQ_LANGUAGE_CODES.len());
+        assert!(language_prefix_ids.is_subset(&special_token_ids));
+        assert!(language_prefix_ids.contains(&5));
+        assert!(!language_prefix_ids.contains(&0));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_round_trip() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let serialized = serde_json::to_string(&m2m100_vocab)?;
+        let deserialized_vocab: M2M100Vocab = serde_json::from_str(&serialized)?;
+
+        //        Then
+        assert_eq!(deserialized_vocab, m2m100_vocab);
+        assert_eq!(deserialized_vocab.indices, m2m100_vocab.indices);
+        assert_eq!(deserialized_vocab.get_unknown_value(), "<unk>");
+        assert_eq!(
+            deserialized_vocab.get_language_id("fr"),
+            m2m100_vocab.get_language_id("fr")
+        );
+        for token in ["\u{2581}hello", ">>ast<<", "</s>", "oov_value"].iter() {
+            assert_eq!(
+                deserialized_vocab.token_to_id(token),
+                m2m100_vocab.token_to_id(token)
+            );
+        }
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_code_id_range() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let language_code_id_range = m2m100_vocab.language_code_id_range();
+
+        //        Then
+        assert_eq!(language_code_id_range, 5..105);
+        assert_eq!(
+            m2m100_vocab.token_to_id(">>af.<<"),
+            language_code_id_range.start
+        );
+        assert_eq!(
+            m2m100_vocab.token_to_id(">>zu.<<"),
+            language_code_id_range.end - 1
+        );
+        assert!(language_code_id_range
+            .clone()
+            .all(|id| m2m100_vocab.is_language_code_id(id)));
+        assert!(!m2m100_vocab.is_language_code_id(2));
+        assert!(!m2m100_vocab.is_language_code_id(4));
+        assert!(!m2m100_vocab.is_language_code_id(105));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_ids() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let fr_id = m2m100_vocab.token_to_id(">>fr.<<");
+
+        //        When
+        let rendered = m2m100_vocab.render_ids(&[fr_id, 4, 2, 1, 1]);
+
+        //        Then
+        assert_eq!(
+            rendered,
+            vec![
+                TokenKind::LanguagePrefix(">>fr.<<".to_string()),
+                TokenKind::Normal("\u{2581}hello".to_string()),
+                TokenKind::Special("</s>".to_string()),
+            ]
+        );
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_special_token_id_accessors() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(m2m100_vocab.pad_id(), Some(1));
+        assert_eq!(m2m100_vocab.bos_id(), Some(0));
+        assert_eq!(m2m100_vocab.eos_id(), Some(2));
+        assert_eq!(m2m100_vocab.sep_id(), Some(2));
+        assert_eq!(m2m100_vocab.cls_id(), None);
+        assert_eq!(m2m100_vocab.mask_id(), None);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_from_json_value() -> anyhow::Result<()> {
+        //        Given
+        let config = serde_json::json!({
+            "model_type": "m2m_100",
+            "vocab": {"<s>": 0, "<pad>": 1, "</s>": 2, "<unk>": 3, "\u{2581}hello": 4},
+            "vocab_with_metadata": {"<s>": 0, "<pad>": 1, "</s>": 2, "<unk>": 3, "name": "m2m_100"}
+        });
+
+        //        When
+        let m2m100_vocab = M2M100Vocab::from_json_value(&config["vocab"])?;
+        let not_an_object = M2M100Vocab::from_json_value(&config["model_type"]);
+        let missing_field = M2M100Vocab::from_json_value(&config["merges"]);
+        let non_integer_id = M2M100Vocab::from_json_value(&config["vocab_with_metadata"]);
+
+        //        Then
+        assert_eq!(m2m100_vocab.values.len(), 105);
+        assert_eq!(m2m100_vocab.token_to_id("\u{2581}hello"), 4);
+        assert_eq!(m2m100_vocab.token_to_id(">>af.<<"), 5);
+        assert_eq!(m2m100_vocab.special_values.get("<pad>"), Some(&1));
+        assert!(matches!(
+            not_an_object,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        assert!(matches!(
+            missing_field,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        assert!(matches!(
+            non_integer_id,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let summary = m2m100_vocab.summary();
+
+        //        Then
+        assert_eq!(
+            summary,
+            format!(
+                "size: 105, special tokens: {}, unknown value: <unk>, language codes: 5..=104",
+                m2m100_vocab.special_values.len()
+            )
+        );
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_language_code_tokens() -> anyhow::Result<()> {
+        //        Given
+        let values: HashMap<String, i64> = [("<s>", 0), ("<pad>", 1), ("</s>", 2), ("<unk>", 3)]
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect();
+        let mut m2m100_vocab = M2M100Vocab::from_values(values)?;
+        let first_new_id = m2m100_vocab.values.len() as i64;
+
+        //        When
+        let ids = m2m100_vocab.add_tokens(&[">>xx.<<".to_string(), "\u{2581}hello".to_string()]);
+
+        //        Then
+        assert_eq!(ids, vec![first_new_id, first_new_id + 1]);
+        assert_eq!(m2m100_vocab.get_language_id("xx"), Some(first_new_id));
+        assert!(m2m100_vocab.is_language_code_id(first_new_id));
+        assert!(!m2m100_vocab.is_language_code_id(first_new_id + 1));
+        assert_eq!(
+            m2m100_vocab.id_to_token(&(first_new_id + 1)),
+            "\u{2581}hello"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let mut other_vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            other_vocab_file,
+            "{{\"<s>\": 0, \"<pad>\": 1, \"</s>\": 2, \"<unk>\": 3, \"\u{2581}world\": 4}}"
+        )?;
+        let other_path = other_vocab_file.into_temp_path();
+        let mut m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let mut other_vocab = M2M100Vocab::from_file(other_path.to_path_buf().to_str().unwrap())?;
+        other_vocab.add_tokens(&["\u{2581}hello".to_string()]);
+
+        //        When
+        let (id_mapping, conflicts) = m2m100_vocab.merge(&other_vocab)?;
+
+        //        Then
+        let new_id = FAIRSEQ_LANGUAGE_CODES.len() as i64 + 5;
+        assert_eq!(conflicts, 1);
+        assert_eq!(m2m100_vocab.len(), FAIRSEQ_LANGUAGE_CODES.len() + 6);
+        assert_eq!(m2m100_vocab.token_to_id("\u{2581}hello"), 4);
+        assert_eq!(m2m100_vocab.token_to_id("\u{2581}world"), new_id);
+        assert_eq!(m2m100_vocab.token_to_id(">>af.<<"), 5);
+        assert_eq!(id_mapping.len(), FAIRSEQ_LANGUAGE_CODES.len() + 6);
+        assert_eq!(id_mapping[4], Some(new_id));
+        assert_eq!(id_mapping[new_id as usize], Some(4));
+        assert_eq!(m2m100_vocab.values.len(), m2m100_vocab.indices.len());
+        for (token, id) in m2m100_vocab.values.iter() {
+            assert_eq!(m2m100_vocab.indices.get(id), Some(token));
+        }
+        drop(path);
+        drop(other_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_missing_special_value() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let mut m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let mut other_vocab = m2m100_vocab.clone();
+        other_vocab.add_tokens(&["\u{2581}world".to_string()]);
+        other_vocab
+            .special_values
+            .insert("<extra>".to_string(), 1000);
+        let original_vocab = m2m100_vocab.clone();
+
+        //        When
+        let merge_result = m2m100_vocab.merge(&other_vocab);
+
+        //        Then
+        assert!(matches!(
+            merge_result,
+            Err(TokenizerError::TokenNotFound(_))
+        ));
+        assert_eq!(m2m100_vocab, original_vocab);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_file_with_added_tokens() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let mut added_tokens_file = tempfile::NamedTempFile::new()?;
+        write!(
+            added_tokens_file,
+            "{{\"<pad>\": 1, \"<extra>\": 105, \">>xx.<<\": 106}}"
+        )?;
+        let added_tokens_path = added_tokens_file.into_temp_path();
+        let mut colliding_tokens_file = tempfile::NamedTempFile::new()?;
+        write!(colliding_tokens_file, "{{\"<extra>\": 4}}")?;
+        let colliding_tokens_path = colliding_tokens_file.into_temp_path();
+
+        //        When
+        let m2m100_vocab = M2M100Vocab::from_file_with_added_tokens(
+            path.to_path_buf().to_str().unwrap(),
+            added_tokens_path.to_path_buf().to_str().unwrap(),
+        )?;
+        let colliding_vocab = M2M100Vocab::from_file_with_added_tokens(
+            path.to_path_buf().to_str().unwrap(),
+            colliding_tokens_path.to_path_buf().to_str().unwrap(),
+        );
+
+        //        Then
+        assert_eq!(m2m100_vocab.token_to_id("<extra>"), 105);
+        assert_eq!(m2m100_vocab.id_to_token(&105), "<extra>");
+        assert_eq!(m2m100_vocab.special_values.get("<pad>"), Some(&1));
+        assert!(!m2m100_vocab.special_values.contains_key("<extra>"));
+        assert_eq!(m2m100_vocab.special_values.get(">>xx.<<"), Some(&106));
+        assert!(m2m100_vocab
+            .language_codes_bytes
+            .contains(">>xx.<<".as_bytes()));
+        assert!(matches!(
+            colliding_vocab,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        drop(path);
+        drop(added_tokens_path);
+        drop(colliding_tokens_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_language_id() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(
+            m2m100_vocab.get_language_id("fr"),
+            Some(m2m100_vocab.token_to_id(">>fr.<<"))
+        );
+        assert_eq!(
+            m2m100_vocab.get_language_id("ast"),
+            Some(m2m100_vocab.token_to_id(">>ast<<"))
+        );
+        assert_eq!(m2m100_vocab.get_language_id("af"), Some(5));
+        assert_eq!(m2m100_vocab.get_language_id("xx"), None);
+        assert_eq!(m2m100_vocab.get_language_id("english"), None);
+        assert_eq!(M2M100Vocab::language_codes().len(), 100);
+        assert!(M2M100Vocab::language_codes().contains(&"zu"));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_language_prefix() -> anyhow::Result<()> {
+        //        Given
+        let path = generate_test_vocab_file()?;
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let french_id = m2m100_vocab.token_to_id(">>fr.<<");
+        let asturian_id = m2m100_vocab.token_to_id(">>ast<<");
+
+        //        When & Then
+        assert_eq!(
+            m2m100_vocab.strip_language_prefix(&[french_id, 4, 2]),
+            (Some("fr"), &[4, 2][..])
+        );
+        assert_eq!(
+            m2m100_vocab.strip_language_prefix(&[asturian_id]),
+            (Some("ast"), &[][..])
+        );
+        assert_eq!(
+            m2m100_vocab.strip_language_prefix(&[0, french_id, 4]),
+            (None, &[0, french_id, 4][..])
+        );
+        assert_eq!(m2m100_vocab.strip_language_prefix(&[]), (None, &[][..]));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_code_token() {
+        //        Given
+        let test_tuples = [
+            ("fr", ">>fr.<<"),
+            ("ast", ">>ast<<"),
+            ("zh_Hans", ">>zh_Hans<<"),
+            ("eng_Latn", ">>eng_Latn<<"),
+        ];
+
+        //        When & Then
+        for (language_code, expected_token) in test_tuples.iter() {
+            assert_eq!(language_code_token(language_code), *expected_token);
+        }
+    }
+
+    #[test]
+    fn test_decode_skip_special() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "{{\"<s>\": 0, \"<pad>\": 1, \"</s>\": 2, \"<unk>\": 3, \"hello\": 4, \"world\": 5}}"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap())?;
+        let french_id = m2m100_vocab.get_language_id("fr").unwrap();
+
+        //        When
+        let tokens = m2m100_vocab.decode_skip_special(&[0, french_id, 4, 5, 2, 1, 1]);
+
+        //        Then
+        assert_eq!(tokens, vec!["hello", "world"]);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_code_id_collision() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "{{\"<s>\": 0, \"<pad>\": 1, \"</s>\": 2, \"<unk>\": 3, \"\u{2581}hello\": 5}}"
+        )?;
+        let path = vocab_file.into_temp_path();
+
+        //        When
+        let m2m100_vocab = M2M100Vocab::from_file(path.to_path_buf().to_str().unwrap());
+
+        //        Then
+        assert!(matches!(
+            m2m100_vocab,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        drop(path);
+        Ok(())
     }
 }
@@ -13,11 +13,12 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::Vocab;
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq,
+};
+use crate::vocab::{JsonVocab, Vocab, VocabMut};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Read;
 
 /// # Marian Vocab
 /// Vocabulary for Marian tokenizer. Contains the following special values:
@@ -55,17 +56,59 @@ impl MarianVocab {
     pub fn eos_value() -> &'static str {
         "</s>"
     }
+
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a JSON vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<MarianVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<MarianVocab, TokenizerError> {
+        check_unique_ids(&values)?;
+        let mut special_values = HashMap::new();
+        let unknown_value = MarianVocab::unknown_value();
+        MarianVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+
+        let pad_value = MarianVocab::pad_value();
+        MarianVocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
+
+        let eos_value = MarianVocab::eos_value();
+        MarianVocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(MarianVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
+    }
 }
 
+vocab_partial_eq!(MarianVocab);
+
 impl Vocab for MarianVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            MarianVocab::unknown_value(),
+            MarianVocab::pad_value(),
+            MarianVocab::eos_value(),
+        ]
     }
 
+    special_token_config!(MarianVocab, eos_value, pad_value);
+
     fn values(&self) -> &HashMap<String, i64> {
         &self.values
     }
@@ -82,38 +125,8 @@ impl Vocab for MarianVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<MarianVocab, TokenizerError> {
-        let f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let br = BufReader::new(f);
-        let values: HashMap<String, i64> = match serde_json::from_reader(br) {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
-
-        let mut special_values = HashMap::new();
-        let unknown_value = MarianVocab::unknown_value();
-        MarianVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let pad_value = MarianVocab::pad_value();
-        MarianVocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
-
-        let eos_value = MarianVocab::eos_value();
-        MarianVocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
-
-        let indices = swap_key_values(&values);
-        let special_indices = swap_key_values(&special_values);
-
-        Ok(MarianVocab {
-            values,
-            indices,
-            unknown_value,
-            special_values,
-            special_indices,
-        })
+    fn from_reader<R: Read>(reader: R) -> Result<MarianVocab, TokenizerError> {
+        MarianVocab::from_values(MarianVocab::read_json_vocab(reader)?)
     }
 
     fn token_to_id(&self, token: &str) -> i64 {
@@ -128,4 +141,28 @@ impl Vocab for MarianVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for MarianVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
+
+impl JsonVocab for MarianVocab {}
@@ -12,11 +12,12 @@
 
 use crate::error::TokenizerError;
 use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{
+    read_protobuf, read_sentencepiece_proto_with_values, SentencePieceValues,
+};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::io::Read;
 
 pub static FAIRSEQ_LANGUAGE_CODES: [&str; 52] = [
@@ -86,15 +87,49 @@ impl MBart50Vocab {
     pub fn pad_value() -> &'static str {
         "<pad>"
     }
+
+    /// Returns the id of a language code token. Language codes can be given either in the format
+    /// of the vocabulary (e.g. `>>en<<`) or in the format of the original MBart-50 release
+    /// (e.g. `en_XX`), both referring to the same token.
+    ///
+    /// # Parameters
+    /// - language_code (`&str`): language code (e.g. `>>en<<` or `en_XX`)
+    ///
+    /// # Returns
+    /// - `Option<i64>`: id of the language code token, `None` if the language is not supported
+    pub fn language_code_id(&self, language_code: &str) -> Option<i64> {
+        let language_code = match language_code.split_once('_') {
+            Some((language, _)) => format!(">>{}<<", language),
+            None => language_code.to_owned(),
+        };
+        if self.language_codes_bytes.contains(language_code.as_bytes()) {
+            self.special_values.get(&language_code).copied()
+        } else {
+            None
+        }
+    }
 }
 
+vocab_partial_eq!(MBart50Vocab, language_codes_bytes);
+
 impl Vocab for MBart50Vocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            MBart50Vocab::unknown_value(),
+            MBart50Vocab::sep_value(),
+            MBart50Vocab::eos_value(),
+            MBart50Vocab::cls_value(),
+            MBart50Vocab::mask_value(),
+            MBart50Vocab::pad_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::mbart50()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -113,25 +148,9 @@ impl Vocab for MBart50Vocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<MBart50Vocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+    fn from_reader<R: Read>(reader: R) -> Result<MBart50Vocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
         let mut values = HashMap::new();
-        let mut special_values = HashMap::new();
-
         values.insert(MBart50Vocab::cls_value().to_owned(), values.len() as i64);
         values.insert(MBart50Vocab::pad_value().to_owned(), values.len() as i64);
         values.insert(MBart50Vocab::eos_value().to_owned(), values.len() as i64);
@@ -145,29 +164,24 @@ impl Vocab for MBart50Vocab {
 
         for language_code in FAIRSEQ_LANGUAGE_CODES.iter() {
             values.insert(language_code.to_string(), values.len() as i64);
-            MBart50Vocab::_register_as_special_value(language_code, &values, &mut special_values)?;
         }
 
         values.insert(MBart50Vocab::mask_value().to_owned(), values.len() as i64);
 
-        let unknown_value = MBart50Vocab::unknown_value();
-        MBart50Vocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let sep_value = MBart50Vocab::sep_value();
-        MBart50Vocab::_register_as_special_value(sep_value, &values, &mut special_values)?;
-
-        let eos_value = MBart50Vocab::eos_value();
-        MBart50Vocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
-
-        let cls_value = MBart50Vocab::cls_value();
-        MBart50Vocab::_register_as_special_value(cls_value, &values, &mut special_values)?;
-
-        let mask_value = MBart50Vocab::mask_value();
-        MBart50Vocab::_register_as_special_value(mask_value, &values, &mut special_values)?;
-
-        let pad_value = MBart50Vocab::pad_value();
-        MBart50Vocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
+        let SentencePieceValues {
+            values,
+            mut special_values,
+            ..
+        } = read_sentencepiece_proto_with_values::<MBart50Vocab>(
+            &proto,
+            values,
+            &SpecialTokenConfig::mbart50(),
+        )?;
+        for language_code in FAIRSEQ_LANGUAGE_CODES.iter() {
+            MBart50Vocab::_register_as_special_value(language_code, &values, &mut special_values)?;
+        }
 
+        let unknown_value = MBart50Vocab::unknown_value();
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
         let language_codes_bytes = FAIRSEQ_LANGUAGE_CODES
@@ -197,4 +211,26 @@ impl Vocab for MBart50Vocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for MBart50Vocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
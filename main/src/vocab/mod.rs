@@ -20,6 +20,8 @@
 //! The following vocabularies have been implemented:
 //! - BERT
 //! - ALBERT
+//! - DeBERTa (v2)
+//! - FNet
 //! - GPT2
 //! - GPT
 //! - Marian
@@ -28,47 +30,81 @@
 //! - XLMRoBERTa
 //! - XLNet
 //! - SentencePiece
+//! - Byte-level (character-level Reformer)
 //!
 //! All vocabularies implement the `Vocab` trait exposing a standard interface for integration with
-//! the tokenizers.
+//! the tokenizers. `PretrainedVocab` loads the vocabulary matching the model type of a pretrained
+//! model directory. `VocabSet` holds one vocabulary per language code for multilingual settings
+//! relying on language-specific vocabulary files. `ScriptUnknownVocab` resolves script-specific
+//! unknown tokens for multilingual vocabularies. `CorpusVocabBuilder` creates a word-level
+//! vocabulary from the token counts of a corpus. `CachedVocab` caches the ids of frequently looked up
+//! tokens. Vocabularies read from JSON files implement `JsonVocab`, writing them back in the same
+//! format.
+//!
+//! With the `sha2` feature enabled, `Vocab::from_file_verified` checks the SHA-256 digest of a
+//! vocabulary file before loading it, and returns a `VerifiedVocab` recording the digest.
 
 mod albert_vocab;
 pub(crate) mod base_vocab;
 mod bert_vocab;
 pub(crate) mod bpe_vocab;
+mod byte_vocab;
+mod cached_vocab;
+#[cfg(feature = "sha2")]
+mod checksum;
+mod deberta_v2_vocab;
+mod fnet_vocab;
 mod gpt2_vocab;
 mod m2m100_vocab;
 mod marian_vocab;
 mod mbart50_vocab;
 mod openai_gpt_vocab;
 mod pegasus_vocab;
+pub(crate) mod pretrained_vocab;
 mod prophetnet_vocab;
 mod reformer_vocab;
 mod roberta_vocab;
+mod script_unknown_vocab;
 mod sentence_piece_bpe_model;
+mod sentence_piece_normalizer;
 mod sentence_piece_unigram_model;
 mod sentence_piece_vocab;
 pub(crate) mod sentencepiece_proto;
+mod special_token_config;
 mod t5_vocab;
+mod vocab_builder;
+mod vocab_set;
 mod xlm_roberta_vocab;
 mod xlnet_vocab;
 
 pub use albert_vocab::AlbertVocab;
-pub use base_vocab::{BaseVocab, Vocab};
+pub use base_vocab::{BaseVocab, JsonVocab, TokenKind, Vocab, VocabDiff, VocabMut};
 pub use bert_vocab::BertVocab;
 pub use bpe_vocab::{BpePairRef, BpePairVocab};
+pub use byte_vocab::ByteVocab;
+pub use cached_vocab::{CachedVocab, DEFAULT_CACHE_CAPACITY};
+#[cfg(feature = "sha2")]
+pub use checksum::{file_sha256, VerifiedVocab};
+pub use deberta_v2_vocab::DeBERTaV2Vocab;
+pub use fnet_vocab::FNetVocab;
 pub use gpt2_vocab::Gpt2Vocab;
 pub use m2m100_vocab::M2M100Vocab;
 pub use marian_vocab::MarianVocab;
 pub use mbart50_vocab::MBart50Vocab;
 pub use openai_gpt_vocab::OpenAiGptVocab;
 pub use pegasus_vocab::PegasusVocab;
+pub use pretrained_vocab::PretrainedVocab;
 pub use prophetnet_vocab::ProphetNetVocab;
 pub use reformer_vocab::ReformerVocab;
 pub use roberta_vocab::RobertaVocab;
+pub use script_unknown_vocab::{Script, ScriptUnknownVocab};
 pub use sentence_piece_bpe_model::SentencePieceBpeModel;
-pub use sentence_piece_unigram_model::SentencePieceModel;
-pub use sentence_piece_vocab::SentencePieceVocab;
+pub use sentence_piece_normalizer::SentencePieceNormalizer;
+pub use sentence_piece_unigram_model::{PieceInfo, PieceType, SentencePieceModel};
+pub use sentence_piece_vocab::{SentencePieceVocab, MAX_SENTENCEPIECE_MODEL_SIZE};
+pub use special_token_config::SpecialTokenConfig;
 pub use t5_vocab::T5Vocab;
+pub use vocab_builder::{CorpusVocabBuilder, VocabBuilder};
+pub use vocab_set::VocabSet;
 pub use xlm_roberta_vocab::XLMRobertaVocab;
 pub use xlnet_vocab::XLNetVocab;
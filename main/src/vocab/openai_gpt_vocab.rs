@@ -12,10 +12,11 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::{swap_key_values, Vocab};
+use crate::vocab::base_vocab::{
+    check_unique_ids, swap_key_values, vocab_partial_eq, JsonVocab, Vocab, VocabMut,
+};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Read;
 
 /// # GPT Vocab
 /// Vocabulary for GPT tokenizer. Only contains the unknown token as a special value.
@@ -40,12 +41,40 @@ pub struct OpenAiGptVocab {
     pub special_indices: HashMap<i64, String>,
 }
 
-impl Vocab for OpenAiGptVocab {
-    fn unknown_value() -> &'static str {
-        "<unk>"
+impl OpenAiGptVocab {
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a JSON vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<OpenAiGptVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<OpenAiGptVocab, TokenizerError> {
+        check_unique_ids(&values)?;
+        let mut special_values = HashMap::new();
+        let unknown_value = OpenAiGptVocab::unknown_value();
+        OpenAiGptVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+
+        let indices = swap_key_values(&values);
+        let special_indices = swap_key_values(&special_values);
+
+        Ok(OpenAiGptVocab {
+            values,
+            indices,
+            unknown_value,
+            special_values,
+            special_indices,
+        })
     }
+}
 
-    fn get_unknown_value(&self) -> &'static str {
+vocab_partial_eq!(OpenAiGptVocab);
+
+impl Vocab for OpenAiGptVocab {
+    fn unknown_value() -> &'static str {
         "<unk>"
     }
 
@@ -65,31 +94,8 @@ impl Vocab for OpenAiGptVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<OpenAiGptVocab, TokenizerError> {
-        let f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let br = BufReader::new(f);
-        let values: HashMap<String, i64> = match serde_json::from_reader(br) {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
-        let mut special_values = HashMap::new();
-        let unknown_value = OpenAiGptVocab::unknown_value();
-        OpenAiGptVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let indices = swap_key_values(&values);
-        let special_indices = swap_key_values(&special_values);
-
-        Ok(OpenAiGptVocab {
-            values,
-            indices,
-            unknown_value,
-            special_values,
-            special_indices,
-        })
+    fn from_reader<R: Read>(reader: R) -> Result<OpenAiGptVocab, TokenizerError> {
+        OpenAiGptVocab::from_values(OpenAiGptVocab::read_json_vocab(reader)?)
     }
 
     fn token_to_id(&self, token: &str) -> i64 {
@@ -106,6 +112,26 @@ impl Vocab for OpenAiGptVocab {
     }
 }
 
+impl VocabMut for OpenAiGptVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+impl JsonVocab for OpenAiGptVocab {}
+
 //==============================
 // Unit tests
 //==============================
@@ -12,12 +12,13 @@
 
 use crate::error::TokenizerError;
 use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{
+    read_protobuf, read_sentencepiece_proto_with_values, SentencePieceValues,
+};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::Read;
 
 /// # Pegasus Vocab
@@ -70,26 +71,25 @@ impl PegasusVocab {
     }
 }
 
-impl PegasusVocab {
-    fn _add_and_register_special_value(
-        values: &mut HashMap<String, i64>,
-        special_values: &mut HashMap<String, i64>,
-        value: &str,
-        offset: i64,
-    ) -> Result<i64, TokenizerError> {
-        values.insert(value.to_string(), offset as i64);
-        PegasusVocab::_register_as_special_value(value, values, special_values)?;
-        Ok(offset + 1)
-    }
-}
+vocab_partial_eq!(PegasusVocab);
 
 impl Vocab for PegasusVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            PegasusVocab::unknown_value(),
+            PegasusVocab::pad_value(),
+            PegasusVocab::eos_value(),
+            PegasusVocab::mask_value(),
+            PegasusVocab::mask_sent_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::pegasus()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -108,75 +108,26 @@ impl Vocab for PegasusVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<PegasusVocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
-
-        let mut values = HashMap::new();
-        let mut special_values = HashMap::new();
+    fn from_reader<R: Read>(reader: R) -> Result<PegasusVocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
 
         // Insert special tokens (not contained in SentencePiece proto)
-        let mut offset = 0_i64;
-
-        // pad value
-        let pad_value = PegasusVocab::pad_value();
-        offset = PegasusVocab::_add_and_register_special_value(
-            &mut values,
-            &mut special_values,
-            pad_value,
-            offset,
-        )?;
-
-        // EOS value
-        let eos_value = PegasusVocab::eos_value();
-        offset = PegasusVocab::_add_and_register_special_value(
-            &mut values,
-            &mut special_values,
-            eos_value,
-            offset,
-        )?;
-
-        // Mask value
-        let mask_value = PegasusVocab::mask_value();
-        offset = PegasusVocab::_add_and_register_special_value(
-            &mut values,
-            &mut special_values,
-            mask_value,
-            offset,
-        )?;
-
-        // Sentence mask value
-        let mask_sent_value = PegasusVocab::mask_sent_value();
-        offset = PegasusVocab::_add_and_register_special_value(
-            &mut values,
-            &mut special_values,
-            mask_sent_value,
-            offset,
-        )?;
-
+        let additional_special_values: Vec<String> = [
+            PegasusVocab::pad_value(),
+            PegasusVocab::eos_value(),
+            PegasusVocab::mask_value(),
+            PegasusVocab::mask_sent_value(),
+        ]
+        .iter()
+        .map(|value| value.to_string())
         // Reserved additional special tokens
-        for idx in 2..103 {
-            let add_unk_token = format!("<unk_{}>", idx);
-            offset = PegasusVocab::_add_and_register_special_value(
-                &mut values,
-                &mut special_values,
-                add_unk_token.as_str(),
-                offset,
-            )?;
+        .chain((2..103).map(|idx| format!("<unk_{}>", idx)))
+        .collect();
+        let mut values = HashMap::new();
+        for value in additional_special_values.iter() {
+            values.insert(value.clone(), values.len() as i64);
         }
+        let offset = values.len() as i64;
 
         let mut current_piece: String;
         let mut idx = 0;
@@ -191,9 +142,20 @@ impl Vocab for PegasusVocab {
             };
         }
 
-        let unknown_value = PegasusVocab::unknown_value();
-        PegasusVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
+        let SentencePieceValues {
+            values,
+            mut special_values,
+            ..
+        } = read_sentencepiece_proto_with_values::<PegasusVocab>(
+            &proto,
+            values,
+            &SpecialTokenConfig::pegasus(),
+        )?;
+        for value in additional_special_values.iter() {
+            PegasusVocab::_register_as_special_value(value, &values, &mut special_values)?;
+        }
 
+        let unknown_value = PegasusVocab::unknown_value();
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
 
@@ -218,4 +180,26 @@ impl Vocab for PegasusVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for PegasusVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
@@ -0,0 +1,493 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::{
+    AlbertVocab, BertVocab, DeBERTaV2Vocab, FNetVocab, Gpt2Vocab, M2M100Vocab, MBart50Vocab,
+    MarianVocab, OpenAiGptVocab, PegasusVocab, ProphetNetVocab, ReformerVocab, RobertaVocab,
+    T5Vocab, Vocab, XLMRobertaVocab, XLNetVocab,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// # PretrainedVocab
+/// Vocabulary loaded from a pretrained model directory (as distributed by the Transformers library),
+/// wrapping the concrete vocabulary matching the model type. The model type is read from the
+/// `tokenizer_class` of `tokenizer_config.json` if available, and from the `model_type` of
+/// `config.json` otherwise. The vocabulary file expected for the model type is then loaded from the
+/// same directory:
+/// - ALBERT, FNet, Pegasus, Reformer, T5, XLNet: `spiece.model`
+/// - BERT, DistilBERT: `vocab.txt`
+/// - DeBERTa (v2): `spm.model`
+/// - GPT, GPT2, CTRL, RoBERTa, M2M100, Marian: `vocab.json`
+/// - MBart50, XLMRoBERTa: `sentencepiece.bpe.model`
+/// - ProphetNet: `prophetnet.tokenizer`
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum PretrainedVocab {
+    /// ALBERT vocabulary
+    Albert(Box<AlbertVocab>),
+    /// BERT (and DistilBERT) vocabulary
+    Bert(Box<BertVocab>),
+    /// DeBERTa (v2) vocabulary
+    DeBERTaV2(Box<DeBERTaV2Vocab>),
+    /// FNet vocabulary
+    FNet(Box<FNetVocab>),
+    /// GPT2 vocabulary
+    Gpt2(Box<Gpt2Vocab>),
+    /// M2M100 vocabulary
+    M2M100(Box<M2M100Vocab>),
+    /// Marian vocabulary
+    Marian(Box<MarianVocab>),
+    /// MBart50 vocabulary
+    MBart50(Box<MBart50Vocab>),
+    /// GPT (and CTRL) vocabulary
+    OpenAiGpt(Box<OpenAiGptVocab>),
+    /// Pegasus vocabulary
+    Pegasus(Box<PegasusVocab>),
+    /// ProphetNet vocabulary
+    ProphetNet(Box<ProphetNetVocab>),
+    /// Reformer vocabulary
+    Reformer(Box<ReformerVocab>),
+    /// RoBERTa vocabulary
+    Roberta(Box<RobertaVocab>),
+    /// T5 vocabulary
+    T5(Box<T5Vocab>),
+    /// XLMRoBERTa vocabulary
+    XLMRoberta(Box<XLMRobertaVocab>),
+    /// XLNet vocabulary
+    XLNet(Box<XLNetVocab>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $vocab:ident => $body:expr) => {
+        match $self {
+            PretrainedVocab::Albert($vocab) => $body,
+            PretrainedVocab::Bert($vocab) => $body,
+            PretrainedVocab::DeBERTaV2($vocab) => $body,
+            PretrainedVocab::FNet($vocab) => $body,
+            PretrainedVocab::Gpt2($vocab) => $body,
+            PretrainedVocab::M2M100($vocab) => $body,
+            PretrainedVocab::Marian($vocab) => $body,
+            PretrainedVocab::MBart50($vocab) => $body,
+            PretrainedVocab::OpenAiGpt($vocab) => $body,
+            PretrainedVocab::Pegasus($vocab) => $body,
+            PretrainedVocab::ProphetNet($vocab) => $body,
+            PretrainedVocab::Reformer($vocab) => $body,
+            PretrainedVocab::Roberta($vocab) => $body,
+            PretrainedVocab::T5($vocab) => $body,
+            PretrainedVocab::XLMRoberta($vocab) => $body,
+            PretrainedVocab::XLNet($vocab) => $body,
+        }
+    };
+}
+
+impl PretrainedVocab {
+    /// Create a new instance of a `PretrainedVocab` from a pretrained model directory.
+    ///
+    /// # Parameters
+    /// - path (`&Path`): path to the directory containing the configuration and vocabulary files
+    ///
+    /// # Returns
+    /// - `Result<PretrainedVocab, TokenizerError>`: vocabulary matching the model type, or an error if the model type can not be determined, is not supported, or if the vocabulary file can not be loaded
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::PretrainedVocab;
+    /// let vocab = PretrainedVocab::from_pretrained("path/to/model/directory").unwrap();
+    /// let token_id = vocab.token_to_id("hello");
+    /// ```
+    pub fn from_pretrained<P: AsRef<Path>>(path: P) -> Result<PretrainedVocab, TokenizerError> {
+        let path = path.as_ref();
+        let model_type = read_model_type(path)?;
+        let vocab_path = |file_name: &str| -> Result<String, TokenizerError> {
+            path.join(file_name)
+                .to_str()
+                .map(|value| value.to_owned())
+                .ok_or_else(|| {
+                    TokenizerError::FileNotFound(format!(
+                        "invalid vocabulary path for {}",
+                        file_name
+                    ))
+                })
+        };
+        Ok(match normalize_model_type(&model_type).as_str() {
+            "albert" => PretrainedVocab::Albert(Box::new(AlbertVocab::from_file(&vocab_path(
+                "spiece.model",
+            )?)?)),
+            "bert" | "distilbert" => {
+                PretrainedVocab::Bert(Box::new(BertVocab::from_file(&vocab_path("vocab.txt")?)?))
+            }
+            "debertav2" => PretrainedVocab::DeBERTaV2(Box::new(DeBERTaV2Vocab::from_file(
+                &vocab_path("spm.model")?,
+            )?)),
+            "fnet" => PretrainedVocab::FNet(Box::new(FNetVocab::from_file(&vocab_path(
+                "spiece.model",
+            )?)?)),
+            "gpt2" => {
+                PretrainedVocab::Gpt2(Box::new(Gpt2Vocab::from_file(&vocab_path("vocab.json")?)?))
+            }
+            "m2m100" => PretrainedVocab::M2M100(Box::new(M2M100Vocab::from_file(&vocab_path(
+                "vocab.json",
+            )?)?)),
+            "marian" => PretrainedVocab::Marian(Box::new(MarianVocab::from_file(&vocab_path(
+                "vocab.json",
+            )?)?)),
+            "mbart50" => PretrainedVocab::MBart50(Box::new(MBart50Vocab::from_file(&vocab_path(
+                "sentencepiece.bpe.model",
+            )?)?)),
+            "openaigpt" | "ctrl" => PretrainedVocab::OpenAiGpt(Box::new(
+                OpenAiGptVocab::from_file(&vocab_path("vocab.json")?)?,
+            )),
+            "pegasus" => PretrainedVocab::Pegasus(Box::new(PegasusVocab::from_file(&vocab_path(
+                "spiece.model",
+            )?)?)),
+            "prophetnet" => PretrainedVocab::ProphetNet(Box::new(ProphetNetVocab::from_file(
+                &vocab_path("prophetnet.tokenizer")?,
+            )?)),
+            "reformer" => PretrainedVocab::Reformer(Box::new(ReformerVocab::from_file(
+                &vocab_path("spiece.model")?,
+            )?)),
+            "roberta" => PretrainedVocab::Roberta(Box::new(RobertaVocab::from_file(&vocab_path(
+                "vocab.json",
+            )?)?)),
+            "t5" => {
+                PretrainedVocab::T5(Box::new(T5Vocab::from_file(&vocab_path("spiece.model")?)?))
+            }
+            "xlmroberta" => PretrainedVocab::XLMRoberta(Box::new(XLMRobertaVocab::from_file(
+                &vocab_path("sentencepiece.bpe.model")?,
+            )?)),
+            "xlnet" => PretrainedVocab::XLNet(Box::new(XLNetVocab::from_file(&vocab_path(
+                "spiece.model",
+            )?)?)),
+            _ => {
+                return Err(TokenizerError::ValueError(format!(
+                    "Unsupported model type: {}",
+                    model_type
+                )));
+            }
+        })
+    }
+
+    /// Returns the string used for unknown (out of vocabulary) tokens by the wrapped vocabulary
+    pub fn get_unknown_value(&self) -> &str {
+        dispatch!(self, vocab => vocab.get_unknown_value())
+    }
+
+    /// Return the map of token strings to IDs
+    pub fn values(&self) -> &HashMap<String, i64> {
+        dispatch!(self, vocab => vocab.values())
+    }
+
+    /// Return the number of entries in the wrapped vocabulary
+    pub fn len(&self) -> usize {
+        dispatch!(self, vocab => vocab.len())
+    }
+
+    /// Return `true` if the wrapped vocabulary contains no entries
+    pub fn is_empty(&self) -> bool {
+        dispatch!(self, vocab => vocab.is_empty())
+    }
+
+    /// Return `true` if the token is present in the wrapped vocabulary (regular or special value)
+    pub fn contains_token(&self, token: &str) -> bool {
+        dispatch!(self, vocab => vocab.contains_token(token))
+    }
+
+    /// Return `true` if the id is present in the wrapped vocabulary (regular or special value)
+    pub fn contains_id(&self, id: i64) -> bool {
+        dispatch!(self, vocab => vocab.contains_id(id))
+    }
+
+    /// Return an iterator over the (token, id) pairs of the wrapped vocabulary
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (&str, i64)> + '_> {
+        dispatch!(self, vocab => vocab.iter())
+    }
+
+    /// Return an iterator over the (token, id) pairs of the special values of the wrapped vocabulary
+    pub fn iter_special(&self) -> Box<dyn Iterator<Item = (&str, i64)> + '_> {
+        dispatch!(self, vocab => vocab.iter_special())
+    }
+
+    /// Return a one-line summary of the wrapped vocabulary
+    pub fn summary(&self) -> String {
+        dispatch!(self, vocab => vocab.summary())
+    }
+
+    /// Return the id of the padding token of the wrapped vocabulary, if defined
+    pub fn pad_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.pad_id())
+    }
+
+    /// Return the id of the beginning of sequence token of the wrapped vocabulary, if defined
+    pub fn bos_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.bos_id())
+    }
+
+    /// Return the id of the end of sequence token of the wrapped vocabulary, if defined
+    pub fn eos_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.eos_id())
+    }
+
+    /// Return the id of the separation token of the wrapped vocabulary, if defined
+    pub fn sep_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.sep_id())
+    }
+
+    /// Return the id of the class token of the wrapped vocabulary, if defined
+    pub fn cls_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.cls_id())
+    }
+
+    /// Return the id of the mask token of the wrapped vocabulary, if defined
+    pub fn mask_id(&self) -> Option<i64> {
+        dispatch!(self, vocab => vocab.mask_id())
+    }
+
+    /// Return the map of token IDs to strings
+    pub fn indices(&self) -> &HashMap<i64, String> {
+        dispatch!(self, vocab => vocab.indices())
+    }
+
+    /// Return the map of token strings to IDs for special values
+    pub fn special_values(&self) -> &HashMap<String, i64> {
+        dispatch!(self, vocab => vocab.special_values())
+    }
+
+    /// Return the map of token IDs to strings for special values
+    pub fn special_indices(&self) -> &HashMap<i64, String> {
+        dispatch!(self, vocab => vocab.special_indices())
+    }
+
+    /// Converts a token to an id using the wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - token (`&str`): token to convert
+    ///
+    /// # Returns
+    /// - `i64`: token index for the value provided. If not found in the indices, returns the unknown token index
+    pub fn token_to_id(&self, token: &str) -> i64 {
+        dispatch!(self, vocab => vocab.token_to_id(token))
+    }
+
+    /// Converts an id to a token using the wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - id (`&i64`): token id to convert
+    ///
+    /// # Returns
+    /// - `String`: token value for the index provided. If not found in the indices, returns the unknown token value
+    pub fn id_to_token(&self, id: &i64) -> String {
+        dispatch!(self, vocab => vocab.id_to_token(id))
+    }
+
+    /// Converts a list of tokens to a list of indices using the wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - tokens (`&[&str]`): list of tokens to convert
+    ///
+    /// # Returns
+    /// - `Vec<i64>`: Vector containing the indices for the tokens provided
+    pub fn convert_tokens_to_ids(&self, tokens: &[&str]) -> Vec<i64> {
+        dispatch!(self, vocab => vocab.convert_tokens_to_ids(tokens))
+    }
+
+    /// Converts a list of indices to a list of tokens using the wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): list of indices to convert
+    ///
+    /// # Returns
+    /// - `Vec<String>`: Vector containing the tokens for the indices provided
+    pub fn convert_ids_to_tokens(&self, ids: &[i64]) -> Vec<String> {
+        dispatch!(self, vocab => vocab.convert_ids_to_tokens(ids))
+    }
+
+    /// Returns `true` if the token for an id starts a new word, following the convention of the
+    /// wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - id (`i64`): token id
+    ///
+    /// # Returns
+    /// - `bool`: flag indicating if the token starts a new word
+    pub fn is_word_start(&self, id: i64) -> bool {
+        dispatch!(self, vocab => vocab.is_word_start(id))
+    }
+
+    /// Partitions a sequence of ids into per-word groups, following the word start convention of
+    /// the wrapped vocabulary.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): sequence of token ids
+    ///
+    /// # Returns
+    /// - `Vec<Vec<i64>>`: id groups, one per word
+    pub fn group_subwords(&self, ids: &[i64]) -> Vec<Vec<i64>> {
+        dispatch!(self, vocab => vocab.group_subwords(ids))
+    }
+}
+
+pub(crate) fn read_json_file(path: &Path) -> Result<Option<Value>, TokenizerError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let f = File::open(path).map_err(|e| {
+        TokenizerError::FileNotFound(format!("{} file not found :{}", path.display(), e))
+    })?;
+    let value: Value = serde_json::from_reader(BufReader::new(f)).map_err(|e| {
+        TokenizerError::VocabularyParsingError(format!(
+            "Invalid configuration file {}: {}",
+            path.display(),
+            e
+        ))
+    })?;
+    Ok(Some(value))
+}
+
+pub(crate) fn read_model_type(path: &Path) -> Result<String, TokenizerError> {
+    let tokenizer_class = read_json_file(&path.join("tokenizer_config.json"))?.and_then(|config| {
+        config
+            .get("tokenizer_class")
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_owned())
+    });
+    if let Some(tokenizer_class) = tokenizer_class {
+        return Ok(tokenizer_class);
+    }
+    read_json_file(&path.join("config.json"))?
+        .and_then(|config| {
+            config
+                .get("model_type")
+                .and_then(|value| value.as_str())
+                .map(|value| value.to_owned())
+        })
+        .ok_or_else(|| {
+            TokenizerError::FileNotFound(format!(
+                "No model type found in the configuration files of {}",
+                path.display()
+            ))
+        })
+}
+
+/// Maps a `model_type` (e.g. `xlm-roberta`) or `tokenizer_class` (e.g. `XLMRobertaTokenizerFast`)
+/// to a common lower-case identifier (e.g. `xlmroberta`)
+pub(crate) fn normalize_model_type(model_type: &str) -> String {
+    let model_type = model_type.to_lowercase().replace(['-', '_'], "");
+    let model_type = model_type.strip_suffix("fast").unwrap_or(&model_type);
+    model_type
+        .strip_suffix("tokenizer")
+        .unwrap_or(model_type)
+        .to_owned()
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_normalize_model_type() {
+        //        Given
+        let test_tuples = [
+            ("bert", "bert"),
+            ("xlm-roberta", "xlmroberta"),
+            ("XLMRobertaTokenizerFast", "xlmroberta"),
+            ("m2m_100", "m2m100"),
+            ("M2M100Tokenizer", "m2m100"),
+            ("deberta-v2", "debertav2"),
+            ("DebertaV2Tokenizer", "debertav2"),
+            ("OpenAIGPTTokenizer", "openaigpt"),
+        ];
+
+        //        When & Then
+        for (model_type, expected_result) in test_tuples.iter() {
+            assert_eq!(normalize_model_type(model_type), *expected_result);
+        }
+    }
+
+    #[test]
+    fn test_create_object_from_pretrained() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut config_file = File::create(model_dir.path().join("config.json"))?;
+        write!(config_file, "{{\"model_type\": \"bert\"}}")?;
+        let mut vocab_file = File::create(model_dir.path().join("vocab.txt"))?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n ! \n [CLS] \n [SEP] \n [MASK] \n [PAD]"
+        )?;
+
+        //        When
+        let vocab = PretrainedVocab::from_pretrained(model_dir.path())?;
+
+        //        Then
+        assert!(matches!(vocab, PretrainedVocab::Bert(_)));
+        assert_eq!(vocab.token_to_id("world"), 1);
+        assert_eq!(vocab.id_to_token(&3), "!");
+        assert_eq!(vocab.get_unknown_value(), "[UNK]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenizer_config_takes_precedence() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut config_file = File::create(model_dir.path().join("config.json"))?;
+        write!(config_file, "{{\"model_type\": \"gpt2\"}}")?;
+        let mut tokenizer_config_file =
+            File::create(model_dir.path().join("tokenizer_config.json"))?;
+        write!(
+            tokenizer_config_file,
+            "{{\"tokenizer_class\": \"DistilBertTokenizer\"}}"
+        )?;
+        let mut vocab_file = File::create(model_dir.path().join("vocab.txt"))?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n [CLS] \n [SEP] \n [MASK] \n [PAD]"
+        )?;
+
+        //        When
+        let vocab = PretrainedVocab::from_pretrained(model_dir.path())?;
+
+        //        Then
+        assert!(matches!(vocab, PretrainedVocab::Bert(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_model_type() -> anyhow::Result<()> {
+        //        Given
+        let model_dir = tempfile::tempdir()?;
+        let mut config_file = File::create(model_dir.path().join("config.json"))?;
+        write!(config_file, "{{\"model_type\": \"unknown-model\"}}")?;
+
+        //        When
+        let vocab = PretrainedVocab::from_pretrained(model_dir.path());
+
+        //        Then
+        assert!(matches!(vocab, Err(TokenizerError::ValueError(_))));
+        assert!(matches!(
+            PretrainedVocab::from_pretrained(tempfile::tempdir()?.path()),
+            Err(TokenizerError::FileNotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -11,14 +11,17 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::{swap_key_values, Vocab};
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq, Vocab, VocabMut,
+};
 use std::collections::HashMap;
+use std::io::Read;
 
 /// # ProphetNet Vocab
 /// Vocabulary for ProphetNet tokenizer. Contains the following special values:
 /// - SEP token
 /// - CLS token
-/// - X_SEP token
+/// - X_SEP token (optional, registered if present in the vocabulary)
 /// - PAD token
 /// - MASK token
 ///
@@ -68,35 +71,62 @@ impl ProphetNetVocab {
     pub fn mask_value() -> &'static str {
         "[MASK]"
     }
-}
-
-impl Vocab for ProphetNetVocab {
-    fn unknown_value() -> &'static str {
-        "[UNK]"
-    }
-
-    fn get_unknown_value(&self) -> &'static str {
-        "[UNK]"
-    }
-
-    fn values(&self) -> &HashMap<String, i64> {
-        &self.values
-    }
-
-    fn indices(&self) -> &HashMap<i64, String> {
-        &self.indices
-    }
 
-    fn special_values(&self) -> &HashMap<String, i64> {
-        &self.special_values
+    /// Returns the id of the X_SEP token, used by ProphetNet to separate sentences within a sequence,
+    /// `None` if the vocabulary does not contain it
+    pub fn x_sep_id(&self) -> Option<i64> {
+        self.special_values
+            .get(ProphetNetVocab::x_sep_value())
+            .copied()
     }
 
-    fn special_indices(&self) -> &HashMap<i64, String> {
-        &self.special_indices
+    /// Returns the target ids of the predicting streams of ProphetNet for a sequence of ids. The
+    /// model predicts the next `ngram` tokens at each position: the stream `k` (starting at 0)
+    /// holds the sequence shifted left by `k` positions, padded at the end with the id of the PAD
+    /// token. The first stream is the sequence itself.
+    ///
+    /// # Parameters
+    /// - ids (`&[i64]`): sequence of target ids
+    /// - ngram (`usize`): number of future tokens predicted at each position
+    ///
+    /// # Returns
+    /// - `Vec<Vec<i64>>`: `ngram` sequences of ids, with the same length as `ids`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{ProphetNetVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = ProphetNetVocab::from_file(path).unwrap();
+    /// let stream_ids = vocab.ngram_stream_ids(&[2023, 2003, 1037, 3231, 102], 2);
+    /// ```
+    pub fn ngram_stream_ids(&self, ids: &[i64], ngram: usize) -> Vec<Vec<i64>> {
+        let pad_id = self.token_to_id(ProphetNetVocab::pad_value());
+        (0..ngram)
+            .map(|stream| {
+                ids.iter()
+                    .skip(stream)
+                    .copied()
+                    .chain(std::iter::repeat(pad_id))
+                    .take(ids.len())
+                    .collect()
+            })
+            .collect()
     }
 
-    fn from_file(path: &str) -> Result<ProphetNetVocab, TokenizerError> {
-        let values = ProphetNetVocab::read_vocab_file(path)?;
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a flat text vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<ProphetNetVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<ProphetNetVocab, TokenizerError> {
+        check_unique_ids(&values)?;
         let mut special_values = HashMap::new();
 
         let unknown_value = ProphetNetVocab::unknown_value();
@@ -114,6 +144,11 @@ impl Vocab for ProphetNetVocab {
         let mask_value = ProphetNetVocab::mask_value();
         ProphetNetVocab::_register_as_special_value(mask_value, &values, &mut special_values)?;
 
+        let x_sep_value = ProphetNetVocab::x_sep_value();
+        if values.contains_key(x_sep_value) {
+            ProphetNetVocab::_register_as_special_value(x_sep_value, &values, &mut special_values)?;
+        }
+
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
 
@@ -125,6 +160,47 @@ impl Vocab for ProphetNetVocab {
             special_indices,
         })
     }
+}
+
+vocab_partial_eq!(ProphetNetVocab);
+
+impl Vocab for ProphetNetVocab {
+    fn unknown_value() -> &'static str {
+        "[UNK]"
+    }
+
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            ProphetNetVocab::unknown_value(),
+            ProphetNetVocab::pad_value(),
+            ProphetNetVocab::cls_value(),
+            ProphetNetVocab::sep_value(),
+            ProphetNetVocab::mask_value(),
+            ProphetNetVocab::x_sep_value(),
+        ]
+    }
+
+    special_token_config!(ProphetNetVocab, cls_value, sep_value, mask_value, pad_value);
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<ProphetNetVocab, TokenizerError> {
+        ProphetNetVocab::from_values(ProphetNetVocab::read_vocab(reader)?)
+    }
 
     fn token_to_id(&self, token: &str) -> i64 {
         self._token_to_id(
@@ -140,6 +216,24 @@ impl Vocab for ProphetNetVocab {
     }
 }
 
+impl VocabMut for ProphetNetVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
 //==============================
 // Unit tests
 //==============================
@@ -204,6 +298,7 @@ mod tests {
 
         let special_values: HashMap<String, i64> = [
             ("[UNK]".to_owned(), 2),
+            ("[X_SEP]".to_owned(), 4),
             ("[SEP]".to_owned(), 5),
             ("[MASK]".to_owned(), 6),
             ("[PAD]".to_owned(), 7),
@@ -220,6 +315,55 @@ mod tests {
         assert_eq!(base_vocab.unknown_value, "[UNK]");
         assert_eq!(base_vocab.values, target_values);
         assert_eq!(base_vocab.special_values, special_values);
+        assert_eq!(base_vocab.x_sep_id(), Some(4));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_from_file_without_x_sep_token() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n ! \n [SEP] \n [MASK] \n [PAD] \n [CLS]"
+        )?;
+        let path = vocab_file.into_temp_path();
+
+        //        When
+        let base_vocab = ProphetNetVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(base_vocab.x_sep_id(), None);
+        assert_eq!(base_vocab.special_values.len(), 5);
+        assert_eq!(base_vocab.token_to_id("[X_SEP]"), 2);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ngram_stream_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(
+            vocab_file,
+            "hello \n world \n [UNK] \n ! \n [X_SEP] \n [SEP] \n [MASK] \n [PAD] \n [CLS]"
+        )?;
+        let path = vocab_file.into_temp_path();
+        let base_vocab = ProphetNetVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let stream_ids = base_vocab.ngram_stream_ids(&[0, 1, 3, 5], 3);
+
+        //        Then
+        assert_eq!(
+            stream_ids,
+            vec![vec![0, 1, 3, 5], vec![1, 3, 5, 7], vec![3, 5, 7, 7]]
+        );
+        assert_eq!(
+            base_vocab.ngram_stream_ids(&[0, 1], 0),
+            Vec::<Vec<i64>>::new()
+        );
         drop(path);
         Ok(())
     }
@@ -13,11 +13,10 @@
 
 use crate::error::TokenizerError;
 use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{read_sentencepiece, SentencePieceValues};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::Read;
 
 /// # ReformerVocab
@@ -52,13 +51,19 @@ impl ReformerVocab {
     }
 }
 
+vocab_partial_eq!(ReformerVocab);
+
 impl Vocab for ReformerVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![ReformerVocab::unknown_value(), ReformerVocab::eos_value()]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::reformer()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -77,35 +82,14 @@ impl Vocab for ReformerVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<ReformerVocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
-
-        let mut values = HashMap::new();
-        for (idx, piece) in proto.get_pieces().iter().enumerate() {
-            values.insert(piece.get_piece().to_owned(), idx as i64);
-        }
-
-        let mut special_values = HashMap::new();
-        let unknown_value = ReformerVocab::unknown_value();
-        ReformerVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let eos_value = ReformerVocab::eos_value();
-        ReformerVocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
+    fn from_reader<R: Read>(reader: R) -> Result<ReformerVocab, TokenizerError> {
+        let SentencePieceValues {
+            values,
+            special_values,
+            ..
+        } = read_sentencepiece::<ReformerVocab, _>(reader, &SpecialTokenConfig::reformer())?;
 
+        let unknown_value = ReformerVocab::unknown_value();
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
 
@@ -130,4 +114,26 @@ impl Vocab for ReformerVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for ReformerVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
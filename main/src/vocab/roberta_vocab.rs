@@ -12,10 +12,12 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::{swap_key_values, Vocab};
+use crate::vocab::base_vocab::{
+    check_unique_ids, special_token_config, swap_key_values, vocab_partial_eq, JsonVocab, Vocab,
+    VocabMut,
+};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::Read;
 
 /// # RoBERTa Vocab
 /// Vocabulary for RoBERTa tokenizer. Contains the following special values:
@@ -77,45 +79,19 @@ impl RobertaVocab {
     pub fn mask_value() -> &'static str {
         "<mask>"
     }
-}
-
-impl Vocab for RobertaVocab {
-    fn unknown_value() -> &'static str {
-        "<unk>"
-    }
-
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
-    }
 
-    fn values(&self) -> &HashMap<String, i64> {
-        &self.values
-    }
-
-    fn indices(&self) -> &HashMap<i64, String> {
-        &self.indices
-    }
-
-    fn special_values(&self) -> &HashMap<String, i64> {
-        &self.special_values
-    }
-
-    fn special_indices(&self) -> &HashMap<i64, String> {
-        &self.special_indices
-    }
-
-    ///Read a Roberta-style vocab.json file
-    fn from_file(path: &str) -> Result<RobertaVocab, TokenizerError> {
-        let f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let br = BufReader::new(f);
-        let values: HashMap<String, i64> = match serde_json::from_reader(br) {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+    /// Create a vocabulary from a mapping of tokens to ids, without reading a file (e.g. to build a
+    /// small vocabulary in code). The special values of the vocabulary are registered and must be
+    /// present in the mapping. `from_file` reads the mapping from a JSON vocabulary file and
+    /// calls this method.
+    ///
+    /// # Parameters
+    /// - values (`HashMap<String, i64>`): mapping of tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<RobertaVocab, TokenizerError>`: vocabulary, error if the ids are not unique or if a special value is missing
+    pub fn from_values(values: HashMap<String, i64>) -> Result<RobertaVocab, TokenizerError> {
+        check_unique_ids(&values)?;
         let mut special_values = HashMap::new();
         let unknown_value = RobertaVocab::unknown_value();
         RobertaVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
@@ -149,6 +125,57 @@ impl Vocab for RobertaVocab {
             special_indices,
         })
     }
+}
+
+vocab_partial_eq!(RobertaVocab);
+
+impl Vocab for RobertaVocab {
+    fn unknown_value() -> &'static str {
+        "<unk>"
+    }
+
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            RobertaVocab::unknown_value(),
+            RobertaVocab::pad_value(),
+            RobertaVocab::sep_value(),
+            RobertaVocab::cls_value(),
+            RobertaVocab::mask_value(),
+            RobertaVocab::bos_value(),
+            RobertaVocab::eos_value(),
+        ]
+    }
+
+    special_token_config!(
+        RobertaVocab,
+        bos_value,
+        eos_value,
+        cls_value,
+        sep_value,
+        mask_value,
+        pad_value
+    );
+
+    fn values(&self) -> &HashMap<String, i64> {
+        &self.values
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        &self.indices
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        &self.special_values
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        &self.special_indices
+    }
+
+    ///Read a Roberta-style vocab.json file
+    fn from_reader<R: Read>(reader: R) -> Result<RobertaVocab, TokenizerError> {
+        RobertaVocab::from_values(RobertaVocab::read_json_vocab(reader)?)
+    }
 
     fn token_to_id(&self, token: &str) -> i64 {
         self._token_to_id(
@@ -162,8 +189,32 @@ impl Vocab for RobertaVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{0120}')
+    }
 }
 
+impl VocabMut for RobertaVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+impl JsonVocab for RobertaVocab {}
+
 //==============================
 // Unit tests
 //==============================
@@ -0,0 +1,318 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::tokenizer::tokenization_utils::is_cjk_char;
+use crate::vocab::{JsonVocab, SpecialTokenConfig, Vocab, VocabMut};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// # Script
+/// Writing system of a character, used to select a script-specific unknown token. The scripts are
+/// identified from Unicode block ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    /// Latin letters (including accented letters)
+    Latin,
+    /// Greek letters
+    Greek,
+    /// Cyrillic letters
+    Cyrillic,
+    /// Hebrew letters
+    Hebrew,
+    /// Arabic letters
+    Arabic,
+    /// Devanagari letters
+    Devanagari,
+    /// Thai letters
+    Thai,
+    /// Hangul syllables and jamo
+    Hangul,
+    /// CJK ideographs, Hiragana and Katakana
+    Cjk,
+    /// Characters shared by all scripts (digits, punctuation, symbols, whitespaces) and scripts not listed above
+    Common,
+}
+
+impl Script {
+    /// Returns the script of a character
+    ///
+    /// # Parameters
+    /// - character (`char`): character to classify
+    ///
+    /// # Returns
+    /// - `Script`: script of the character
+    pub fn of(character: char) -> Script {
+        match character as u32 {
+            0x0041..=0x005A
+            | 0x0061..=0x007A
+            | 0x00C0..=0x00D6
+            | 0x00D8..=0x00F6
+            | 0x00F8..=0x024F
+            | 0x1E00..=0x1EFF => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F => Script::Cyrillic,
+            0x0590..=0x05FF => Script::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0x08A0..=0x08FF => Script::Arabic,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0E00..=0x0E7F => Script::Thai,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+            0x3040..=0x30FF => Script::Cjk,
+            _ if is_cjk_char(&character) => Script::Cjk,
+            _ => Script::Common,
+        }
+    }
+}
+
+/// # ScriptUnknownVocab
+/// Vocabulary wrapper supporting script-specific unknown tokens, for multilingual vocabularies
+/// defining one unknown token per writing system (e.g. a CJK unknown token and a Latin unknown
+/// token). Out-of-vocabulary tokens are mapped to the unknown token registered for the script of
+/// their first character that is not shared by all scripts, falling back to the unknown token of
+/// the wrapped vocabulary. Without registered script unknown tokens, the wrapper behaves as the
+/// wrapped vocabulary. The wrapper can be used with the tokenizers generic over their vocabulary
+/// (`BaseTokenizer` and `BertTokenizer`), which map words that cannot be segmented to the unknown
+/// token of their script.
+#[derive(Debug, Clone)]
+pub struct ScriptUnknownVocab<V: Vocab> {
+    /// Wrapped vocabulary
+    pub vocab: V,
+
+    /// A mapping of scripts to the IDs of their unknown tokens
+    pub script_unknown_ids: HashMap<Script, i64>,
+}
+
+impl<V: Vocab> ScriptUnknownVocab<V> {
+    /// Create a new `ScriptUnknownVocab` wrapping an existing vocabulary, without script-specific
+    /// unknown tokens
+    ///
+    /// # Parameters
+    /// - vocab (`V`): vocabulary to wrap
+    pub fn new(vocab: V) -> ScriptUnknownVocab<V> {
+        ScriptUnknownVocab {
+            vocab,
+            script_unknown_ids: HashMap::new(),
+        }
+    }
+}
+
+impl<V: VocabMut> ScriptUnknownVocab<V> {
+    /// Register the unknown token for a script. The token is also registered as a special value.
+    ///
+    /// # Parameters
+    /// - script (`Script`): script resolved to the unknown token
+    /// - token (`&str`): unknown token for the script, which must be present in the vocabulary
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: error if the token is not in the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, Script, ScriptUnknownVocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let mut vocab = ScriptUnknownVocab::new(BertVocab::from_file(path).unwrap());
+    /// vocab.register_unknown(Script::Cjk, "[UNK_CJK]").unwrap();
+    /// let unknown_id = vocab.unknown_id_for_char('中');
+    /// ```
+    pub fn register_unknown(&mut self, script: Script, token: &str) -> Result<(), TokenizerError> {
+        let token_id = *self.vocab.values().get(token).ok_or_else(|| {
+            TokenizerError::TokenNotFound(format!(
+                "The unknown value {} could not be found in the vocabulary",
+                token
+            ))
+        })?;
+        self.vocab
+            .special_values_mut()
+            .insert(token.to_owned(), token_id);
+        self.vocab
+            .special_indices_mut()
+            .insert(token_id, token.to_owned());
+        self.script_unknown_ids.insert(script, token_id);
+        Ok(())
+    }
+}
+
+impl<V: Vocab> Vocab for ScriptUnknownVocab<V> {
+    fn unknown_value() -> &'static str {
+        V::unknown_value()
+    }
+
+    fn get_unknown_value(&self) -> &str {
+        self.vocab.get_unknown_value()
+    }
+
+    fn expected_special_values(&self) -> Vec<&str> {
+        self.vocab.expected_special_values()
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        self.vocab.special_token_config()
+    }
+
+    fn values(&self) -> &HashMap<String, i64> {
+        self.vocab.values()
+    }
+
+    fn indices(&self) -> &HashMap<i64, String> {
+        self.vocab.indices()
+    }
+
+    fn special_values(&self) -> &HashMap<String, i64> {
+        self.vocab.special_values()
+    }
+
+    fn special_indices(&self) -> &HashMap<i64, String> {
+        self.vocab.special_indices()
+    }
+
+    fn from_reader<R: Read>(reader: R) -> Result<ScriptUnknownVocab<V>, TokenizerError> {
+        Ok(ScriptUnknownVocab::new(V::from_reader(reader)?))
+    }
+
+    fn from_file(path: &str) -> Result<ScriptUnknownVocab<V>, TokenizerError> {
+        Ok(ScriptUnknownVocab::new(V::from_file(path)?))
+    }
+
+    fn token_to_id(&self, token: &str) -> i64 {
+        if let Some(index) = self.vocab.try_token_to_id(token) {
+            return index;
+        }
+        match token
+            .chars()
+            .find(|character| Script::of(*character) != Script::Common)
+        {
+            Some(character) => self.unknown_id_for_char(character),
+            None => self.vocab.token_to_id(token),
+        }
+    }
+
+    fn try_token_to_id(&self, token: &str) -> Option<i64> {
+        self.vocab.try_token_to_id(token)
+    }
+
+    fn contains_token(&self, token: &str) -> bool {
+        self.vocab.contains_token(token)
+    }
+
+    fn id_to_token(&self, id: &i64) -> String {
+        self.vocab.id_to_token(id)
+    }
+
+    fn unknown_id_for_char(&self, character: char) -> i64 {
+        match self.script_unknown_ids.get(&Script::of(character)) {
+            Some(index) => *index,
+            None => self.vocab.unknown_id_for_char(character),
+        }
+    }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self.vocab.is_word_start(id)
+    }
+}
+
+impl<V: VocabMut> VocabMut for ScriptUnknownVocab<V> {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.vocab.values_mut()
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.vocab.indices_mut()
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        self.vocab.special_values_mut()
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        self.vocab.special_indices_mut()
+    }
+
+    fn insert_token(&mut self, token: &str, id: i64) {
+        self.vocab.insert_token(token, id)
+    }
+
+    fn add_tokens(&mut self, tokens: &[String]) -> Vec<i64> {
+        self.vocab.add_tokens(tokens)
+    }
+}
+
+impl<V: JsonVocab> JsonVocab for ScriptUnknownVocab<V> {}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::BaseVocab;
+    use std::io::Write;
+
+    #[test]
+    fn test_script_of() {
+        //        Given
+        let test_tuples = [
+            ('a', Script::Latin),
+            ('é', Script::Latin),
+            ('×', Script::Common),
+            ('α', Script::Greek),
+            ('ж', Script::Cyrillic),
+            ('ש', Script::Hebrew),
+            ('ع', Script::Arabic),
+            ('क', Script::Devanagari),
+            ('ก', Script::Thai),
+            ('한', Script::Hangul),
+            ('中', Script::Cjk),
+            ('か', Script::Cjk),
+            ('7', Script::Common),
+            ('#', Script::Common),
+            ('\u{2581}', Script::Common),
+        ];
+
+        //        When & Then
+        for (character, expected_script) in test_tuples.iter() {
+            assert_eq!(Script::of(*character), *expected_script);
+        }
+    }
+
+    #[test]
+    fn test_script_unknown_ids() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n [UNK] \n [UNK_CJK] \n [UNK_LATIN]")?;
+        let path = vocab_file.into_temp_path();
+        let mut vocab: ScriptUnknownVocab<BaseVocab> =
+            ScriptUnknownVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let single_unknown_id = vocab.unknown_id_for_char('中');
+        vocab.register_unknown(Script::Cjk, "[UNK_CJK]")?;
+        vocab.register_unknown(Script::Latin, "[UNK_LATIN]")?;
+        let missing = vocab.register_unknown(Script::Greek, "[UNK_GREEK]");
+
+        //        Then
+        assert_eq!(single_unknown_id, 1);
+        assert_eq!(vocab.unknown_id_for_char('中'), 2);
+        assert_eq!(vocab.unknown_id_for_char('a'), 3);
+        assert_eq!(vocab.unknown_id_for_char('ж'), 1);
+        assert_eq!(vocab.token_to_id("hello"), 0);
+        assert_eq!(vocab.token_to_id("##華"), 2);
+        assert_eq!(vocab.token_to_id("\u{2581}world"), 3);
+        assert_eq!(vocab.token_to_id("!?"), 1);
+        assert!(vocab.special_values().contains_key("[UNK_CJK]"));
+        assert!(matches!(missing, Err(TokenizerError::TokenNotFound(_))));
+        drop(path);
+        Ok(())
+    }
+}
@@ -14,14 +14,11 @@
 use crate::error::TokenizerError;
 use crate::tokenizer::base_tokenizer::{Token, TokenRef};
 use crate::tokenizer::tokenization_utils::{is_punctuation, is_whitespace};
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
+use crate::vocab::sentence_piece_vocab::read_protobuf_file;
 use crate::{Mask, Offset, OffsetSize};
 use hashbrown::HashMap;
-use protobuf::Message;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
-use std::fs::File;
-use std::io::Read;
 use std::ops::Index;
 
 #[derive(Debug, Clone)]
@@ -49,21 +46,7 @@ impl SentencePieceBpeModel {
     /// let sentence_piece_model = SentencePieceBpeModel::from_file(path);
     /// ```
     pub fn from_file(path: &str) -> Result<SentencePieceBpeModel, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+        let proto = read_protobuf_file(path)?;
 
         let mut values = HashMap::new();
         for (idx, piece) in proto.get_pieces().iter().enumerate() {
@@ -0,0 +1,364 @@
+// Copyright 2016 Google LLC. All Rights Reserved.
+// Copyright 2019-2020 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::sentence_piece_vocab::read_protobuf_file;
+use crate::vocab::sentencepiece_proto::sentencepiece_model::NormalizerSpec;
+use crate::{OffsetSize, Token};
+use serde::{Deserialize, Serialize};
+
+/// # SentencePiece normalizer
+/// Normalization rules of a SentencePiece model, read from the `NormalizerSpec` of the protobuf
+/// file. The precompiled character map is a double-array trie (in the darts-clone format) mapping
+/// input strings to their normalized replacement, applied by the reference implementation before
+/// segmentation (e.g. NFKC normalization and removal of control characters for `nmt_nfkc`).
+///
+/// The whitespace options are exposed for information: the tokenizers handle whitespaces
+/// themselves and only the character map replacements are applied by `normalize`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentencePieceNormalizer {
+    /// Name of the normalization rule (e.g. `nmt_nfkc`)
+    pub name: String,
+
+    /// Flag indicating if a whitespace is added at the beginning of the text
+    pub add_dummy_prefix: bool,
+
+    /// Flag indicating if leading, trailing and duplicate internal whitespaces are removed
+    pub remove_extra_whitespaces: bool,
+
+    /// Flag indicating if whitespaces are replaced by the meta symbol `▁`
+    pub escape_whitespaces: bool,
+
+    trie: Vec<u32>,
+    normalized: Vec<u8>,
+}
+
+impl SentencePieceNormalizer {
+    /// Read the normalizer of a SentencePiece protobuf file
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the SentencePiece model file
+    ///
+    /// # Returns
+    /// - `Result<SentencePieceNormalizer, TokenizerError>`: error if the file or its precompiled character map cannot be parsed
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::SentencePieceNormalizer;
+    /// let path = "path/to/spiece.model";
+    ///
+    /// let normalizer = SentencePieceNormalizer::from_file(path).unwrap();
+    /// ```
+    pub fn from_file(path: &str) -> Result<SentencePieceNormalizer, TokenizerError> {
+        let proto = read_protobuf_file(path)?;
+        SentencePieceNormalizer::from_proto(proto.get_normalizer_spec())
+    }
+
+    pub(crate) fn from_proto(
+        spec: &NormalizerSpec,
+    ) -> Result<SentencePieceNormalizer, TokenizerError> {
+        let (trie, normalized) = decode_precompiled_charsmap(spec.get_precompiled_charsmap())?;
+        Ok(SentencePieceNormalizer {
+            name: spec.get_name().to_owned(),
+            add_dummy_prefix: spec.get_add_dummy_prefix(),
+            remove_extra_whitespaces: spec.get_remove_extra_whitespaces(),
+            escape_whitespaces: spec.get_escape_whitespaces(),
+            trie,
+            normalized,
+        })
+    }
+
+    /// Returns `true` if the normalizer defines character map replacements
+    pub fn has_rules(&self) -> bool {
+        !self.trie.is_empty()
+    }
+
+    /// Returns the replacement for the longest prefix of the input matching a rule of the
+    /// character map, with the length in bytes of the matched prefix.
+    fn longest_match<'a>(&'a self, input: &str) -> Option<(usize, &'a str)> {
+        let unit = |position: usize| self.trie.get(position).copied();
+        let offset = |unit: u32| ((unit >> 10) << ((unit & (1 << 9)) >> 6)) as usize;
+
+        let mut longest = None;
+        let mut node_position = offset(unit(0)?);
+        for (byte_index, byte) in input.bytes().enumerate() {
+            node_position ^= byte as usize;
+            let node = match unit(node_position) {
+                Some(node) if node & ((1 << 31) | 0xFF) == byte as u32 => node,
+                _ => break,
+            };
+            node_position ^= offset(node);
+            if (node >> 8) & 1 == 1 && input.is_char_boundary(byte_index + 1) {
+                if let Some(leaf) = unit(node_position) {
+                    longest = Some((byte_index + 1, (leaf & ((1 << 31) - 1)) as usize));
+                }
+            }
+        }
+        let (length, value) = longest?;
+        let replacement = self.normalized.get(value..)?;
+        let end = replacement
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(replacement.len());
+        std::str::from_utf8(&replacement[..end])
+            .ok()
+            .map(|replacement| (length, replacement))
+    }
+
+    /// Apply the character map replacements to a string
+    ///
+    /// # Parameters
+    /// - text (`&str`): text to normalize
+    ///
+    /// # Returns
+    /// - `String`: normalized text
+    pub fn normalize(&self, text: &str) -> String {
+        if !self.has_rules() {
+            return text.to_owned();
+        }
+        let mut output = String::with_capacity(text.len());
+        let mut position = 0;
+        while let Some(character) = text[position..].chars().next() {
+            match self.longest_match(&text[position..]) {
+                Some((length, replacement)) => {
+                    output.push_str(replacement);
+                    position += length;
+                }
+                None => {
+                    output.push(character);
+                    position += character.len_utf8();
+                }
+            }
+        }
+        output
+    }
+
+    /// Apply the character map replacements to a token, updating its offsets. Characters inserted
+    /// by a replacement refer to the first original character of the matched prefix.
+    ///
+    /// # Parameters
+    /// - token (`&mut Token`): token to normalize
+    pub fn normalize_token(&self, token: &mut Token) {
+        if !self.has_rules() {
+            return;
+        }
+        let mut normalized_string = String::with_capacity(token.text.len());
+        let mut character_mapping: Vec<OffsetSize> =
+            Vec::with_capacity(token.reference_offsets.len());
+        let mut position = 0;
+        let mut char_position = 0;
+        while let Some(character) = token.text[position..].chars().next() {
+            let reference_offset = token.reference_offsets[char_position];
+            match self.longest_match(&token.text[position..]) {
+                Some((length, replacement)) => {
+                    normalized_string.push_str(replacement);
+                    character_mapping.extend(std::iter::repeat_n(
+                        reference_offset,
+                        replacement.chars().count(),
+                    ));
+                    char_position += token.text[position..position + length].chars().count();
+                    position += length;
+                }
+                None => {
+                    normalized_string.push(character);
+                    character_mapping.push(reference_offset);
+                    char_position += 1;
+                    position += character.len_utf8();
+                }
+            }
+        }
+        token.text = normalized_string;
+        token.reference_offsets = character_mapping;
+        token.offset.begin = *token.reference_offsets.first().unwrap_or(&(0));
+        token.offset.end = *token.reference_offsets.last().unwrap_or(&(0)) + 1;
+    }
+}
+
+/// Splits a precompiled character map into the units of its double-array trie and the
+/// null-terminated replacement strings. The blob starts with the size in bytes of the trie as a
+/// little-endian `u32`, followed by the trie and the replacement strings.
+fn decode_precompiled_charsmap(blob: &[u8]) -> Result<(Vec<u32>, Vec<u8>), TokenizerError> {
+    if blob.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+    if blob.len() < 4 {
+        return Err(TokenizerError::VocabularyParsingError(
+            "Precompiled character map is too short".to_string(),
+        ));
+    }
+    let trie_size = u32::from_le_bytes([blob[0], blob[1], blob[2], blob[3]]) as usize;
+    if !trie_size.is_multiple_of(4) || trie_size > blob.len() - 4 {
+        return Err(TokenizerError::VocabularyParsingError(format!(
+            "Invalid trie size {} in precompiled character map of {} bytes",
+            trie_size,
+            blob.len()
+        )));
+    }
+    let trie = blob[4..4 + trie_size]
+        .chunks_exact(4)
+        .map(|unit| u32::from_le_bytes([unit[0], unit[1], unit[2], unit[3]]))
+        .collect();
+    let normalized = blob[4 + trie_size..].to_vec();
+    Ok((trie, normalized))
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Offset;
+    use std::collections::BTreeSet;
+
+    /// Builds a precompiled character map in the darts-clone double-array format
+    pub(crate) fn build_precompiled_charsmap(rules: &[(&str, &str)]) -> Vec<u8> {
+        let mut normalized: Vec<u8> = vec![];
+        let mut keys: Vec<(Vec<u8>, u32)> = vec![];
+        for (key, replacement) in rules {
+            keys.push((key.as_bytes().to_vec(), normalized.len() as u32));
+            normalized.extend_from_slice(replacement.as_bytes());
+            normalized.push(0);
+        }
+
+        let mut units: Vec<u32> = vec![0];
+        let mut used: Vec<bool> = vec![true];
+        let mut stack: Vec<(usize, Vec<u8>)> = vec![(0, vec![])];
+        while let Some((position, prefix)) = stack.pop() {
+            let mut children: BTreeSet<u8> = BTreeSet::new();
+            let mut value = None;
+            for (key, key_value) in keys.iter() {
+                if key.starts_with(&prefix) {
+                    if key.len() == prefix.len() {
+                        value = Some(*key_value);
+                    } else {
+                        children.insert(key[prefix.len()]);
+                    }
+                }
+            }
+            let base = (1usize..)
+                .find(|base| {
+                    (value.is_none() || !used.get(*base).copied().unwrap_or(false))
+                        && children.iter().all(|label| {
+                            !used.get(base ^ *label as usize).copied().unwrap_or(false)
+                        })
+                })
+                .unwrap();
+            let required = children
+                .iter()
+                .map(|label| base ^ *label as usize)
+                .chain(std::iter::once(base))
+                .max()
+                .unwrap();
+            if units.len() <= required {
+                units.resize(required + 1, 0);
+                used.resize(required + 1, false);
+            }
+            let offset = (position ^ base) as u32;
+            units[position] |= (offset << 10) | if value.is_some() { 1 << 8 } else { 0 };
+            if let Some(value) = value {
+                units[base] = (1 << 31) | value;
+                used[base] = true;
+            }
+            for label in children.iter() {
+                let child_position = base ^ *label as usize;
+                units[child_position] = *label as u32;
+                used[child_position] = true;
+                let mut child_prefix = prefix.clone();
+                child_prefix.push(*label);
+                stack.push((child_position, child_prefix));
+            }
+        }
+
+        let mut blob = ((units.len() * 4) as u32).to_le_bytes().to_vec();
+        for unit in units {
+            blob.extend_from_slice(&unit.to_le_bytes());
+        }
+        blob.extend(normalized);
+        blob
+    }
+
+    fn generate_test_normalizer() -> SentencePieceNormalizer {
+        let mut spec = NormalizerSpec::new();
+        spec.set_name("test".to_string());
+        spec.set_precompiled_charsmap(build_precompiled_charsmap(&[
+            ("\u{FF21}", "A"),
+            ("\u{FB01}", "fi"),
+            ("f", "F"),
+            ("ff", "<ff>"),
+            ("\u{200B}", ""),
+        ]));
+        SentencePieceNormalizer::from_proto(&spec).unwrap()
+    }
+
+    #[test]
+    fn test_normalize() {
+        //        Given
+        let normalizer = generate_test_normalizer();
+
+        //        When & Then
+        assert!(normalizer.has_rules());
+        assert_eq!(normalizer.name, "test");
+        assert_eq!(normalizer.normalize("\u{FF21}b\u{FB01}"), "Abfi");
+        assert_eq!(normalizer.normalize("fff"), "<ff>F");
+        assert_eq!(normalizer.normalize("a\u{200B}b"), "ab");
+        assert_eq!(normalizer.normalize("héllo"), "héllo");
+        assert_eq!(normalizer.normalize(""), "");
+    }
+
+    #[test]
+    fn test_normalize_token() {
+        //        Given
+        let normalizer = generate_test_normalizer();
+        let mut token = Token {
+            text: "\u{FB01}\u{200B}x\u{FF21}".to_string(),
+            offset: Offset { begin: 0, end: 4 },
+            reference_offsets: vec![0, 1, 2, 3],
+            mask: Default::default(),
+        };
+
+        //        When
+        normalizer.normalize_token(&mut token);
+
+        //        Then
+        assert_eq!(token.text, "fixA");
+        assert_eq!(token.reference_offsets, vec![0, 0, 2, 3]);
+        assert_eq!(token.offset, Offset { begin: 0, end: 4 });
+    }
+
+    #[test]
+    fn test_empty_normalizer() {
+        //        Given
+        let normalizer = SentencePieceNormalizer::from_proto(&NormalizerSpec::new()).unwrap();
+
+        //        When & Then
+        assert!(!normalizer.has_rules());
+        assert_eq!(normalizer.normalize("\u{FF21}"), "\u{FF21}");
+    }
+
+    #[test]
+    fn test_invalid_precompiled_charsmap() {
+        //        Given
+        let mut spec = NormalizerSpec::new();
+        spec.set_precompiled_charsmap(vec![0xFF, 0, 0, 0, 1, 2]);
+
+        //        When
+        let normalizer = SentencePieceNormalizer::from_proto(&spec);
+
+        //        Then
+        assert!(matches!(
+            normalizer,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+    }
+}
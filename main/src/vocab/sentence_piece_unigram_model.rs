@@ -11,14 +11,14 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::tokenizer::tokenization_utils::{is_punctuation, is_whitespace};
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
+use crate::tokenizer::tokenization_utils::{
+    is_punctuation, is_whitespace, prepend_whitespace_marker,
+};
+use crate::vocab::sentence_piece_vocab::read_protobuf_file;
+use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto_SentencePiece_Type;
 use crate::{Mask, Offset, OffsetSize, Token, TokenRef};
 use hashbrown::HashMap as BrownHashMap;
 use itertools::Itertools;
-use protobuf::Message;
-use std::fs::File;
-use std::io::Read;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Node<'a> {
@@ -54,6 +54,49 @@ impl TrieNode {
     }
 }
 
+/// # Piece type
+/// Type of a SentencePiece piece, mirroring the type field of the SentencePiece protobuf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceType {
+    /// Normal piece
+    Normal,
+    /// Unknown piece
+    Unknown,
+    /// Control piece (e.g. BOS or EOS markers)
+    Control,
+    /// User defined piece, always segmented as a single token
+    UserDefined,
+    /// Byte fallback piece (e.g. `<0x0A>`)
+    Byte,
+    /// Unused piece
+    Unused,
+}
+
+impl From<ModelProto_SentencePiece_Type> for PieceType {
+    fn from(piece_type: ModelProto_SentencePiece_Type) -> Self {
+        match piece_type {
+            ModelProto_SentencePiece_Type::NORMAL => PieceType::Normal,
+            ModelProto_SentencePiece_Type::UNKNOWN => PieceType::Unknown,
+            ModelProto_SentencePiece_Type::CONTROL => PieceType::Control,
+            ModelProto_SentencePiece_Type::USER_DEFINED => PieceType::UserDefined,
+            ModelProto_SentencePiece_Type::BYTE => PieceType::Byte,
+            ModelProto_SentencePiece_Type::UNUSED => PieceType::Unused,
+        }
+    }
+}
+
+/// # Piece information
+/// Metadata stored in the SentencePiece protobuf for a piece
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieceInfo {
+    /// Piece string
+    pub piece: String,
+    /// Unigram log-probability of the piece
+    pub score: f32,
+    /// Type of the piece
+    pub kind: PieceType,
+}
+
 /// # SentencePiece Model
 /// Model for SentencePiece tokenizer. Contains the following special values. This model performs
 /// the SentencePiece unigram decomposition. As such, it contains a `Trie` data structure for efficient
@@ -64,6 +107,51 @@ impl TrieNode {
 pub struct SentencePieceModel {
     /// Trie data structure containing the vocabulary elements and their unigram log-probabilities
     pub root: TrieNode,
+    pieces: Vec<PieceInfo>,
+}
+
+/// Meta symbol used by SentencePiece to mark whitespace (U+2581)
+pub(crate) const META_SYMBOL: char = '\u{2581}';
+
+/// Checks the whitespace marker used by a set of SentencePiece pieces. Returns an error if the
+/// pieces mix the meta symbol with literal spaces, or if they do not contain the meta symbol but
+/// rely on a different convention (a literal space or the GPT2 byte-level space `Ġ`). Pieces
+/// without any whitespace marker are accepted.
+pub(crate) fn check_meta_symbol<'a>(
+    pieces: impl Iterator<Item = &'a str>,
+) -> Result<(), TokenizerError> {
+    let mut meta_symbol_count = 0usize;
+    let mut space_count = 0usize;
+    let mut byte_level_space_count = 0usize;
+    for piece in pieces {
+        if piece.contains(META_SYMBOL) {
+            meta_symbol_count += 1;
+        }
+        if piece.starts_with(' ') {
+            space_count += 1;
+        }
+        if piece.starts_with('\u{0120}') {
+            byte_level_space_count += 1;
+        }
+    }
+    if meta_symbol_count == 0 && (space_count > 0 || byte_level_space_count > 0) {
+        let (marker, count) = if space_count >= byte_level_space_count {
+            (' ', space_count)
+        } else {
+            ('\u{0120}', byte_level_space_count)
+        };
+        return Err(TokenizerError::VocabularyParsingError(format!(
+            "SentencePiece pieces do not contain the meta symbol {:?}, {} pieces start with {:?} instead",
+            META_SYMBOL, count, marker
+        )));
+    }
+    if meta_symbol_count > 0 && space_count > 0 {
+        return Err(TokenizerError::VocabularyParsingError(format!(
+            "SentencePiece pieces mix the meta symbol {:?} ({} pieces) with literal spaces ({} pieces)",
+            META_SYMBOL, meta_symbol_count, space_count
+        )));
+    }
+    Ok(())
 }
 
 impl SentencePieceModel {
@@ -77,29 +165,53 @@ impl SentencePieceModel {
     /// let sentence_piece_model = SentencePieceModel::from_file(path);
     /// ```
     pub fn from_file(path: &str) -> Result<SentencePieceModel, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+        let proto = read_protobuf_file(path)?;
+        if proto.get_normalizer_spec().get_escape_whitespaces() {
+            check_meta_symbol(proto.get_pieces().iter().map(|piece| piece.get_piece()))?;
+        }
         let root = TrieNode::new("".to_string());
-        let mut vocab = SentencePieceModel { root };
+        let pieces = proto
+            .get_pieces()
+            .iter()
+            .map(|piece| PieceInfo {
+                piece: piece.get_piece().to_owned(),
+                score: piece.get_score(),
+                kind: piece.get_field_type().into(),
+            })
+            .collect();
+        let mut vocab = SentencePieceModel { root, pieces };
         for (idx, piece) in proto.get_pieces().iter().enumerate() {
             vocab.insert(piece.get_piece(), piece.get_score(), idx as i64);
         }
         Ok(vocab)
     }
 
+    /// Returns the metadata of a piece (string, score and type) as stored in the SentencePiece
+    /// protobuf. The id is the position of the piece in the protobuf, which is the index reported
+    /// by the lattice nodes of the model. Note that some vocabularies (e.g. XLM-RoBERTa) shift the
+    /// protobuf ids to insert additional special tokens.
+    ///
+    /// # Parameters
+    /// - id (`i64`): position of the piece in the protobuf
+    ///
+    /// # Returns
+    /// - `Option<PieceInfo>` piece metadata, `None` if the id is out of range
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rust_tokenizers::vocab::SentencePieceModel;
+    /// let path = "path/to/spiece.model";
+    /// let sentence_piece_model = SentencePieceModel::from_file(path).unwrap();
+    ///
+    /// let piece_info = sentence_piece_model.piece_info(42);
+    /// ```
+    pub fn piece_info(&self, id: i64) -> Option<PieceInfo> {
+        if id < 0 {
+            return None;
+        }
+        self.pieces.get(id as usize).cloned()
+    }
+
     fn insert(&mut self, word: &str, score: f32, index: i64) {
         let char_count = word.chars().count();
         let mut node = &mut self.root;
@@ -317,6 +429,69 @@ impl SentencePieceModel {
         output
     }
 
+    /// Tokenize a single word into the sub-tokens of its most likely segmentation. The whitespace
+    /// marker (`▁`) is prepended to the word if missing, so that it is segmented as a word
+    /// following a whitespace. No normalization is applied to the word.
+    ///
+    /// # Arguments
+    /// - word (`TokenRef`): word to tokenize
+    ///
+    /// # Returns
+    /// - `Vec<Token>` sequence of most likely sub-tokens
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rust_tokenizers::vocab::SentencePieceModel;
+    /// use rust_tokenizers::TokenRef;
+    /// let path = "path/to/spiece.model";
+    /// let sentence_piece_model = SentencePieceModel::from_file(path).unwrap();
+    ///
+    /// let word = TokenRef::new("hello", &[0, 1, 2, 3, 4]);
+    /// let sub_tokens = sentence_piece_model.tokenize_word(word);
+    /// ```
+    pub fn tokenize_word(&self, word: TokenRef) -> Vec<Token> {
+        let mut word = word.to_owned();
+        prepend_whitespace_marker(&mut word);
+        let output = self.decode_forward_token_ref(word.as_ref());
+        let decoded = self.decode_backward(&output);
+        self.parse_nodes_to_tokens(decoded)
+    }
+
+    /// Count the sub-tokens of the most likely segmentation of a token, without building them.
+    /// Consecutive unknown pieces are counted as a single sub-token, as in
+    /// `parse_nodes_to_tokens`.
+    ///
+    /// # Arguments
+    /// - token (`TokenRef`): token to decompose in sub-tokens
+    ///
+    /// # Returns
+    /// - `usize` number of sub-tokens of the most likely segmentation
+    ///
+    /// # Example
+    /// ```no_run
+    /// use rust_tokenizers::vocab::SentencePieceModel;
+    /// use rust_tokenizers::TokenRef;
+    /// let path = "path/to/spiece.model";
+    /// let sentence_piece_model = SentencePieceModel::from_file(path).unwrap();
+    ///
+    /// let token = TokenRef::new("\u{2581}hello", &[0, 1, 2, 3, 4, 5]);
+    /// let num_sub_tokens = sentence_piece_model.count_pieces(token);
+    /// ```
+    pub fn count_pieces(&self, token: TokenRef) -> usize {
+        let nodes = self.decode_forward_token_ref(token);
+        let mut count = 0;
+        let mut is_next_unknown = false;
+        let mut next_node = nodes.last().and_then(Option::as_ref);
+        while let Some(node) = next_node {
+            if !(is_next_unknown && node.index == 0) {
+                count += 1;
+            }
+            is_next_unknown = node.index == 0;
+            next_node = nodes[node.start].as_ref();
+        }
+        count
+    }
+
     /// Populates the `mask` field for a sequence of sub-tokens generated by a SentencePiece model.
     /// These masks are not generated as part of the standard unigram decomposition and must be added
     /// afterwards. Mutates the tokens in-place.
@@ -373,3 +548,166 @@ impl SentencePieceModel {
         }
     }
 }
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    #[test]
+    fn test_piece_info() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for (piece, score, piece_type) in [
+            ("<unk>", 0.0, ModelProto_SentencePiece_Type::UNKNOWN),
+            ("<s>", 0.0, ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", -2.5, ModelProto_SentencePiece_Type::NORMAL),
+            ("<0x0A>", 0.0, ModelProto_SentencePiece_Type::BYTE),
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(*score);
+            sentence_piece.set_field_type(*piece_type);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+
+        //        When
+        let model = SentencePieceModel::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(
+            model.piece_info(2),
+            Some(PieceInfo {
+                piece: "\u{2581}hello".to_owned(),
+                score: -2.5,
+                kind: PieceType::Normal,
+            })
+        );
+        assert_eq!(model.piece_info(0).unwrap().kind, PieceType::Unknown);
+        assert_eq!(model.piece_info(1).unwrap().kind, PieceType::Control);
+        assert_eq!(model.piece_info(3).unwrap().kind, PieceType::Byte);
+        assert_eq!(model.piece_info(4), None);
+        assert_eq!(model.piece_info(-1), None);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize_word() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for (piece, score, piece_type) in [
+            ("<unk>", 0.0, ModelProto_SentencePiece_Type::UNKNOWN),
+            ("\u{2581}hello", -2.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("\u{2581}he", -3.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("llo", -3.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("s", -1.0, ModelProto_SentencePiece_Type::NORMAL),
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(*score);
+            sentence_piece.set_field_type(*piece_type);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+        let model = SentencePieceModel::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When
+        let tokens = model.tokenize_word(TokenRef::new("hellos", &[0, 1, 2, 3, 4, 5]));
+
+        //        Then
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.text.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["\u{2581}hello", "s"]
+        );
+        assert_eq!(tokens[0].reference_offsets, vec![0, 0, 1, 2, 3, 4]);
+        assert_eq!(tokens[1].reference_offsets, vec![5]);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_pieces() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for (piece, score, piece_type) in [
+            ("<unk>", 0.0, ModelProto_SentencePiece_Type::UNKNOWN),
+            ("\u{2581}hello", -2.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("\u{2581}he", -3.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("llo", -3.0, ModelProto_SentencePiece_Type::NORMAL),
+            ("s", -1.0, ModelProto_SentencePiece_Type::NORMAL),
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_score(*score);
+            sentence_piece.set_field_type(*piece_type);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        let path = model_file.into_temp_path();
+        let model = SentencePieceModel::from_file(path.to_path_buf().to_str().unwrap())?;
+        let test_texts = [
+            "\u{2581}hellos",
+            "\u{2581}hexyzs",
+            "xyz\u{2581}hello",
+            "s",
+            "",
+        ];
+
+        //        When & Then
+        for text in test_texts.iter() {
+            let reference_offsets = (0..text.chars().count() as u32).collect::<Vec<u32>>();
+            let token = TokenRef::new(text, &reference_offsets);
+            let nodes = model.decode_forward_token_ref(token);
+            let tokens = model.parse_nodes_to_tokens(model.decode_backward(&nodes));
+            assert_eq!(model.count_pieces(token), tokens.len());
+        }
+        assert_eq!(
+            model.count_pieces(TokenRef::new("\u{2581}hexyzs", &[0, 1, 2, 3, 4, 5, 6])),
+            3
+        );
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_meta_symbol() {
+        //        Given
+        let sentence_piece_pieces = ["<unk>", "\u{2581}hello", "\u{2581}", "world"];
+        let no_marker_pieces = ["<unk>", "a", "b"];
+        let mixed_pieces = ["<unk>", "\u{2581}hello", " world"];
+        let mixed_with_byte_level_pieces = ["<unk>", "\u{2581}hello", " world", "\u{0120}!"];
+        let gpt2_pieces = ["<unk>", "\u{0120}hello", "\u{0120}world", "!"];
+
+        //        When & Then
+        assert!(check_meta_symbol(sentence_piece_pieces.iter().copied()).is_ok());
+        assert!(check_meta_symbol(no_marker_pieces.iter().copied()).is_ok());
+        assert!(check_meta_symbol(mixed_pieces.iter().copied()).is_err());
+        assert!(check_meta_symbol(mixed_with_byte_level_pieces.iter().copied()).is_err());
+        assert!(check_meta_symbol(gpt2_pieces.iter().copied()).is_err());
+    }
+}
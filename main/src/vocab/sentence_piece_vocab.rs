@@ -11,14 +11,184 @@
 // limitations under the License.
 
 use crate::error::TokenizerError;
-use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
+use crate::vocab::base_vocab::{
+    parse_byte_piece, special_token_config, swap_key_values, vocab_partial_eq,
+};
+use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+    ModelProto, ModelProto_SentencePiece_Type,
+};
+use crate::vocab::{SentencePieceNormalizer, SpecialTokenConfig, Vocab, VocabMut};
 use protobuf::Message;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 
+/// Maximum size in bytes of the SentencePiece protobuf files read by the vocabularies (256 MiB, well
+/// above the size of published models). Larger inputs are rejected before being parsed.
+pub const MAX_SENTENCEPIECE_MODEL_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Read and parse a SentencePiece protobuf file
+pub(crate) fn read_protobuf_file(path: &str) -> Result<ModelProto, TokenizerError> {
+    let f = File::open(path).map_err(|e| {
+        TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+    })?;
+    read_protobuf(f)
+}
+
+/// Read and parse a SentencePiece protobuf from a reader
+pub(crate) fn read_protobuf<R: Read>(reader: R) -> Result<ModelProto, TokenizerError> {
+    read_protobuf_with_size_limit(reader, MAX_SENTENCEPIECE_MODEL_SIZE)
+}
+
+/// Read and parse a SentencePiece protobuf from a reader, reading at most `max_size` bytes. Inputs
+/// exceeding the limit, truncated or invalid protobufs return a `VocabularyParsingError`.
+pub(crate) fn read_protobuf_with_size_limit<R: Read>(
+    reader: R,
+    max_size: u64,
+) -> Result<ModelProto, TokenizerError> {
+    let mut contents = Vec::new();
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut contents)
+        .map_err(|e| TokenizerError::VocabularyParsingError(e.to_string()))?;
+    check_protobuf_size(contents.len(), max_size)?;
+    ModelProto::parse_from_bytes(contents.as_slice())
+        .map_err(|e| TokenizerError::VocabularyParsingError(e.to_string()))
+}
+
+fn check_protobuf_size(size: usize, max_size: u64) -> Result<(), TokenizerError> {
+    if size as u64 > max_size {
+        return Err(TokenizerError::VocabularyParsingError(format!(
+            "SentencePiece model exceeds the maximum size of {} bytes",
+            max_size
+        )));
+    }
+    Ok(())
+}
+
+/// Memory-map a SentencePiece protobuf file and parse it from the mapped bytes, without copying the
+/// file contents to the heap. The parsed protobuf owns its strings and the map is dropped on return.
+///
+/// # Safety
+/// The file must not be modified or truncated (by this or another process) while it is mapped,
+/// see `AlbertVocab::from_mmap_file`.
+#[cfg(feature = "mmap")]
+pub(crate) unsafe fn read_protobuf_mmap_file(path: &str) -> Result<ModelProto, TokenizerError> {
+    let f = File::open(path).map_err(|e| {
+        TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
+    })?;
+    // Safety: the caller guarantees that the file is not modified while it is mapped
+    let mmap = memmap2::Mmap::map(&f)
+        .map_err(|e| TokenizerError::IOError(format!("{} could not be mapped: {}", path, e)))?;
+    check_protobuf_size(mmap.len(), MAX_SENTENCEPIECE_MODEL_SIZE)?;
+    ModelProto::parse_from_bytes(&mmap)
+        .map_err(|e| TokenizerError::VocabularyParsingError(e.to_string()))
+}
+
+/// Token mappings read from a SentencePiece protobuf by `read_sentencepiece`
+pub(crate) struct SentencePieceValues {
+    pub(crate) values: HashMap<String, i64>,
+    pub(crate) unknown_value: String,
+    pub(crate) special_values: HashMap<String, i64>,
+    pub(crate) byte_values: HashMap<u8, i64>,
+    pub(crate) scores: Vec<f32>,
+    pub(crate) normalizer: SentencePieceNormalizer,
+}
+
+/// Read a SentencePiece protobuf from a reader and register the special tokens of the configuration
+/// provided. Pieces of the `CONTROL` and `USER_DEFINED` types are registered as special values, and
+/// pieces of the `BYTE` type are registered as byte-fallback entries. The unknown value is read from
+/// the piece of the `UNKNOWN` type, defaulting to the unknown value of the configuration. The
+/// normalizer of the model is read from its `NormalizerSpec`.
+pub(crate) fn read_sentencepiece<V: Vocab, R: Read>(
+    reader: R,
+    special_token_config: &SpecialTokenConfig,
+) -> Result<SentencePieceValues, TokenizerError> {
+    read_sentencepiece_proto::<V>(&read_protobuf(reader)?, special_token_config)
+}
+
+/// Read the token mappings of a parsed SentencePiece protobuf, see `read_sentencepiece`
+pub(crate) fn read_sentencepiece_proto<V: Vocab>(
+    proto: &ModelProto,
+    special_token_config: &SpecialTokenConfig,
+) -> Result<SentencePieceValues, TokenizerError> {
+    read_sentencepiece_proto_with_values::<V>(proto, piece_values(proto), special_token_config)
+}
+
+/// Returns the mapping of the pieces of a SentencePiece protobuf to their index in the model
+pub(crate) fn piece_values(proto: &ModelProto) -> HashMap<String, i64> {
+    proto
+        .get_pieces()
+        .iter()
+        .enumerate()
+        .map(|(idx, piece)| (piece.get_piece().to_owned(), idx as i64))
+        .collect()
+}
+
+/// Read the token mappings of a parsed SentencePiece protobuf with a custom layout of ids, for
+/// vocabularies not using the index of the pieces as their id (e.g. models converted from fairseq
+/// or appending sentinel tokens). `values` maps the tokens of the vocabulary to their id: the
+/// piece types, scores and byte-fallback entries are registered for the id of each piece in
+/// `values`, and pieces absent from `values` are ignored. See `read_sentencepiece`.
+pub(crate) fn read_sentencepiece_proto_with_values<V: Vocab>(
+    proto: &ModelProto,
+    values: HashMap<String, i64>,
+    special_token_config: &SpecialTokenConfig,
+) -> Result<SentencePieceValues, TokenizerError> {
+    if proto.get_pieces().is_empty() {
+        return Err(TokenizerError::VocabularyParsingError(
+            "SentencePiece model contains no pieces".to_string(),
+        ));
+    }
+
+    let mut special_values = HashMap::new();
+    let mut byte_values = HashMap::new();
+    let mut scores = vec![0.0; values.values().max().map_or(0, |id| *id as usize + 1)];
+    for (idx, piece) in proto.get_pieces().iter().enumerate() {
+        let id = match values.get(piece.get_piece()) {
+            Some(id) => *id,
+            None => continue,
+        };
+        scores[id as usize] = piece.get_score();
+        match piece.get_field_type() {
+            ModelProto_SentencePiece_Type::CONTROL
+            | ModelProto_SentencePiece_Type::USER_DEFINED => {
+                special_values.insert(piece.get_piece().to_owned(), id);
+            }
+            ModelProto_SentencePiece_Type::BYTE => {
+                let byte = parse_byte_piece(piece.get_piece()).ok_or_else(|| {
+                    TokenizerError::VocabularyParsingError(format!(
+                        "Invalid byte piece {} at index {}",
+                        piece.get_piece(),
+                        idx
+                    ))
+                })?;
+                byte_values.insert(byte, id);
+            }
+            _ => {}
+        }
+    }
+    let unknown_value = proto
+        .get_pieces()
+        .iter()
+        .find(|piece| piece.get_field_type() == ModelProto_SentencePiece_Type::UNKNOWN)
+        .map_or(special_token_config.unknown_value.as_ref(), |piece| {
+            piece.get_piece()
+        })
+        .to_owned();
+    special_token_config.register::<V>(&unknown_value, &values, &mut special_values)?;
+    let normalizer = SentencePieceNormalizer::from_proto(proto.get_normalizer_spec())?;
+
+    Ok(SentencePieceValues {
+        values,
+        unknown_value,
+        special_values,
+        byte_values,
+        scores,
+        normalizer,
+    })
+}
+
 /// # SentencePieceVocab
 /// Vocabulary for SentencePiece model/tokenizer. Contains the following special values:
 /// - BOS token
@@ -81,14 +251,22 @@ impl SentencePieceVocab {
     }
 }
 
+vocab_partial_eq!(SentencePieceVocab);
+
 impl Vocab for SentencePieceVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
-    }
+    special_token_config!(
+        SentencePieceVocab,
+        bos_value,
+        eos_value,
+        cls_value,
+        sep_value,
+        mask_value,
+        pad_value
+    );
 
     fn values(&self) -> &HashMap<String, i64> {
         &self.values
@@ -106,28 +284,8 @@ impl Vocab for SentencePieceVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<SentencePieceVocab, TokenizerError> {
-        let mut f = match File::open(path) {
-            Ok(file) => file,
-            Err(_) => {
-                return Err(TokenizerError::FileNotFound(format!(
-                    "{} vocabulary file not found",
-                    path
-                )));
-            }
-        };
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+    fn from_reader<R: Read>(reader: R) -> Result<SentencePieceVocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
 
         let mut values = HashMap::new();
         for (idx, piece) in proto.get_pieces().iter().enumerate() {
@@ -166,4 +324,26 @@ impl Vocab for SentencePieceVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for SentencePieceVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
 }
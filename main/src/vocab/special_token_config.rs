@@ -0,0 +1,252 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::{
+    AlbertVocab, DeBERTaV2Vocab, FNetVocab, MBart50Vocab, PegasusVocab, ReformerVocab, T5Vocab,
+    Vocab, XLMRobertaVocab, XLNetVocab,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// # SpecialTokenConfig
+/// Set of special token strings to register when loading a vocabulary. This allows vocabularies
+/// sharing the same file format (e.g. SentencePiece models) but a different special tokens layout to
+/// share the same loading logic. Special tokens set to `None` are not registered. Token strings may
+/// be borrowed (`"<s>".into()`) or owned (`String::from("<s>").into()`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpecialTokenConfig {
+    /// The string to use for unknown (out of vocabulary) tokens
+    pub unknown_value: Cow<'static, str>,
+    /// Beginning of sequence token
+    pub bos_value: Option<Cow<'static, str>>,
+    /// End of sequence token
+    pub eos_value: Option<Cow<'static, str>>,
+    /// Class token
+    pub cls_value: Option<Cow<'static, str>>,
+    /// Separation token
+    pub sep_value: Option<Cow<'static, str>>,
+    /// Mask token
+    pub mask_value: Option<Cow<'static, str>>,
+    /// Padding token
+    pub pad_value: Option<Cow<'static, str>>,
+}
+
+impl SpecialTokenConfig {
+    /// Returns a special tokens configuration defining the unknown value only
+    ///
+    /// # Parameters
+    /// - unknown_value (`impl Into<Cow<'static, str>>`): the string to use for unknown (out of vocabulary) tokens
+    pub fn new(unknown_value: impl Into<Cow<'static, str>>) -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: unknown_value.into(),
+            bos_value: None,
+            eos_value: None,
+            cls_value: None,
+            sep_value: None,
+            mask_value: None,
+            pad_value: None,
+        }
+    }
+
+    /// Returns the special tokens configuration for ALBERT
+    pub fn albert() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: AlbertVocab::unknown_value().into(),
+            bos_value: Some(AlbertVocab::bos_value().into()),
+            eos_value: Some(AlbertVocab::eos_value().into()),
+            cls_value: Some(AlbertVocab::cls_value().into()),
+            sep_value: Some(AlbertVocab::sep_value().into()),
+            mask_value: Some(AlbertVocab::mask_value().into()),
+            pad_value: Some(AlbertVocab::pad_value().into()),
+        }
+    }
+
+    /// Returns the special tokens configuration for FNet, which does not define BOS and EOS tokens
+    pub fn fnet() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: FNetVocab::unknown_value().into(),
+            bos_value: None,
+            eos_value: None,
+            cls_value: Some(FNetVocab::cls_value().into()),
+            sep_value: Some(FNetVocab::sep_value().into()),
+            mask_value: Some(FNetVocab::mask_value().into()),
+            pad_value: Some(FNetVocab::pad_value().into()),
+        }
+    }
+
+    /// Returns the special tokens configuration for XLMRoBERTa
+    pub fn xlm_roberta() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: XLMRobertaVocab::unknown_value().into(),
+            bos_value: Some(XLMRobertaVocab::bos_value().into()),
+            eos_value: Some(XLMRobertaVocab::eos_value().into()),
+            cls_value: Some(XLMRobertaVocab::cls_value().into()),
+            sep_value: Some(XLMRobertaVocab::sep_value().into()),
+            mask_value: Some(XLMRobertaVocab::mask_value().into()),
+            pad_value: Some(XLMRobertaVocab::pad_value().into()),
+        }
+    }
+
+    /// Returns the special tokens configuration for T5, which only defines EOS and PAD tokens (the
+    /// sentinel tokens are registered by `T5Vocab`)
+    pub fn t5() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            eos_value: Some(T5Vocab::eos_value().into()),
+            pad_value: Some(T5Vocab::pad_value().into()),
+            ..SpecialTokenConfig::new(T5Vocab::unknown_value())
+        }
+    }
+
+    /// Returns the special tokens configuration for DeBERTa (v2)
+    pub fn deberta_v2() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: DeBERTaV2Vocab::unknown_value().into(),
+            bos_value: Some(DeBERTaV2Vocab::bos_value().into()),
+            eos_value: Some(DeBERTaV2Vocab::eos_value().into()),
+            cls_value: Some(DeBERTaV2Vocab::cls_value().into()),
+            sep_value: Some(DeBERTaV2Vocab::sep_value().into()),
+            mask_value: Some(DeBERTaV2Vocab::mask_value().into()),
+            pad_value: Some(DeBERTaV2Vocab::pad_value().into()),
+        }
+    }
+
+    /// Returns the special tokens configuration for MBart50, which does not define a BOS token (the
+    /// language codes are registered by `MBart50Vocab`)
+    pub fn mbart50() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            eos_value: Some(MBart50Vocab::eos_value().into()),
+            cls_value: Some(MBart50Vocab::cls_value().into()),
+            sep_value: Some(MBart50Vocab::sep_value().into()),
+            mask_value: Some(MBart50Vocab::mask_value().into()),
+            pad_value: Some(MBart50Vocab::pad_value().into()),
+            ..SpecialTokenConfig::new(MBart50Vocab::unknown_value())
+        }
+    }
+
+    /// Returns the special tokens configuration for XLNet (the end of paragraph and end of document
+    /// tokens are registered by `XLNetVocab`)
+    pub fn xlnet() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            unknown_value: XLNetVocab::unknown_value().into(),
+            bos_value: Some(XLNetVocab::bos_value().into()),
+            eos_value: Some(XLNetVocab::eos_value().into()),
+            cls_value: Some(XLNetVocab::cls_value().into()),
+            sep_value: Some(XLNetVocab::sep_value().into()),
+            mask_value: Some(XLNetVocab::mask_value().into()),
+            pad_value: Some(XLNetVocab::pad_value().into()),
+        }
+    }
+
+    /// Returns the special tokens configuration for Pegasus (the sentence mask and reserved tokens
+    /// are registered by `PegasusVocab`)
+    pub fn pegasus() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            eos_value: Some(PegasusVocab::eos_value().into()),
+            mask_value: Some(PegasusVocab::mask_value().into()),
+            pad_value: Some(PegasusVocab::pad_value().into()),
+            ..SpecialTokenConfig::new(PegasusVocab::unknown_value())
+        }
+    }
+
+    /// Returns the special tokens configuration for Reformer, which only defines an EOS token
+    pub fn reformer() -> SpecialTokenConfig {
+        SpecialTokenConfig {
+            eos_value: Some(ReformerVocab::eos_value().into()),
+            ..SpecialTokenConfig::new(ReformerVocab::unknown_value())
+        }
+    }
+
+    /// Returns the special token strings of the configuration, starting with the unknown value
+    pub fn special_values(&self) -> Vec<&str> {
+        let mut special_values = vec![self.unknown_value.as_ref()];
+        special_values.extend(
+            [
+                &self.bos_value,
+                &self.eos_value,
+                &self.cls_value,
+                &self.sep_value,
+                &self.mask_value,
+                &self.pad_value,
+            ]
+            .iter()
+            .filter_map(|value| value.as_deref()),
+        );
+        special_values
+    }
+
+    /// Register the special tokens of the configuration as special values. The unknown value of the
+    /// configuration is replaced by the unknown value provided, which may be read from the model file.
+    ///
+    /// # Parameters
+    /// - unknown_value (`&str`): unknown value to register
+    /// - values (`&HashMap<String, i64>`): mapping from tokens to ids. This should contain the special tokens to register
+    /// - special_values (`&mut HashMap<String, i64>`): mapping from special tokens to ids
+    ///
+    /// # Returns
+    /// - `Result<(), TokenizerError>`: error if one of the special tokens is missing from the values
+    pub fn register<V: Vocab>(
+        &self,
+        unknown_value: &str,
+        values: &HashMap<String, i64>,
+        special_values: &mut HashMap<String, i64>,
+    ) -> Result<(), TokenizerError> {
+        V::_register_as_special_value(unknown_value, values, special_values)?;
+        for special_value in self.special_values().into_iter().skip(1) {
+            V::_register_as_special_value(special_value, values, special_values)?;
+        }
+        Ok(())
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_special_values() {
+        //        Given
+        let albert_config = SpecialTokenConfig::albert();
+        let fnet_config = SpecialTokenConfig::fnet();
+        let t5_config = SpecialTokenConfig::t5();
+
+        //        When & Then
+        assert_eq!(
+            albert_config.special_values(),
+            vec!["<unk>", "[CLS]", "[SEP]", "[CLS]", "[SEP]", "[MASK]", "<pad>"]
+        );
+        assert_eq!(
+            fnet_config.special_values(),
+            vec!["<unk>", "[CLS]", "[SEP]", "[MASK]", "<pad>"]
+        );
+        assert_eq!(t5_config.special_values(), vec!["<unk>", "</s>", "<pad>"]);
+    }
+
+    #[test]
+    fn test_owned_special_values() {
+        //        Given
+        let custom_config = SpecialTokenConfig {
+            cls_value: Some(String::from("<s>").into()),
+            sep_value: Some(String::from("</s>").into()),
+            ..SpecialTokenConfig::albert()
+        };
+
+        //        When & Then
+        assert_eq!(
+            custom_config.special_values(),
+            vec!["<unk>", "[CLS]", "[SEP]", "<s>", "</s>", "[MASK]", "<pad>"]
+        );
+    }
+}
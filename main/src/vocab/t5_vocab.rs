@@ -12,19 +12,27 @@
 
 use crate::error::TokenizerError;
 use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{
+    piece_values, read_protobuf, read_sentencepiece_proto_with_values, SentencePieceValues,
+};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::Read;
 
+/// Number of sentinel tokens (`<extra_id_0>` to `<extra_id_99>`) appended to the T5 vocabulary
+const SENTINEL_COUNT: usize = 100;
+
 /// # T5 Vocab
 /// Vocabulary for T5 tokenizer. Contains the following special values:
 /// - PAD token
 /// - EOS token
+/// - sentinel tokens (`<extra_id_0>` to `<extra_id_99>`)
 ///
-/// Expects a SentencePiece protobuf file when created from file.
+/// Expects a SentencePiece protobuf file when created from file. The 100 sentinel tokens used for
+/// span corruption are appended after the SentencePiece pieces in descending order: `<extra_id_n>`
+/// is assigned the id `vocab_size - 1 - n`, with `vocab_size` the number of pieces plus 100.
+/// Sentinel tokens already present in the SentencePiece model keep their id.
 #[derive(Debug, Clone)]
 pub struct T5Vocab {
     /// A mapping of tokens as string to indices (i.e. the encoder base)
@@ -55,15 +63,51 @@ impl T5Vocab {
     pub fn pad_value() -> &'static str {
         "<pad>"
     }
+
+    /// Returns the id of a sentinel token (`<extra_id_n>`), used to mask spans of the input
+    ///
+    /// # Parameters
+    /// - n (`usize`): index of the sentinel token
+    ///
+    /// # Returns
+    /// - `Option<i64>`: id of the sentinel token, `None` if the sentinel is not in the vocabulary
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{T5Vocab, Vocab};
+    /// let path = "path/to/file";
+    ///
+    /// let vocab = T5Vocab::from_file(path).unwrap();
+    /// let first_sentinel_id = vocab.get_sentinel_id(0);
+    /// ```
+    pub fn get_sentinel_id(&self, n: usize) -> Option<i64> {
+        self.special_values.get(&sentinel_token(n)).copied()
+    }
 }
 
+/// Formats the sentinel token of index n (`<extra_id_n>`)
+fn sentinel_token(n: usize) -> String {
+    format!("<extra_id_{}>", n)
+}
+
+vocab_partial_eq!(T5Vocab);
+
 impl Vocab for T5Vocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            T5Vocab::unknown_value(),
+            T5Vocab::eos_value(),
+            T5Vocab::pad_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::t5()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -82,37 +126,32 @@ impl Vocab for T5Vocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<T5Vocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
+    fn from_reader<R: Read>(reader: R) -> Result<T5Vocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
+        let mut values = piece_values(&proto);
+        let vocab_size = (proto.get_pieces().len() + SENTINEL_COUNT) as i64;
+
+        let sentinel_values: Vec<String> = (0..SENTINEL_COUNT).map(sentinel_token).collect();
+        for (n, sentinel_value) in sentinel_values.iter().enumerate() {
+            if !values.contains_key(sentinel_value) {
+                values.insert(sentinel_value.clone(), vocab_size - 1 - n as i64);
             }
-        };
-        let mut values = HashMap::new();
-        for (idx, piece) in proto.get_pieces().iter().enumerate() {
-            values.insert(piece.get_piece().to_owned(), idx as i64);
         }
 
-        let mut special_values = HashMap::new();
-        let unknown_value = T5Vocab::unknown_value();
-        T5Vocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let eos_value = T5Vocab::eos_value();
-        T5Vocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
-
-        let pad_value = T5Vocab::pad_value();
-        T5Vocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
+        let SentencePieceValues {
+            values,
+            mut special_values,
+            ..
+        } = read_sentencepiece_proto_with_values::<T5Vocab>(
+            &proto,
+            values,
+            &SpecialTokenConfig::t5(),
+        )?;
+        for sentinel_value in sentinel_values.iter() {
+            T5Vocab::_register_as_special_value(sentinel_value, &values, &mut special_values)?;
+        }
 
+        let unknown_value = T5Vocab::unknown_value();
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
 
@@ -137,4 +176,87 @@ impl Vocab for T5Vocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for T5Vocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece,
+    };
+    use protobuf::Message;
+
+    fn generate_test_proto(pieces: &[&str]) -> anyhow::Result<Vec<u8>> {
+        let mut proto = ModelProto::new();
+        for piece in pieces {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            proto.mut_pieces().push(sentence_piece);
+        }
+        Ok(proto.write_to_bytes()?)
+    }
+
+    #[test]
+    fn test_sentinel_ids() -> anyhow::Result<()> {
+        //        Given
+        let proto = generate_test_proto(&["<pad>", "</s>", "<unk>", "\u{2581}hello"])?;
+
+        //        When
+        let t5_vocab = T5Vocab::from_reader(proto.as_slice())?;
+
+        //        Then
+        assert_eq!(t5_vocab.len(), 104);
+        assert_eq!(t5_vocab.get_sentinel_id(0), Some(103));
+        assert_eq!(t5_vocab.get_sentinel_id(1), Some(102));
+        assert_eq!(t5_vocab.get_sentinel_id(99), Some(4));
+        assert_eq!(t5_vocab.get_sentinel_id(100), None);
+        assert_eq!(t5_vocab.token_to_id("<extra_id_0>"), 103);
+        assert_eq!(t5_vocab.id_to_token(&4), "<extra_id_99>");
+        assert_eq!(t5_vocab.token_to_id("\u{2581}hello"), 3);
+        assert_eq!(t5_vocab.special_values.len(), 103);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sentinel_ids_in_model() -> anyhow::Result<()> {
+        //        Given
+        let proto = generate_test_proto(&["<pad>", "</s>", "<unk>", "<extra_id_0>"])?;
+
+        //        When
+        let t5_vocab = T5Vocab::from_reader(proto.as_slice())?;
+
+        //        Then
+        assert_eq!(t5_vocab.get_sentinel_id(0), Some(3));
+        assert_eq!(t5_vocab.get_sentinel_id(1), Some(102));
+        assert_eq!(t5_vocab.get_sentinel_id(99), Some(4));
+        assert_eq!(t5_vocab.len(), 103);
+        Ok(())
+    }
 }
@@ -0,0 +1,319 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::{BertVocab, Vocab, VocabMut};
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+
+/// # VocabBuilder
+/// Builder loading a vocabulary from file and applying additional options after loading.
+/// Options are set with chainable setters, and the vocabulary is read by `build`.
+#[derive(Debug, Clone)]
+pub struct VocabBuilder<V: VocabMut> {
+    special_tokens: Vec<String>,
+    _vocab: PhantomData<V>,
+}
+
+impl<V: VocabMut> Default for VocabBuilder<V> {
+    fn default() -> Self {
+        VocabBuilder {
+            special_tokens: vec![],
+            _vocab: PhantomData,
+        }
+    }
+}
+
+impl<V: VocabMut> VocabBuilder<V> {
+    /// Create a new `VocabBuilder` with default options
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{BertVocab, VocabBuilder};
+    /// let vocab = VocabBuilder::<BertVocab>::new()
+    ///     .add_special_tokens(&["[E1]", "[E2]"])
+    ///     .build("path/to/vocab/file")
+    ///     .unwrap();
+    /// ```
+    pub fn new() -> VocabBuilder<V> {
+        VocabBuilder::default()
+    }
+
+    /// Register additional tokens as special values once the vocabulary is loaded. The tokens
+    /// must be present in the vocabulary file.
+    ///
+    /// # Parameters
+    /// - special_tokens (`&[&str]`): tokens to register as special values
+    pub fn add_special_tokens(mut self, special_tokens: &[&str]) -> VocabBuilder<V> {
+        self.special_tokens
+            .extend(special_tokens.iter().map(|token| token.to_string()));
+        self
+    }
+
+    /// Read the vocabulary from file and apply the builder options
+    ///
+    /// # Parameters
+    /// - path (`&str`): path to the vocabulary file
+    ///
+    /// # Returns
+    /// - `Result<V, TokenizerError>`: vocabulary read, error if an additional special token is missing from the vocabulary
+    pub fn build(&self, path: &str) -> Result<V, TokenizerError> {
+        let mut vocab = V::from_file(path)?;
+        for token in self.special_tokens.iter() {
+            let token_id = *vocab.values().get(token).ok_or_else(|| {
+                TokenizerError::TokenNotFound(format!(
+                    "The special value {} could not be found in the vocabulary",
+                    token
+                ))
+            })?;
+            vocab.special_values_mut().insert(token.clone(), token_id);
+            vocab.special_indices_mut().insert(token_id, token.clone());
+        }
+        Ok(vocab)
+    }
+}
+
+/// # CorpusVocabBuilder
+/// Builder creating a word-level `BertVocab` from the tokens of a corpus, without an external
+/// training step. The BERT special tokens (`[PAD]`, `[UNK]`, `[CLS]`, `[SEP]` and `[MASK]`) and the
+/// additional special tokens are reserved at the front of the vocabulary, followed by the tokens of
+/// the corpus by decreasing frequency (ties are ordered alphabetically).
+#[derive(Debug, Clone, Default)]
+pub struct CorpusVocabBuilder {
+    max_size: Option<usize>,
+    min_frequency: usize,
+    special_tokens: Vec<String>,
+}
+
+impl CorpusVocabBuilder {
+    /// Create a new `CorpusVocabBuilder` without size limit or frequency threshold
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rust_tokenizers::vocab::{CorpusVocabBuilder, Vocab};
+    /// let corpus = "the cat sat on the mat";
+    ///
+    /// let (vocab, dropped_tokens) = CorpusVocabBuilder::new()
+    ///     .max_size(7)
+    ///     .build_from_tokens(corpus.split_whitespace())
+    ///     .unwrap();
+    /// assert_eq!(vocab.token_to_id("the"), 5);
+    /// assert_eq!(dropped_tokens.len(), 3);
+    /// ```
+    pub fn new() -> CorpusVocabBuilder {
+        CorpusVocabBuilder::default()
+    }
+
+    /// Set the maximum size of the vocabulary, including the special tokens
+    ///
+    /// # Parameters
+    /// - max_size (`usize`): maximum number of tokens in the vocabulary
+    pub fn max_size(mut self, max_size: usize) -> CorpusVocabBuilder {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set the minimum number of occurrences of a token in the corpus for it to be kept
+    ///
+    /// # Parameters
+    /// - min_frequency (`usize`): minimum count of the tokens kept
+    pub fn min_frequency(mut self, min_frequency: usize) -> CorpusVocabBuilder {
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    /// Reserve additional special tokens at the front of the vocabulary, after the BERT special
+    /// tokens. These are registered as special values.
+    ///
+    /// # Parameters
+    /// - special_tokens (`&[&str]`): tokens to register as special values
+    pub fn add_special_tokens(mut self, special_tokens: &[&str]) -> CorpusVocabBuilder {
+        self.special_tokens
+            .extend(special_tokens.iter().map(|token| token.to_string()));
+        self
+    }
+
+    /// Build the vocabulary from the tokens of a corpus
+    ///
+    /// # Parameters
+    /// - tokens (`IntoIterator<Item = AsRef<str>>`): tokens of the corpus
+    ///
+    /// # Returns
+    /// - `Result<(BertVocab, HashMap<String, usize>), TokenizerError>`: vocabulary and counts of the tokens dropped by the size limit or frequency threshold, error if the maximum size is smaller than the number of special tokens
+    pub fn build_from_tokens<I, S>(
+        &self,
+        tokens: I,
+    ) -> Result<(BertVocab, HashMap<String, usize>), TokenizerError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for token in tokens {
+            *counts.entry(token.as_ref().to_owned()).or_insert(0) += 1;
+        }
+        self.build_from_counts(counts)
+    }
+
+    /// Build the vocabulary from the token counts of a corpus. Counts of repeated tokens are summed.
+    ///
+    /// # Parameters
+    /// - counts (`IntoIterator<Item = (String, usize)>`): tokens of the corpus and their number of occurrences
+    ///
+    /// # Returns
+    /// - `Result<(BertVocab, HashMap<String, usize>), TokenizerError>`: vocabulary and counts of the tokens dropped by the size limit or frequency threshold, error if the maximum size is smaller than the number of special tokens
+    pub fn build_from_counts<I>(
+        &self,
+        counts: I,
+    ) -> Result<(BertVocab, HashMap<String, usize>), TokenizerError>
+    where
+        I: IntoIterator<Item = (String, usize)>,
+    {
+        let mut reserved_tokens = vec![
+            BertVocab::pad_value().to_string(),
+            BertVocab::unknown_value().to_string(),
+            BertVocab::cls_value().to_string(),
+            BertVocab::sep_value().to_string(),
+            BertVocab::mask_value().to_string(),
+        ];
+        for token in self.special_tokens.iter() {
+            if !reserved_tokens.contains(token) {
+                reserved_tokens.push(token.clone());
+            }
+        }
+        let max_size = self.max_size.unwrap_or(usize::MAX);
+        if max_size < reserved_tokens.len() {
+            return Err(TokenizerError::ValueError(format!(
+                "The maximum vocabulary size {} is smaller than the number of special tokens {}",
+                max_size,
+                reserved_tokens.len()
+            )));
+        }
+
+        let reserved_set: HashSet<String> = reserved_tokens.iter().cloned().collect();
+        let mut summed_counts: HashMap<String, usize> = HashMap::new();
+        for (token, count) in counts {
+            if !reserved_set.contains(&token) {
+                *summed_counts.entry(token).or_insert(0) += count;
+            }
+        }
+        let mut sorted_counts = summed_counts.into_iter().collect::<Vec<(String, usize)>>();
+        sorted_counts.sort_unstable_by(|(token_a, count_a), (token_b, count_b)| {
+            count_b.cmp(count_a).then_with(|| token_a.cmp(token_b))
+        });
+
+        let mut values: HashMap<String, i64> = reserved_tokens
+            .iter()
+            .enumerate()
+            .map(|(id, token)| (token.clone(), id as i64))
+            .collect();
+        let mut dropped_tokens = HashMap::new();
+        for (token, count) in sorted_counts {
+            if count >= self.min_frequency && values.len() < max_size {
+                let id = values.len() as i64;
+                values.insert(token, id);
+            } else {
+                dropped_tokens.insert(token, count);
+            }
+        }
+
+        let mut vocab = BertVocab::from_values(values)?;
+        for token in self.special_tokens.iter() {
+            let token_id = vocab.values[token];
+            vocab.special_values.insert(token.clone(), token_id);
+            vocab.special_indices.insert(token_id, token.clone());
+        }
+        Ok((vocab, dropped_tokens))
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::BaseVocab;
+    use std::io::Write;
+
+    #[test]
+    fn test_build_with_special_tokens() -> anyhow::Result<()> {
+        //        Given
+        let mut vocab_file = tempfile::NamedTempFile::new()?;
+        write!(vocab_file, "hello \n world \n [UNK] \n [E1]")?;
+        let path = vocab_file.into_temp_path();
+        let path_str = path.to_path_buf().to_str().unwrap().to_owned();
+
+        //        When
+        let base_vocab = VocabBuilder::<BaseVocab>::new()
+            .add_special_tokens(&["[E1]"])
+            .build(&path_str)?;
+        let missing = VocabBuilder::<BaseVocab>::new()
+            .add_special_tokens(&["[E2]"])
+            .build(&path_str);
+
+        //        Then
+        assert_eq!(base_vocab.special_values.get("[E1]"), Some(&3));
+        assert_eq!(
+            base_vocab.special_indices.get(&3).map(String::as_str),
+            Some("[E1]")
+        );
+        assert!(matches!(missing, Err(TokenizerError::TokenNotFound(_))));
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_from_corpus() -> anyhow::Result<()> {
+        //        Given
+        let corpus = "the cat sat on the mat the cat [E1] ran";
+
+        //        When
+        let (vocab, dropped_tokens) = CorpusVocabBuilder::new()
+            .max_size(8)
+            .min_frequency(1)
+            .add_special_tokens(&["[E1]"])
+            .build_from_tokens(corpus.split_whitespace())?;
+        let (frequent_vocab, frequent_dropped_tokens) = CorpusVocabBuilder::new()
+            .min_frequency(2)
+            .build_from_counts(vec![
+                ("the".to_string(), 1),
+                ("cat".to_string(), 2),
+                ("the".to_string(), 2),
+                ("mat".to_string(), 1),
+            ])?;
+        let too_small = CorpusVocabBuilder::new()
+            .max_size(3)
+            .build_from_tokens(["the"]);
+
+        //        Then
+        assert_eq!(vocab.len(), 8);
+        assert_eq!(vocab.token_to_id("[PAD]"), 0);
+        assert_eq!(vocab.token_to_id("[MASK]"), 4);
+        assert_eq!(vocab.token_to_id("[E1]"), 5);
+        assert_eq!(vocab.token_to_id("the"), 6);
+        assert_eq!(vocab.token_to_id("cat"), 7);
+        assert_eq!(vocab.token_to_id("mat"), 1);
+        assert!(vocab.special_values.contains_key("[E1]"));
+        assert_eq!(dropped_tokens.len(), 4);
+        assert_eq!(dropped_tokens.get("sat"), Some(&1));
+        assert!(!dropped_tokens.contains_key("[E1]"));
+        assert_eq!(frequent_vocab.token_to_id("the"), 5);
+        assert_eq!(frequent_vocab.token_to_id("cat"), 6);
+        assert_eq!(frequent_dropped_tokens.get("mat"), Some(&1));
+        assert!(matches!(too_small, Err(TokenizerError::ValueError(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2019-2021 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::TokenizerError;
+use crate::vocab::Vocab;
+use std::collections::HashMap;
+
+/// # VocabSet
+/// Set of vocabularies keyed by language code, for multilingual settings relying on one vocabulary
+/// file per language. All vocabularies are loaded upfront so that switching language at runtime does
+/// not require any file access.
+///
+/// The `Vocab` trait is not object safe (it requires `Sized` and exposes associated functions), so
+/// the lookup returns the concrete vocabulary type `&T` rather than a `&dyn Vocab`. All
+/// vocabularies of a set therefore share the same type.
+#[derive(Debug, Clone)]
+pub struct VocabSet<T: Vocab> {
+    vocabs: HashMap<String, T>,
+}
+
+impl<T: Vocab> VocabSet<T> {
+    /// Create a new `VocabSet` by loading a vocabulary file for each language code.
+    ///
+    /// # Parameters
+    /// - files (`&[(&str, &str)]`): pairs of language code and path to the vocabulary file for the language
+    ///
+    /// # Returns
+    /// - `Result<VocabSet<T>, TokenizerError>`: error if one of the vocabulary files could not be loaded
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{MarianVocab, VocabSet};
+    /// let vocab_set: VocabSet<MarianVocab> = VocabSet::from_files(&[
+    ///     ("en", "path/to/en/vocab/file"),
+    ///     ("fr", "path/to/fr/vocab/file"),
+    /// ])
+    /// .unwrap();
+    /// ```
+    pub fn from_files(files: &[(&str, &str)]) -> Result<VocabSet<T>, TokenizerError> {
+        let mut vocabs = HashMap::with_capacity(files.len());
+        for (language_code, path) in files {
+            vocabs.insert(language_code.to_string(), T::from_file(path)?);
+        }
+        Ok(VocabSet { vocabs })
+    }
+
+    /// Create a new `VocabSet` from existing vocabularies keyed by language code.
+    ///
+    /// # Parameters
+    /// - vocabs (`HashMap<String, T>`): mapping from language codes to vocabularies
+    ///
+    /// # Returns
+    /// - `VocabSet<T>` containing the vocabularies provided
+    pub fn from_existing_vocabs(vocabs: HashMap<String, T>) -> VocabSet<T> {
+        VocabSet { vocabs }
+    }
+
+    /// Add a vocabulary to the set, returning the vocabulary previously registered for the
+    /// language code if any.
+    ///
+    /// # Parameters
+    /// - language_code (`&str`): language code for the vocabulary
+    /// - vocab (`T`): vocabulary to register
+    ///
+    /// # Returns
+    /// - `Option<T>` vocabulary previously registered for the language code
+    pub fn insert(&mut self, language_code: &str, vocab: T) -> Option<T> {
+        self.vocabs.insert(language_code.to_string(), vocab)
+    }
+
+    /// Returns the vocabulary for a language code.
+    ///
+    /// # Parameters
+    /// - code (`&str`): language code to look up
+    ///
+    /// # Returns
+    /// - `Option<&T>` vocabulary for the language, `None` if no vocabulary was loaded for the code
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_tokenizers::vocab::{MarianVocab, Vocab, VocabSet};
+    /// let vocab_set: VocabSet<MarianVocab> = VocabSet::from_files(&[
+    ///     ("en", "path/to/en/vocab/file"),
+    ///     ("fr", "path/to/fr/vocab/file"),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let hello_id = vocab_set.for_language("en").unwrap().token_to_id("hello");
+    /// ```
+    pub fn for_language(&self, code: &str) -> Option<&T> {
+        self.vocabs.get(code)
+    }
+
+    /// Returns the language codes available in the set, sorted alphabetically.
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages = self
+            .vocabs
+            .keys()
+            .map(|code| code.as_str())
+            .collect::<Vec<&str>>();
+        languages.sort_unstable();
+        languages
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::BaseVocab;
+    use std::io::Write;
+
+    #[test]
+    fn test_vocab_set_from_files() -> anyhow::Result<()> {
+        //        Given
+        let mut en_file = tempfile::NamedTempFile::new()?;
+        write!(en_file, "hello\n[UNK]\nworld\n")?;
+        let en_path = en_file.into_temp_path();
+        let mut fr_file = tempfile::NamedTempFile::new()?;
+        write!(fr_file, "[UNK]\nbonjour\nmonde\n")?;
+        let fr_path = fr_file.into_temp_path();
+
+        //        When
+        let vocab_set: VocabSet<BaseVocab> = VocabSet::from_files(&[
+            ("en", en_path.to_path_buf().to_str().unwrap()),
+            ("fr", fr_path.to_path_buf().to_str().unwrap()),
+        ])?;
+
+        //        Then
+        assert_eq!(vocab_set.languages(), vec!["en", "fr"]);
+        assert_eq!(
+            vocab_set.for_language("en").unwrap().token_to_id("hello"),
+            0
+        );
+        assert_eq!(
+            vocab_set.for_language("en").unwrap().token_to_id("bonjour"),
+            1
+        );
+        assert_eq!(
+            vocab_set.for_language("fr").unwrap().token_to_id("bonjour"),
+            1
+        );
+        assert_eq!(
+            vocab_set.for_language("fr").unwrap().token_to_id("hello"),
+            0
+        );
+        assert!(vocab_set.for_language("de").is_none());
+        drop(en_path);
+        drop(fr_path);
+        Ok(())
+    }
+}
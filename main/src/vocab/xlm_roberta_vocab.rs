@@ -13,11 +13,12 @@
 
 use crate::error::TokenizerError;
 use crate::vocab::base_vocab::swap_key_values;
-use crate::vocab::sentencepiece_proto::sentencepiece_model::ModelProto;
-use crate::vocab::Vocab;
-use protobuf::Message;
+use crate::vocab::base_vocab::vocab_partial_eq;
+use crate::vocab::sentence_piece_vocab::{
+    read_protobuf, read_sentencepiece_proto_with_values, SentencePieceValues,
+};
+use crate::vocab::{SpecialTokenConfig, Vocab, VocabMut};
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::Read;
 
 /// # XLMRoBERTa Vocab
@@ -29,7 +30,9 @@ use std::io::Read;
 /// - MASK token
 /// - CLS token
 ///
-/// Expects a SentencePiece protobuf file when created from file.
+/// Expects a SentencePiece protobuf file when created from file. The token ids follow the fairseq
+/// convention: `<s>`, `<pad>`, `</s>` and `<unk>` take ids 0 to 3, the SentencePiece pieces are
+/// offset by 1 relative to their index in the model file, and `<mask>` is appended last.
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub struct XLMRobertaVocab {
@@ -83,13 +86,26 @@ impl XLMRobertaVocab {
     }
 }
 
+vocab_partial_eq!(XLMRobertaVocab);
+
 impl Vocab for XLMRobertaVocab {
     fn unknown_value() -> &'static str {
         "<unk>"
     }
 
-    fn get_unknown_value(&self) -> &'static str {
-        "<unk>"
+    fn expected_special_values(&self) -> Vec<&'static str> {
+        vec![
+            XLMRobertaVocab::unknown_value(),
+            XLMRobertaVocab::bos_value(),
+            XLMRobertaVocab::eos_value(),
+            XLMRobertaVocab::cls_value(),
+            XLMRobertaVocab::mask_value(),
+            XLMRobertaVocab::pad_value(),
+        ]
+    }
+
+    fn special_token_config(&self) -> SpecialTokenConfig {
+        SpecialTokenConfig::xlm_roberta()
     }
 
     fn values(&self) -> &HashMap<String, i64> {
@@ -108,22 +124,8 @@ impl Vocab for XLMRobertaVocab {
         &self.special_indices
     }
 
-    fn from_file(path: &str) -> Result<XLMRobertaVocab, TokenizerError> {
-        let mut f = File::open(path).map_err(|e| {
-            TokenizerError::FileNotFound(format!("{} vocabulary file not found :{}", path, e))
-        })?;
-        let mut contents = Vec::new();
-        let proto = match f.read_to_end(&mut contents) {
-            Ok(_) => match ModelProto::parse_from_bytes(contents.as_slice()) {
-                Ok(proto_value) => proto_value,
-                Err(e) => {
-                    return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-                }
-            },
-            Err(e) => {
-                return Err(TokenizerError::VocabularyParsingError(e.to_string()));
-            }
-        };
+    fn from_reader<R: Read>(reader: R) -> Result<XLMRobertaVocab, TokenizerError> {
+        let proto = read_protobuf(reader)?;
         let mut values = HashMap::new();
         values.insert(XLMRobertaVocab::cls_value().to_owned(), values.len() as i64);
         values.insert(XLMRobertaVocab::pad_value().to_owned(), values.len() as i64);
@@ -140,25 +142,17 @@ impl Vocab for XLMRobertaVocab {
             values.len() as i64,
         );
 
-        let mut special_values = HashMap::new();
-        let unknown_value = XLMRobertaVocab::unknown_value();
-        XLMRobertaVocab::_register_as_special_value(unknown_value, &values, &mut special_values)?;
-
-        let bos_value = XLMRobertaVocab::bos_value();
-        XLMRobertaVocab::_register_as_special_value(bos_value, &values, &mut special_values)?;
-
-        let eos_value = XLMRobertaVocab::eos_value();
-        XLMRobertaVocab::_register_as_special_value(eos_value, &values, &mut special_values)?;
-
-        let cls_value = XLMRobertaVocab::cls_value();
-        XLMRobertaVocab::_register_as_special_value(cls_value, &values, &mut special_values)?;
-
-        let mask_value = XLMRobertaVocab::mask_value();
-        XLMRobertaVocab::_register_as_special_value(mask_value, &values, &mut special_values)?;
-
-        let pad_value = XLMRobertaVocab::pad_value();
-        XLMRobertaVocab::_register_as_special_value(pad_value, &values, &mut special_values)?;
+        let SentencePieceValues {
+            values,
+            special_values,
+            ..
+        } = read_sentencepiece_proto_with_values::<XLMRobertaVocab>(
+            &proto,
+            values,
+            &SpecialTokenConfig::xlm_roberta(),
+        )?;
 
+        let unknown_value = XLMRobertaVocab::unknown_value();
         let indices = swap_key_values(&values);
         let special_indices = swap_key_values(&special_values);
 
@@ -183,4 +177,159 @@ impl Vocab for XLMRobertaVocab {
     fn id_to_token(&self, id: &i64) -> String {
         self._id_to_token(id, &self.indices, &self.special_indices, self.unknown_value)
     }
+
+    fn is_word_start(&self, id: i64) -> bool {
+        self._is_word_start_with_prefix(id, '\u{2581}')
+    }
+}
+
+impl VocabMut for XLMRobertaVocab {
+    fn values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.values
+    }
+
+    fn indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.indices
+    }
+
+    fn special_values_mut(&mut self) -> &mut HashMap<String, i64> {
+        &mut self.special_values
+    }
+
+    fn special_indices_mut(&mut self) -> &mut HashMap<i64, String> {
+        &mut self.special_indices
+    }
+}
+
+//==============================
+// Unit tests
+//==============================
+#[cfg(test)]
+mod tests {
+    extern crate anyhow;
+
+    use super::*;
+    use crate::vocab::sentencepiece_proto::sentencepiece_model::{
+        ModelProto, ModelProto_SentencePiece, ModelProto_SentencePiece_Type,
+    };
+    use protobuf::Message;
+    use std::io::Write;
+
+    fn generate_test_proto_file(pieces: &[&str]) -> anyhow::Result<tempfile::TempPath> {
+        let mut proto = ModelProto::new();
+        for piece in pieces {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let mut model_file = tempfile::NamedTempFile::new()?;
+        model_file.write_all(&proto.write_to_bytes()?)?;
+        Ok(model_file.into_temp_path())
+    }
+
+    #[test]
+    fn test_create_object_from_file() -> anyhow::Result<()> {
+        //        Given
+        let path =
+            generate_test_proto_file(&["<unk>", "<s>", "</s>", "\u{2581}hello", "\u{2581}world"])?;
+        let target_values: HashMap<String, i64> = [
+            ("<s>".to_owned(), 0),
+            ("<pad>".to_owned(), 1),
+            ("</s>".to_owned(), 2),
+            ("<unk>".to_owned(), 3),
+            ("\u{2581}hello".to_owned(), 4),
+            ("\u{2581}world".to_owned(), 5),
+            ("<mask>".to_owned(), 6),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        //        When
+        let xlm_roberta_vocab = XLMRobertaVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        Then
+        assert_eq!(xlm_roberta_vocab.values, target_values);
+        assert_eq!(xlm_roberta_vocab.special_values.len(), 5);
+        drop(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_object_from_reader() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for piece in ["<unk>", "<s>", "</s>", "\u{2581}hello"].iter() {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let bytes = proto.write_to_bytes()?;
+
+        //        When
+        let xlm_roberta_vocab = XLMRobertaVocab::from_reader(bytes.as_slice())?;
+        let corrupted = XLMRobertaVocab::from_reader(&bytes[..bytes.len() - 1]);
+
+        //        Then
+        assert_eq!(xlm_roberta_vocab.token_to_id("\u{2581}hello"), 4);
+        assert_eq!(xlm_roberta_vocab.token_to_id("<mask>"), 5);
+        assert!(matches!(
+            corrupted,
+            Err(TokenizerError::VocabularyParsingError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_piece_types_with_fairseq_offset() -> anyhow::Result<()> {
+        //        Given
+        let mut proto = ModelProto::new();
+        for (piece, piece_type) in [
+            ("<unk>", ModelProto_SentencePiece_Type::UNKNOWN),
+            ("<s>", ModelProto_SentencePiece_Type::CONTROL),
+            ("</s>", ModelProto_SentencePiece_Type::CONTROL),
+            ("\u{2581}hello", ModelProto_SentencePiece_Type::NORMAL),
+            ("<user>", ModelProto_SentencePiece_Type::USER_DEFINED),
+        ]
+        .iter()
+        {
+            let mut sentence_piece = ModelProto_SentencePiece::new();
+            sentence_piece.set_piece(piece.to_string());
+            sentence_piece.set_field_type(*piece_type);
+            proto.mut_pieces().push(sentence_piece);
+        }
+        let bytes = proto.write_to_bytes()?;
+
+        //        When
+        let xlm_roberta_vocab = XLMRobertaVocab::from_reader(bytes.as_slice())?;
+
+        //        Then
+        assert_eq!(xlm_roberta_vocab.special_values.get("<user>"), Some(&5));
+        assert_eq!(xlm_roberta_vocab.special_values.get("<s>"), Some(&0));
+        assert_eq!(xlm_roberta_vocab.special_values.get("</s>"), Some(&2));
+        assert_eq!(xlm_roberta_vocab.token_to_id("<mask>"), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_decode_tokens() -> anyhow::Result<()> {
+        //        Given
+        let path =
+            generate_test_proto_file(&["<unk>", "<s>", "</s>", "\u{2581}hello", "\u{2581}world"])?;
+        let xlm_roberta_vocab = XLMRobertaVocab::from_file(path.to_path_buf().to_str().unwrap())?;
+
+        //        When & Then
+        assert_eq!(xlm_roberta_vocab.token_to_id("<s>"), 0);
+        assert_eq!(xlm_roberta_vocab.token_to_id("<pad>"), 1);
+        assert_eq!(xlm_roberta_vocab.token_to_id("</s>"), 2);
+        assert_eq!(xlm_roberta_vocab.token_to_id("<unk>"), 3);
+        assert_eq!(xlm_roberta_vocab.token_to_id("\u{2581}hello"), 4);
+        assert_eq!(xlm_roberta_vocab.token_to_id("\u{2581}world"), 5);
+        assert_eq!(xlm_roberta_vocab.token_to_id("<mask>"), 6);
+        assert_eq!(xlm_roberta_vocab.token_to_id("oov_value"), 3);
+        assert_eq!(xlm_roberta_vocab.id_to_token(&(5_i64)), "\u{2581}world");
+        assert_eq!(xlm_roberta_vocab.id_to_token(&(6_i64)), "<mask>");
+        drop(path);
+        Ok(())
+    }
 }